@@ -0,0 +1,72 @@
+//! `proptest` strategies generating the values each type can represent, so
+//! encodings can be tested against any of them.
+
+use bit_vec::BitVec;
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    collection::vec,
+    strategy::{BoxedStrategy, Just, Strategy},
+};
+
+use crate::types::{BitString, Integer, ObjectIdentifier, OctetString};
+
+impl Arbitrary for OctetString {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        vec(any::<u8>(), 0..64).prop_map(OctetString::from).boxed()
+    }
+}
+
+/// Any number of bits, not just whole octets, so strings shrink a bit at a
+/// time towards the empty string.
+impl Arbitrary for BitString {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        vec(any::<bool>(), 0..128)
+            .prop_map(|bits| BitString::from(bits.into_iter().collect::<BitVec>()))
+            .boxed()
+    }
+}
+
+/// Integers of up to 32 octets, which is past what any primitive holds.
+impl Arbitrary for Integer {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        vec(any::<u8>(), 1..32)
+            .prop_map(|bytes| Integer::from_bytes_be(&bytes))
+            .boxed()
+    }
+}
+
+/// Only identifiers with valid root arcs: the first arc is 0, 1, or 2, and
+/// the second is below 40 unless the first is 2 (X.660 A.2). As the first
+/// two arcs are encoded as one number, the second arc under 2 is kept small
+/// enough for that to fit in a `u32`.
+impl Arbitrary for ObjectIdentifier {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (0u32..3)
+            .prop_flat_map(|first| {
+                let second = match first {
+                    2 => 0..u32::MAX - 80,
+                    _ => 0..40,
+                };
+
+                (Just(first), second, vec(any::<u32>(), 0..8))
+            })
+            .prop_map(|(first, second, rest)| {
+                let mut arcs = vec![first, second];
+                arcs.extend(rest);
+                ObjectIdentifier::new(arcs).unwrap()
+            })
+            .boxed()
+    }
+}
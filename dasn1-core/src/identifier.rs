@@ -1,3 +1,10 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    marker::PhantomData,
+    rc::Rc,
+    sync::Arc,
+};
+
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
 pub enum Class {
     Universal = 0,
@@ -27,8 +34,10 @@ impl Class {
 }
 
 /// An abstract representation of the identifier octets used in BER, CER, and
-/// DER to identify .
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// DER to identify a type. Only the class and tag number make up a type's
+/// identity, whether a value uses the constructed or primitive encoding is
+/// tracked separately by each set of encoding rules.
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
 pub struct Identifier {
     pub class: Class,
     pub tag: u32,
@@ -37,6 +46,7 @@ pub struct Identifier {
 macro_rules! consts {
     ($($name:ident = $value:expr),+) => {
         $(
+            #[doc = concat!("The identifier of the universal tag ", stringify!($value), ".")]
             pub const $name: Identifier = Identifier::new(Class::Universal, $value);
         )+
     }
@@ -58,6 +68,7 @@ impl Identifier {
         EMBEDDED_PDV = 11,
         UTF8_STRING = 12,
         RELATIVE_OID = 13,
+        TIME = 14,
         SEQUENCE = 16,
         SET = 17,
         NUMERIC_STRING = 18,
@@ -72,7 +83,58 @@ impl Identifier {
         GENERAL_STRING = 27,
         UNIVERSAL_STRING = 28,
         CHARACTER_STRING = 29,
-        BMP_STRING = 30
+        BMP_STRING = 30,
+        DATE = 31,
+        TIME_OF_DAY = 32,
+        DATE_TIME = 33,
+        DURATION = 34,
+        OID_IRI = 35,
+        RELATIVE_OID_IRI = 36
+    }
+
+    /// The name of the type with the universal tag `tag` (X.680 8.6), or
+    /// `None` if the tag is reserved or not yet assigned.
+    pub fn from_universal(tag: u32) -> Option<&'static str> {
+        let name = match tag {
+            1 => "BOOLEAN",
+            2 => "INTEGER",
+            3 => "BIT STRING",
+            4 => "OCTET STRING",
+            5 => "NULL",
+            6 => "OBJECT IDENTIFIER",
+            7 => "ObjectDescriptor",
+            8 => "EXTERNAL",
+            9 => "REAL",
+            10 => "ENUMERATED",
+            11 => "EMBEDDED PDV",
+            12 => "UTF8String",
+            13 => "RELATIVE-OID",
+            14 => "TIME",
+            16 => "SEQUENCE",
+            17 => "SET",
+            18 => "NumericString",
+            19 => "PrintableString",
+            20 => "TeletexString",
+            21 => "VideotexString",
+            22 => "IA5String",
+            23 => "UTCTime",
+            24 => "GeneralizedTime",
+            25 => "GraphicString",
+            26 => "VisibleString",
+            27 => "GeneralString",
+            28 => "UniversalString",
+            29 => "CHARACTER STRING",
+            30 => "BMPString",
+            31 => "DATE",
+            32 => "TIME-OF-DAY",
+            33 => "DATE-TIME",
+            34 => "DURATION",
+            35 => "OID-IRI",
+            36 => "RELATIVE-OID-IRI",
+            _ => return None,
+        };
+
+        Some(name)
     }
 
     pub const fn new(class: Class, tag: u32) -> Self {
@@ -135,6 +197,50 @@ impl AsnType for () {
     }
 }
 
+/// Markers have no encoding, and take up no space in a SEQUENCE. The
+/// identifier is only there to satisfy the trait, like that of `()`.
+impl<T: ?Sized> AsnType for PhantomData<T> {
+    fn identifier(&self) -> Identifier {
+        Identifier::NULL
+    }
+}
+
+/// Maps are represented as a `SEQUENCE OF SEQUENCE { key, value }`. Entries
+/// are encoded in iteration order, so only `BTreeMap` produces canonical DER.
+impl<K, V> AsnType for BTreeMap<K, V> {
+    fn identifier(&self) -> Identifier {
+        Identifier::SEQUENCE
+    }
+}
+
+impl<K, V, S> AsnType for HashMap<K, V, S> {
+    fn identifier(&self) -> Identifier {
+        Identifier::SEQUENCE
+    }
+}
+
+impl<T> AsnType for Vec<T> {
+    fn identifier(&self) -> Identifier {
+        Identifier::SEQUENCE
+    }
+}
+
+impl<T> AsnType for VecDeque<T> {
+    fn identifier(&self) -> Identifier {
+        Identifier::SEQUENCE
+    }
+}
+
+/// Arrays are represented as a SEQUENCE with exactly `N` elements. serde
+/// only implements `Serialize` and `Deserialize` for arrays of up to 32
+/// elements, so longer ones are marked with
+/// `#[serde(with = "dasn1::types::array")]`.
+impl<T, const N: usize> AsnType for [T; N] {
+    fn identifier(&self) -> Identifier {
+        Identifier::SEQUENCE
+    }
+}
+
 impl<T: AsnType> AsnType for Option<T> {
     fn identifier(&self) -> Identifier {
         match self {
@@ -151,6 +257,26 @@ impl<T: AsnType> AsnType for Option<T> {
     }
 }
 
+/// Pointers are represented by the value they point to, so that types can
+/// contain themselves (e.g. `next: Option<Box<Node>>`).
+macro_rules! impl_pointers {
+    ($($pointer:ident)+) => {
+        $(
+            impl<T: AsnType + ?Sized> AsnType for $pointer<T> {
+                fn identifier(&self) -> Identifier {
+                    (**self).identifier()
+                }
+
+                fn tag_encoding(&self) -> TagEncoding {
+                    (**self).tag_encoding()
+                }
+            }
+        )+
+    }
+}
+
+impl_pointers!(Box Rc Arc);
+
 macro_rules! impl_integers {
     ($($num:ty)+) => {
         $(
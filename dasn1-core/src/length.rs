@@ -0,0 +1,89 @@
+//! The length octets of the BER family of encodings (X.690 8.1.3), shared by
+//! their encoders and decoders.
+
+use std::{
+    fmt,
+    io::{self, Write},
+    mem,
+};
+
+/// The length of a value's contents.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Length {
+    /// The contents are exactly this many bytes long.
+    Definite(usize),
+    /// The contents are terminated by end-of-contents octets.
+    Indefinite,
+}
+
+/// The reasons length octets can't be decoded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LengthError {
+    /// The input ended before the length octets did, needing this many more
+    /// bytes.
+    Incomplete(usize),
+    /// The initial octet was `0xFF`, which X.690 8.1.3.5 reserves.
+    Reserved,
+    /// The length is too large to fit in a `usize`.
+    Overflow,
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LengthError::Incomplete(needed) => {
+                write!(f, "Length octets need {} more bytes", needed)
+            }
+            LengthError::Reserved => write!(f, "Reserved initial length octet 0xFF"),
+            LengthError::Overflow => write!(f, "Length doesn't fit in a usize"),
+        }
+    }
+}
+
+impl std::error::Error for LengthError {}
+
+/// Writes `length` in the definite form, using the short form for lengths
+/// up to 127 and the fewest subsequent octets otherwise.
+pub fn encode_length(length: usize, output: &mut impl Write) -> io::Result<()> {
+    if length <= 127 {
+        return output.write_all(&[length as u8]);
+    }
+
+    let bytes = length.to_be_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count()..];
+
+    output.write_all(&[bytes.len() as u8 | 0x80])?;
+    output.write_all(bytes)
+}
+
+/// Reads the length octets at the start of `bytes`, returning the length and
+/// the input following them.
+pub fn decode_length(bytes: &[u8]) -> Result<(Length, &[u8]), LengthError> {
+    let (&initial, rest) = bytes.split_first().ok_or(LengthError::Incomplete(1))?;
+
+    match initial {
+        0x80 => Ok((Length::Indefinite, rest)),
+        0xFF => Err(LengthError::Reserved),
+        length if length < 0x80 => Ok((Length::Definite(length as usize), rest)),
+        length => {
+            let count = (length ^ 0x80) as usize;
+
+            if rest.len() < count {
+                return Err(LengthError::Incomplete(count - rest.len()));
+            }
+
+            let (octets, rest) = rest.split_at(count);
+            let octets = &octets[octets.iter().take_while(|&&byte| byte == 0).count()..];
+
+            if octets.len() > mem::size_of::<usize>() {
+                return Err(LengthError::Overflow);
+            }
+
+            let length = octets
+                .iter()
+                .fold(0, |length, &byte| (length << 8) | byte as usize);
+
+            Ok((Length::Definite(length), rest))
+        }
+    }
+}
@@ -3,4 +3,8 @@
 
 #![cfg_attr(test, deny(missing_docs))]
 pub mod identifier;
+pub mod length;
 pub mod types;
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
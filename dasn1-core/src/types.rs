@@ -1,15 +1,41 @@
+pub mod any;
+#[cfg(feature = "serde")]
+pub mod array;
+pub mod asn_string;
 pub mod bit_string;
+pub mod constrained;
+#[cfg(feature = "serde")]
+pub mod default;
 pub mod enumerated;
 pub mod integer;
+#[cfg(all(feature = "net", feature = "serde"))]
+pub mod net;
+pub mod null;
 pub mod object_identifier;
 pub mod octet_string;
 pub mod optional;
 pub mod prefix;
+pub mod raw_bytes;
+#[cfg(feature = "serde")]
+pub mod required;
+pub mod restricted_string;
+pub mod set_of;
+pub mod teletex_string;
+pub mod time;
 
+pub use self::any::{Any, AnyValue, InvalidAnyValue};
+pub use self::asn_string::AsnString;
 pub use self::bit_string::BitString;
-pub use self::integer::Integer;
+pub use self::constrained::{Constrained, ConstraintError};
+pub use self::integer::{Integer, TryFromIntegerError};
+pub use self::null::Null;
 pub use self::object_identifier::ObjectIdentifier;
 pub use self::octet_string::OctetString;
 pub use self::optional::Optional;
 pub use self::enumerated::{Enumerable, Enumerated};
-pub use self::prefix::{Implicit, Explicit};
+pub use self::prefix::{Implicit, Explicit, Tagged, Tagging};
+pub use self::raw_bytes::WithRawBytes;
+pub use self::restricted_string::{Ia5String, NumericString, PrintableString};
+pub use self::set_of::SetOf;
+pub use self::teletex_string::{T61String, TeletexString};
+pub use self::time::{GeneralizedTime, UtcTime};
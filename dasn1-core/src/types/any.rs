@@ -0,0 +1,177 @@
+//! Open type values, kept as their complete encoding.
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+use crate::identifier::{AsnType, Class, Identifier};
+
+/// A value of an open type, such as `ANY` or `ANY DEFINED BY`, stored as its
+/// complete encoding (identifier, length, and contents octets). The encoding
+/// is kept verbatim, so it can be written back out or decoded later once the
+/// type is known.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AnyValue(Vec<u8>);
+
+/// Shorthand for `AnyValue`, after the ASN.1 `ANY` type. Unlike
+/// `OctetString`, the identifier and length octets are kept along with the
+/// contents.
+pub type Any = AnyValue;
+
+impl AnyValue {
+    /// Instantiates a new `AnyValue` from an already encoded value, checking
+    /// that `encoded` is exactly one value with a definite length: its
+    /// identifier and length octets, followed by as many contents octets as
+    /// the length gives.
+    pub fn new(encoded: Vec<u8>) -> Result<Self, InvalidAnyValue> {
+        if encoded_len(&encoded) == Some(encoded.len()) {
+            Ok(Self(encoded))
+        } else {
+            Err(InvalidAnyValue)
+        }
+    }
+
+    /// Consumes `self` and returns the encoded value.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// The length of the value at the start of `octets`, going by its identifier
+/// and length octets, or `None` if those are cut short or the length is
+/// indefinite.
+fn encoded_len(octets: &[u8]) -> Option<usize> {
+    let mut position = 1;
+
+    // Tag numbers from 31 are in the following octets, seven bits at a time.
+    if *octets.first()? & 0x1f == 0x1f {
+        while *octets.get(position)? & 0x80 != 0 {
+            position += 1;
+        }
+
+        position += 1;
+    }
+
+    let initial_octet = *octets.get(position)?;
+    position += 1;
+
+    let contents_len = match initial_octet {
+        0..=0x7f => usize::from(initial_octet),
+        0x80 => return None,
+        _ => {
+            let count = usize::from(initial_octet & 0x7f);
+            let length_octets = octets.get(position..position.checked_add(count)?)?;
+            position += count;
+
+            length_octets.iter().try_fold(0usize, |len, &octet| {
+                len.checked_mul(0x100)?.checked_add(usize::from(octet))
+            })?
+        }
+    };
+
+    position.checked_add(contents_len)
+}
+
+/// The error from instantiating an `AnyValue` from octets that aren't exactly
+/// one encoded value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidAnyValue;
+
+impl fmt::Display for InvalidAnyValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("expected exactly one encoded value")
+    }
+}
+
+impl std::error::Error for InvalidAnyValue {}
+
+impl AsRef<[u8]> for AnyValue {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The identifier at the start of the encoding, or `EOC` if the encoding is
+/// empty.
+impl AsnType for AnyValue {
+    fn identifier(&self) -> Identifier {
+        let mut octets = self.0.iter();
+
+        let initial_octet = match octets.next() {
+            Some(&octet) => octet,
+            None => return Identifier::EOC,
+        };
+
+        let class = Class::from_u8(initial_octet >> 6);
+        let mut tag = u32::from(initial_octet & 0x1f);
+
+        // Tag numbers from 31 are in the following octets, seven bits at a time.
+        if tag == 0x1f {
+            tag = 0;
+
+            for &octet in octets {
+                tag = tag << 7 | u32::from(octet & 0x7f);
+
+                if octet & 0x80 == 0 {
+                    break;
+                }
+            }
+        }
+
+        Identifier::new(class, tag)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct RawBytes<'a>(&'a [u8]);
+
+#[cfg(feature = "serde")]
+impl Serialize for RawBytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for AnyValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct("ASN.1#Any", &RawBytes(&self.0))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for AnyValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct("ASN.1#Any", AnyValueVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct AnyValueVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for AnyValueVisitor {
+    type Value = AnyValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an encoded value")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(AnyValue(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(AnyValue(v))
+    }
+}
@@ -0,0 +1,64 @@
+//! Handles arrays of more than 32 elements, as serde only implements
+//! `Serialize` and `Deserialize` for arrays up to that length. The field is
+//! marked with `#[serde(with = "dasn1::types::array")]`, and is encoded the
+//! same as a shorter array, as a SEQUENCE of exactly `N` elements.
+
+use std::{convert::TryInto, fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, Deserialize, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, SerializeTuple, Serializer},
+};
+
+/// Serializes the array as a tuple of its elements, like serde does for
+/// shorter arrays.
+pub fn serialize<T, S, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let mut tuple = serializer.serialize_tuple(N)?;
+
+    for element in array {
+        tuple.serialize_element(element)?;
+    }
+
+    tuple.end()
+}
+
+/// Deserializes exactly `N` elements into an array.
+pub fn deserialize<'de, T, D, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_tuple(N, ArrayVisitor(PhantomData))
+}
+
+struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+where
+    T: Deserialize<'de>,
+{
+    type Value = [T; N];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of {} elements", N)
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<[T; N], S::Error> {
+        let mut elements = Vec::with_capacity(N);
+
+        while elements.len() < N {
+            match seq.next_element()? {
+                Some(element) => elements.push(element),
+                None => return Err(de::Error::invalid_length(elements.len(), &self)),
+            }
+        }
+
+        elements
+            .try_into()
+            .map_err(|elements: Vec<T>| de::Error::invalid_length(elements.len(), &self))
+    }
+}
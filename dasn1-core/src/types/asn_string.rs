@@ -0,0 +1,105 @@
+//! A string encoded as the narrowest restricted string type that can hold it.
+
+use std::{fmt, ops::Deref};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    identifier::{AsnType, Identifier},
+    types::{NumericString, PrintableString},
+};
+
+/// A string whose ASN.1 type is chosen from its contents when it's created:
+/// a `NumericString` if it's only digits and spaces, a `PrintableString` if
+/// every character is in that type's set, and a `UTF8String` otherwise.
+///
+/// Decoding accepts any of the three types, keeping the one that was used.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AsnString {
+    /// A string of only digits and spaces.
+    #[cfg_attr(feature = "serde", serde(rename = "ASN.1#[UNIVERSAL 18]"))]
+    Numeric(NumericString),
+    /// A string of latin letters, digits, and some punctuation.
+    #[cfg_attr(feature = "serde", serde(rename = "ASN.1#[UNIVERSAL 19]"))]
+    Printable(PrintableString),
+    /// Any other string.
+    #[cfg_attr(feature = "serde", serde(rename = "ASN.1#[UNIVERSAL 12]"))]
+    Utf8(String),
+}
+
+impl AsnString {
+    /// Wraps `string` in the narrowest type whose character set contains it.
+    pub fn new<I: Into<String>>(string: I) -> Self {
+        let string = string.into();
+
+        if let Ok(numeric) = NumericString::new(&*string) {
+            AsnString::Numeric(numeric)
+        } else if let Ok(printable) = PrintableString::new(&*string) {
+            AsnString::Printable(printable)
+        } else {
+            AsnString::Utf8(string)
+        }
+    }
+
+    /// The string's contents.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AsnString::Numeric(string) => string,
+            AsnString::Printable(string) => string,
+            AsnString::Utf8(string) => string,
+        }
+    }
+
+    /// Consumes `self` and returns the inner `String`.
+    pub fn into_string(self) -> String {
+        match self {
+            AsnString::Numeric(string) => string.into_string(),
+            AsnString::Printable(string) => string.into_string(),
+            AsnString::Utf8(string) => string,
+        }
+    }
+}
+
+impl AsnType for AsnString {
+    fn identifier(&self) -> Identifier {
+        match self {
+            AsnString::Numeric(_) => Identifier::NUMERIC_STRING,
+            AsnString::Printable(_) => Identifier::PRINTABLE_STRING,
+            AsnString::Utf8(_) => Identifier::UTF8_STRING,
+        }
+    }
+}
+
+impl From<String> for AsnString {
+    fn from(string: String) -> Self {
+        Self::new(string)
+    }
+}
+
+impl From<&str> for AsnString {
+    fn from(string: &str) -> Self {
+        Self::new(string)
+    }
+}
+
+impl AsRef<str> for AsnString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Deref for AsnString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for AsnString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
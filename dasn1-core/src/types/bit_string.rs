@@ -1,7 +1,10 @@
-use std::ops;
-use std::{error::Error, fmt};
+use std::{fmt, ops, str::FromStr};
+#[cfg(feature = "serde")]
+use std::error::Error;
 
 use bit_vec::BitVec;
+use failure::{bail, ensure};
+#[cfg(feature = "serde")]
 use serde::{
     self,
     de::{Deserializer, SeqAccess, Visitor},
@@ -205,6 +208,22 @@ impl BitString {
     pub fn from_bytes(input: &[u8]) -> Self {
         Self(BitVec::from_bytes(input))
     }
+
+    /// Iterates over each bit as a `bool`, starting from the first bit.
+    pub fn iter_bits(&self) -> impl Iterator<Item = bool> + '_ {
+        self.0.iter()
+    }
+
+    /// The number of bits that are set.
+    pub fn count_ones(&self) -> usize {
+        self.iter_bits().filter(|&bit| bit).count()
+    }
+
+    /// Appends the bits of `bytes`, most significant bit first, after the
+    /// last bit already in the string.
+    pub fn extend_from_bytes(&mut self, bytes: &[u8]) {
+        self.0.append(&mut BitVec::from_bytes(bytes));
+    }
 }
 
 impl ops::Deref for BitString {
@@ -227,8 +246,63 @@ impl From<BitVec> for BitString {
     }
 }
 
+/// Writes the bits in `bstring` notation, e.g. `'1101'B`.
+impl fmt::Display for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'")?;
+
+        for bit in self.iter_bits() {
+            write!(f, "{}", if bit { '1' } else { '0' })?;
+        }
+
+        write!(f, "'B")
+    }
+}
+
+/// Parses either `bstring` (`'1101'B`) or `hstring` (`'A98A'H`) notation,
+/// ignoring any whitespace between the quotes. Each hexadecimal digit is four
+/// bits, so an `hstring` is always a multiple of four bits long.
+impl FromStr for BitString {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, radix) = match s.strip_prefix('\'') {
+            Some(rest) if rest.ends_with("'B") => (&rest[..rest.len() - 2], 2),
+            Some(rest) if rest.ends_with("'H") => (&rest[..rest.len() - 2], 16),
+            _ => bail!("{:?} isn't in bstring ('...'B) or hstring ('...'H) notation.", s),
+        };
+
+        let mut bits = BitVec::new();
+
+        for digit in digits.chars().filter(|c| !c.is_whitespace()) {
+            ensure!(
+                radix == 2 || !digit.is_ascii_lowercase(),
+                "hstrings only use uppercase digits, found {:?}.",
+                digit
+            );
+
+            let value = match digit.to_digit(radix) {
+                Some(value) => value,
+                None => bail!("{:?} isn't a valid digit in {:?}.", digit, s),
+            };
+
+            if radix == 2 {
+                bits.push(value == 1);
+            } else {
+                for shift in (0..4).rev() {
+                    bits.push(value >> shift & 1 == 1);
+                }
+            }
+        }
+
+        Ok(Self(bits))
+    }
+}
+
+#[cfg(feature = "serde")]
 struct BitStringVisitor;
 
+#[cfg(feature = "serde")]
 impl<'de> Visitor<'de> for BitStringVisitor {
     type Value = BitVec;
 
@@ -254,6 +328,7 @@ impl<'de> Visitor<'de> for BitStringVisitor {
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for BitString {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -265,6 +340,7 @@ impl<'de> Deserialize<'de> for BitString {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for BitString {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -0,0 +1,131 @@
+//! Types whose values are constrained beyond what their encoding enforces.
+
+use std::fmt;
+
+/// A type with constraints on its values, such as a SEQUENCE with a
+/// component `INTEGER (0..255)`. Derived by `AsnType` for structs, checking
+/// their `#[asn(range = "0..=255")]` and `#[asn(size = "1..4")]` fields, and
+/// validating the fields whose types are `Constrained` in turn.
+///
+/// Fields whose type is one of the struct's generic parameters aren't
+/// validated by the derived impl, as whether the parameter is `Constrained`
+/// isn't known where the impl is generated. Such a field can be validated
+/// while decoding instead, by marking it with
+/// `#[serde(deserialize_with = "dasn1::types::constrained::deserialize")]`.
+pub trait Constrained {
+    /// Checks that the value satisfies all of its constraints.
+    fn validate(&self) -> Result<(), ConstraintError>;
+}
+
+/// A value didn't satisfy one of its type's constraints.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConstraintError {
+    field: &'static str,
+    constraint: &'static str,
+}
+
+impl ConstraintError {
+    /// Instantiates a new `ConstraintError` from the field that was out of
+    /// range, and a description of its constraint.
+    pub fn new(field: &'static str, constraint: &'static str) -> Self {
+        Self { field, constraint }
+    }
+
+    /// The field that was out of range.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` doesn't satisfy the constraint {}", self.field, self.constraint)
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+impl<T: Constrained> Constrained for Option<T> {
+    fn validate(&self) -> Result<(), ConstraintError> {
+        self.as_ref().map_or(Ok(()), T::validate)
+    }
+}
+
+impl<T: Constrained> Constrained for Vec<T> {
+    fn validate(&self) -> Result<(), ConstraintError> {
+        self.iter().try_for_each(T::validate)
+    }
+}
+
+impl<T: Constrained + ?Sized> Constrained for Box<T> {
+    fn validate(&self) -> Result<(), ConstraintError> {
+        (**self).validate()
+    }
+}
+
+/// Used by derived `Constrained` impls to validate the fields whose types are
+/// `Constrained` and skip the others, without the derive having to know
+/// which are which. Calling `(&Field(&value)).validate_field()` with both
+/// traits in scope picks `ValidateConstrained` when it applies, as it takes
+/// one fewer reference.
+#[doc(hidden)]
+pub mod field {
+    use super::{Constrained, ConstraintError};
+
+    pub struct Field<'a, T: ?Sized>(pub &'a T);
+
+    pub trait ValidateConstrained {
+        fn validate_field(&self) -> Result<(), ConstraintError>;
+    }
+
+    impl<T: Constrained + ?Sized> ValidateConstrained for Field<'_, T> {
+        fn validate_field(&self) -> Result<(), ConstraintError> {
+            self.0.validate()
+        }
+    }
+
+    pub trait ValidateUnconstrained {
+        fn validate_field(&self) -> Result<(), ConstraintError> {
+            Ok(())
+        }
+    }
+
+    impl<T: ?Sized> ValidateUnconstrained for &Field<'_, T> {}
+}
+
+/// Deserializes a value and checks it with `check`, for the functions the
+/// derive generates for fields with a `range` or `size` constraint.
+#[doc(hidden)]
+#[cfg(feature = "serde")]
+pub fn deserialize_checked<'de, T, D>(
+    deserializer: D,
+    check: impl FnOnce(&T) -> Result<(), ConstraintError>,
+) -> Result<T, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    let value = T::deserialize(deserializer)?;
+    check(&value).map_err(serde::de::Error::custom)?;
+
+    Ok(value)
+}
+
+#[doc(hidden)]
+#[cfg(feature = "serde")]
+pub use serde::Deserializer;
+
+/// Deserializes a value and checks its constraints, for fields holding a
+/// `Constrained` type marked with
+/// `#[serde(deserialize_with = "dasn1::types::constrained::deserialize")]`.
+#[cfg(feature = "serde")]
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: serde::Deserialize<'de> + Constrained,
+    D: serde::Deserializer<'de>,
+{
+    let value = T::deserialize(deserializer)?;
+    value.validate().map_err(serde::de::Error::custom)?;
+
+    Ok(value)
+}
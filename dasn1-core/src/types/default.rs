@@ -0,0 +1,60 @@
+//! Handles components with a DEFAULT value, which DER leaves out when they
+//! hold it (X.690 11.5). The field is marked with
+//! `#[serde(default, skip_serializing_if = "dasn1::types::default::is_default",
+//! deserialize_with = "dasn1::types::default::deserialize")]`, taking its
+//! DEFAULT value from the type's `Default` impl. Decoding DER then rejects
+//! the default value if it's encoded anyway, while BER, and other formats,
+//! accept it.
+
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+
+/// Whether `value` is the default, and so is left out of the encoding.
+pub fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Deserializes the value, failing if it's the default and the decoder
+/// requires it to have been left out.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de> + Default + PartialEq,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_newtype_struct("ASN.1#Default", DefaultVisitor(PhantomData))
+}
+
+/// Expects the value, followed by whether the default value is forbidden.
+/// Formats without an opinion on DEFAULT only provide the value, and an empty
+/// sequence means the component was left out.
+struct DefaultVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for DefaultVisitor<T>
+where
+    T: Deserialize<'de> + Default + PartialEq,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a component with a DEFAULT value")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<T, S::Error> {
+        let value = match seq.next_element()? {
+            Some(value) => value,
+            None => return Ok(T::default()),
+        };
+        let strict = seq.next_element()?.unwrap_or(false);
+
+        if strict && is_default(&value) {
+            Err(de::Error::custom("A DEFAULT component was encoded with its default value."))
+        } else {
+            Ok(value)
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use std::{fmt, marker::PhantomData};
+#[cfg(feature = "serde")]
+use std::{convert::TryFrom, fmt, marker::PhantomData};
 
-use serde::{Deserialize, Deserializer, Serialize, Serializer, de::{SeqAccess, Visitor}};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::{self, Visitor}};
 
+#[cfg(feature = "serde")]
 const NAME: &str = "ASN.1#Enumerated";
 
 /// A representation of the `ENUMERATED` ASN.1 data type. `Enumerated` should be
@@ -23,6 +26,12 @@ impl<E: Enumerable> Enumerated<E> {
     }
 }
 
+impl<E: Enumerable> From<E> for Enumerated<E> {
+    fn from(enumerable: E) -> Self {
+        Enumerated::new(enumerable)
+    }
+}
+
 impl<E: Enumerable> AsRef<E> for Enumerated<E> {
     fn as_ref(&self) -> &E {
         &self.0
@@ -35,21 +44,40 @@ impl<E: Enumerable> AsMut<E> for Enumerated<E> {
     }
 }
 
-/// A marker trait signifying that a type is an `enum` with no data in the
-/// variants. Implementing this trait on `enum`s with data or on `struct`s, and
-/// using the `Enumerated` struct will produce malformed encodings.
-pub trait Enumerable {}
+/// A trait signifying that a type is an `enum` with no data in the variants,
+/// mapping each variant to the value it's encoded as. This can be derived
+/// with `#[derive(Enumerable)]`, which uses the variants' discriminants.
+pub trait Enumerable: Sized {
+    /// Whether the `ENUMERATED` type has an extension marker (`...`), which
+    /// is set by `#[asn(extensible)]` when derived.
+    const EXTENSIBLE: bool = false;
+
+    /// Returns the value `self` is encoded as.
+    fn to_enumerated(&self) -> i64;
+
+    /// Returns the variant encoded as `value`, or `None` if there isn't one.
+    fn from_enumerated(value: i64) -> Option<Self>;
+
+    /// Whether `self` is one of the additions after the extension marker,
+    /// rather than in the root of the enumeration. These are the variants
+    /// marked `#[asn(extension)]` when derived.
+    fn is_extension(&self) -> bool {
+        false
+    }
+}
 
-impl<E: Enumerable + Serialize> Serialize for Enumerated<E> {
+#[cfg(feature = "serde")]
+impl<E: Enumerable> Serialize for Enumerated<E> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_newtype_struct(NAME, &self.0)
+        serializer.serialize_newtype_struct(NAME, &self.0.to_enumerated())
     }
 }
 
-impl<'de, E: Enumerable + Deserialize<'de>> Deserialize<'de> for Enumerated<E> {
+#[cfg(feature = "serde")]
+impl<'de, E: Enumerable> Deserialize<'de> for Enumerated<E> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
@@ -61,17 +89,20 @@ impl<'de, E: Enumerable + Deserialize<'de>> Deserialize<'de> for Enumerated<E> {
     }
 }
 
+#[cfg(feature = "serde")]
 struct EnumeratedVisitor<T> {
     phantom: PhantomData<T>,
 }
 
+#[cfg(feature = "serde")]
 impl<T> EnumeratedVisitor<T> {
     fn new() -> Self {
         Self { phantom: PhantomData }
     }
 }
 
-impl<'de, T: Deserialize<'de>> Visitor<'de> for EnumeratedVisitor<T> {
+#[cfg(feature = "serde")]
+impl<'de, T: Enumerable> Visitor<'de> for EnumeratedVisitor<T> {
     type Value = T;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -79,6 +110,19 @@ impl<'de, T: Deserialize<'de>> Visitor<'de> for EnumeratedVisitor<T> {
     }
 
     fn visit_newtype_struct<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
-        T::deserialize(de)
+        de.deserialize_i64(self)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        T::from_enumerated(value).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Signed(value), &self)
+        })
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        match i64::try_from(value) {
+            Ok(value) => self.visit_i64(value),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Unsigned(value), &self)),
+        }
     }
 }
@@ -1,6 +1,8 @@
-use std::fmt;
+use std::{convert::TryFrom, fmt};
 
 use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+#[cfg(feature = "serde")]
 use serde::{
     Deserialize,
     Deserializer,
@@ -14,6 +16,7 @@ use serde::{
 /// documentation for using `Integer` in Rust.
 ///
 /// [`BigInt`]: https://docs.rs/num-bigint/0.2.2/num_bigint/struct.BigInt.html
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct Integer(BigInt);
 
 impl Integer {
@@ -24,10 +27,38 @@ impl Integer {
     pub fn into_inner(self) -> BigInt {
         self.0
     }
+
+    /// Reads a big-endian two's complement integer, as found in the contents
+    /// of a BER encoded `INTEGER`.
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        Self(BigInt::from_signed_bytes_be(bytes))
+    }
+
+    /// Writes the integer in big-endian two's complement using the fewest
+    /// octets possible, so a positive integer whose highest bit would be set
+    /// gets a leading zero octet.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.0.to_signed_bytes_be()
+    }
+}
+
+/// The error from converting an `Integer` to a primitive integer type its
+/// value doesn't fit in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TryFromIntegerError(&'static str);
+
+impl fmt::Display for TryFromIntegerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "INTEGER out of range for {}", self.0)
+    }
 }
 
+impl std::error::Error for TryFromIntegerError {}
+
+#[cfg(feature = "serde")]
 struct IntegerVisitor;
 
+#[cfg(feature = "serde")]
 impl Serialize for Integer {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -37,6 +68,7 @@ impl Serialize for Integer {
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Integer {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -48,19 +80,41 @@ impl<'de> Deserialize<'de> for Integer {
 }
 
 macro_rules! integers {
-    ($($int:ty)+) => {
+    ($($int:ident => $to_int:ident)+) => {
         $(
             impl From<$int> for Integer {
                 fn from(value: $int) -> Self {
                     Integer(BigInt::from(value))
                 }
             }
+
+            impl TryFrom<Integer> for $int {
+                type Error = TryFromIntegerError;
+
+                fn try_from(value: Integer) -> Result<Self, Self::Error> {
+                    value.0.$to_int().ok_or(TryFromIntegerError(stringify!($int)))
+                }
+            }
         )+
     }
 }
 
-integers!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+integers! {
+    i8 => to_i8
+    i16 => to_i16
+    i32 => to_i32
+    i64 => to_i64
+    i128 => to_i128
+    isize => to_isize
+    u8 => to_u8
+    u16 => to_u16
+    u32 => to_u32
+    u64 => to_u64
+    u128 => to_u128
+    usize => to_usize
+}
 
+#[cfg(feature = "serde")]
 impl<'de> Visitor<'de> for IntegerVisitor {
     type Value = BigInt;
 
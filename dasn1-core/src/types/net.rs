@@ -0,0 +1,95 @@
+//! `serde` functions encoding IP addresses as the fixed size OCTET STRINGs
+//! network specifications carry them in, `OCTET STRING (SIZE(4))` for IPv4
+//! and `OCTET STRING (SIZE(16))` for IPv6. Fields are marked with
+//! `#[serde(with = "dasn1_core::types::net::ipv4")]`, or `ipv6`, or `ip` for
+//! an `IpAddr` that may be either.
+
+use serde::{de, Deserialize, Deserializer};
+
+use super::OctetString;
+
+/// Deserializes an OCTET STRING, checking it holds one of the `lengths`,
+/// which `expected` describes.
+fn deserialize_octets<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    lengths: &[usize],
+    expected: &'static str,
+) -> Result<OctetString, D::Error> {
+    let octets = OctetString::deserialize(deserializer)?;
+
+    if lengths.contains(&octets.len()) {
+        Ok(octets)
+    } else {
+        Err(de::Error::invalid_length(octets.len(), &expected))
+    }
+}
+
+/// An `Ipv4Addr` as an `OCTET STRING (SIZE(4))`.
+pub mod ipv4 {
+    use std::{convert::TryFrom, net::Ipv4Addr};
+
+    use serde::{Deserializer, Serialize, Serializer};
+
+    use super::OctetString;
+
+    /// Serializes the four octets of `address`.
+    pub fn serialize<S: Serializer>(address: &Ipv4Addr, serializer: S) -> Result<S::Ok, S::Error> {
+        OctetString::from(&address.octets()[..]).serialize(serializer)
+    }
+
+    /// Deserializes four octets as an `Ipv4Addr`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv4Addr, D::Error> {
+        let octets = super::deserialize_octets(deserializer, &[4], "4 octets")?;
+        Ok(<[u8; 4]>::try_from(octets.as_slice()).unwrap().into())
+    }
+}
+
+/// An `Ipv6Addr` as an `OCTET STRING (SIZE(16))`.
+pub mod ipv6 {
+    use std::{convert::TryFrom, net::Ipv6Addr};
+
+    use serde::{Deserializer, Serialize, Serializer};
+
+    use super::OctetString;
+
+    /// Serializes the sixteen octets of `address`.
+    pub fn serialize<S: Serializer>(address: &Ipv6Addr, serializer: S) -> Result<S::Ok, S::Error> {
+        OctetString::from(&address.octets()[..]).serialize(serializer)
+    }
+
+    /// Deserializes sixteen octets as an `Ipv6Addr`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv6Addr, D::Error> {
+        let octets = super::deserialize_octets(deserializer, &[16], "16 octets")?;
+        Ok(<[u8; 16]>::try_from(octets.as_slice()).unwrap().into())
+    }
+}
+
+/// An `IpAddr` as an `OCTET STRING (SIZE(4|16))`, the version following
+/// from the length.
+pub mod ip {
+    use std::{
+        convert::TryFrom,
+        net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    };
+
+    use serde::{Deserializer, Serializer};
+
+    /// Serializes the octets of `address`.
+    pub fn serialize<S: Serializer>(address: &IpAddr, serializer: S) -> Result<S::Ok, S::Error> {
+        match address {
+            IpAddr::V4(address) => super::ipv4::serialize(address, serializer),
+            IpAddr::V6(address) => super::ipv6::serialize(address, serializer),
+        }
+    }
+
+    /// Deserializes four octets as an IPv4 address and sixteen as an IPv6
+    /// address.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<IpAddr, D::Error> {
+        let octets = super::deserialize_octets(deserializer, &[4, 16], "4 or 16 octets")?;
+
+        Ok(match octets.len() {
+            4 => Ipv4Addr::from(<[u8; 4]>::try_from(octets.as_slice()).unwrap()).into(),
+            _ => Ipv6Addr::from(<[u8; 16]>::try_from(octets.as_slice()).unwrap()).into(),
+        })
+    }
+}
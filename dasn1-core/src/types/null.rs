@@ -0,0 +1,20 @@
+//! The NULL type.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::identifier::{AsnType, Identifier};
+
+/// The only value of the NULL type. Where `()` is commonly left out of a
+/// value altogether, `Null` is always present, so an `Option<Null>` can tell
+/// a component that's absent (`None`) from one that's present as NULL
+/// (`Some(Null)`), such as the `parameters` of an `AlgorithmIdentifier`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Null;
+
+impl AsnType for Null {
+    fn identifier(&self) -> Identifier {
+        Identifier::NULL
+    }
+}
@@ -1,10 +1,26 @@
-use std::ops;
+use std::{convert::TryFrom, ops};
 
 use failure::{ensure, Fallible};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
-#[serde(rename = "ASN.1#ObjectIdentifier")]
+/// An OBJECT IDENTIFIER, as its sequence of arcs.
+///
+/// Identifiers are ordered by comparing their arcs lexicographically, which
+/// is also the order of their encoded contents. It isn't the order of their
+/// complete DER encodings (e.g. in a `SET OF`), where the length octet is
+/// compared first, so `1.3` sorts before `1.2.3`.
+///
+/// Other representations of an identifier are usually its arcs as integers,
+/// which convert with `TryFrom<Vec<u32>>` and `TryFrom<&[u32]>`, failing the
+/// same way as `new`. The arcs are borrowed back with `as_arcs`, or taken
+/// with `Vec::from`.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename = "ASN.1#ObjectIdentifier")
+)]
 pub struct ObjectIdentifier(Vec<u32>);
 
 impl ObjectIdentifier {
@@ -16,6 +32,55 @@ impl ObjectIdentifier {
 
         Ok(Self(inner))
     }
+
+    /// The arcs of the identifier, starting from the root.
+    pub fn as_arcs(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Whether `self` is `prefix`, or is in the subtree under it (e.g.
+    /// `1.2.840.113549.1.1.1` starts with `1.2.840`).
+    pub fn starts_with(&self, prefix: &ObjectIdentifier) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+
+    /// The identifier with the last arc removed, or `None` if that would
+    /// leave fewer than the two root arcs.
+    pub fn parent(&self) -> Option<ObjectIdentifier> {
+        match self.0.split_last() {
+            Some((_, parent)) if parent.len() >= 2 => Some(Self(parent.to_vec())),
+            _ => None,
+        }
+    }
+
+    /// The identifier with `arc` added to the end.
+    pub fn child(&self, arc: u32) -> ObjectIdentifier {
+        let mut arcs = self.0.clone();
+        arcs.push(arc);
+        Self(arcs)
+    }
+}
+
+impl TryFrom<Vec<u32>> for ObjectIdentifier {
+    type Error = failure::Error;
+
+    fn try_from(arcs: Vec<u32>) -> Fallible<Self> {
+        Self::new(arcs)
+    }
+}
+
+impl TryFrom<&[u32]> for ObjectIdentifier {
+    type Error = failure::Error;
+
+    fn try_from(arcs: &[u32]) -> Fallible<Self> {
+        Self::new(arcs.to_vec())
+    }
+}
+
+impl From<ObjectIdentifier> for Vec<u32> {
+    fn from(oid: ObjectIdentifier) -> Self {
+        oid.0
+    }
 }
 
 impl AsRef<[u32]> for ObjectIdentifier {
@@ -1,9 +1,21 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    io,
+    ops::{Deref, DerefMut, RangeBounds},
+};
 
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use std::fmt;
 
-#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(rename = "ASN.1#OctetString")]
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserializer, SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
+
+/// An OCTET STRING, as its octets. It converts from and into a `Vec<u8>`,
+/// and from a `&[u8]` by copying, while `as_slice` borrows the octets.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename = "ASN.1#OctetString"))]
 pub struct OctetString(Vec<u8>);
 
 impl OctetString {
@@ -14,6 +26,46 @@ impl OctetString {
     pub fn into_vec(self) -> Vec<u8> {
         self.0
     }
+
+    /// The octets of the string.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Copies the octets in `range` into a new `OctetString`.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds, the same as indexing a slice.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        Self(self.0[(range.start_bound().cloned(), range.end_bound().cloned())].to_vec())
+    }
+
+    /// Joins `strings` end to end into a single `OctetString`.
+    pub fn concat(strings: &[OctetString]) -> Self {
+        Self(strings.iter().flat_map(|string| string.iter().cloned()).collect())
+    }
+
+    /// Reads the octets from the start, such as to pipe them into a hasher
+    /// with `io::copy` without copying them first.
+    pub fn as_read(&self) -> impl io::Read + '_ {
+        self.0.as_slice()
+    }
+}
+
+/// Appends the written octets, so an `OctetString` can be built up by
+/// anything writing to an `io::Write`.
+impl io::Write for OctetString {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.0.write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl From<Vec<u8>> for OctetString {
@@ -22,6 +74,18 @@ impl From<Vec<u8>> for OctetString {
     }
 }
 
+impl From<&[u8]> for OctetString {
+    fn from(octets: &[u8]) -> Self {
+        Self(octets.to_vec())
+    }
+}
+
+impl From<OctetString> for Vec<u8> {
+    fn from(string: OctetString) -> Self {
+        string.0
+    }
+}
+
 impl AsRef<[u8]> for OctetString {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
@@ -41,3 +105,52 @@ impl DerefMut for OctetString {
         &mut self.0
     }
 }
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for OctetString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct("ASN.1#OctetString", OctetStringVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Accepts the contents as a single slice of bytes, falling back to a
+/// sequence of `u8`s for formats that don't have a native bytes type.
+struct OctetStringVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for OctetStringVisitor {
+    type Value = OctetString;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an octet string")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_byte_buf(self)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(OctetString(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(OctetString(v))
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+
+        Ok(OctetString(bytes))
+    }
+}
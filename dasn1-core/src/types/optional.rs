@@ -1,3 +1,4 @@
+#[cfg(feature = "serde")]
 use serde::{
     Deserialize,
     de::Deserializer,
@@ -10,6 +11,7 @@ use crate::identifier::AsnType;
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Optional<T: AsnType>(Option<Option<T>>);
 
+#[cfg(feature = "serde")]
 impl<T: Serialize + AsnType> Serialize for Optional<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -20,6 +22,7 @@ impl<T: Serialize + AsnType> Serialize for Optional<T> {
 }
 
 
+#[cfg(feature = "serde")]
 impl<'de, T: Deserialize<'de> + AsnType> Deserialize<'de> for Optional<T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
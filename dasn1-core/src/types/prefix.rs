@@ -1,5 +1,9 @@
-use std::{fmt, marker::PhantomData};
+use std::marker::PhantomData;
 
+#[cfg(feature = "serde")]
+use std::fmt;
+
+#[cfg(feature = "serde")]
 use serde::{
     Deserialize,
     Deserializer,
@@ -12,7 +16,6 @@ use typenum::marker_traits::Unsigned;
 
 use crate::{
     identifier::{
-        Class,
         Identifier,
         AsnType,
         constant::*,
@@ -57,7 +60,9 @@ impl<P: Prefix, C: ConstClass, N: Unsigned> AsnType for ConstIdentifier<P, C, N>
 }
 
 impl<P: Prefix, C: ConstClass, N: Unsigned, T> ConstPrefixed<P, C, N, T> {
+    #[cfg(feature = "serde")]
     const IDENTIFIER: Identifier = Identifier::new(C::CLASS, N::U32);
+    #[cfg(feature = "serde")]
     const NAME: &'static str = P::NAME;
 
     pub fn new(value: T) -> Self {
@@ -72,6 +77,7 @@ impl<P: Prefix, C: ConstClass, N: Unsigned, T> ConstPrefixed<P, C, N, T> {
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de, P: Prefix, C: ConstClass, N: Unsigned, T: Deserialize<'de>> Deserialize<'de> for ConstPrefixed<P, C, N, T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -83,6 +89,7 @@ impl<'de, P: Prefix, C: ConstClass, N: Unsigned, T: Deserialize<'de>> Deserializ
     }
 }
 
+#[cfg(feature = "serde")]
 impl<P: Prefix, C: ConstClass, N: Unsigned, T: Serialize> Serialize for ConstPrefixed<P, C, N, T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -96,17 +103,91 @@ impl<P: Prefix, C: ConstClass, N: Unsigned, T: Serialize> Serialize for ConstPre
     }
 }
 
+/// Whether a `Tagged` value's tag replaces the value's own tag, or is
+/// wrapped around it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Tagging {
+    /// Equivalent to `Implicit`.
+    Implicit,
+    /// Equivalent to `Explicit`.
+    Explicit,
+}
+
+/// A value prefixed with a tag chosen at runtime, rather than at compile
+/// time like `Implicit` and `Explicit`. As the tag isn't part of the type,
+/// `Tagged` can only be serialized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tagged<T> {
+    tag: Identifier,
+    tagging: Tagging,
+    value: T,
+}
+
+impl<T> Tagged<T> {
+    /// Instantiates a new `Tagged` from its components.
+    pub fn new(tag: Identifier, tagging: Tagging, value: T) -> Self {
+        Self { tag, tagging, value }
+    }
+
+    /// Tags `value` with `tag` in place of its own tag.
+    pub fn implicit(tag: Identifier, value: T) -> Self {
+        Self::new(tag, Tagging::Implicit, value)
+    }
+
+    /// Wraps `value`, with its own tag, inside of `tag`.
+    pub fn explicit(tag: Identifier, value: T) -> Self {
+        Self::new(tag, Tagging::Explicit, value)
+    }
+
+    /// Whether the tag is implicit or explicit.
+    pub fn tagging(&self) -> Tagging {
+        self.tagging
+    }
+
+    /// Consumes `self` and returns the untagged value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> AsnType for Tagged<T> {
+    fn identifier(&self) -> Identifier {
+        self.tag
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Tagged<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let name = match self.tagging {
+            Tagging::Implicit => ImplicitPrefix::NAME,
+            Tagging::Explicit => ExplicitPrefix::NAME,
+        };
+
+        serializer.serialize_newtype_struct(
+            name,
+            &(self.tag.class as u8, self.tag.tag, &self.value),
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
 struct PrefixVisitor<T> {
     phantom: PhantomData<T>,
     identifier: Identifier,
 }
 
+#[cfg(feature = "serde")]
 impl<T> PrefixVisitor<T> {
     fn new(identifier: Identifier) -> Self {
         Self { phantom: PhantomData, identifier }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de, T: Deserialize<'de>> Visitor<'de> for PrefixVisitor<T> {
     type Value = T;
 
@@ -117,7 +198,7 @@ impl<'de, T: Deserialize<'de>> Visitor<'de> for PrefixVisitor<T> {
     fn visit_seq<S: SeqAccess<'de>>(self, mut visitor: S) -> Result<Self::Value, S::Error> {
         let class: u8 = visitor.next_element()?.unwrap();
         let tag: u32 = visitor.next_element()?.unwrap();
-        let actual_identifier = Identifier::new(Class::from_u8(class), tag);
+        let actual_identifier = Identifier::new(crate::identifier::Class::from_u8(class), tag);
 
         if self.identifier != actual_identifier {
             return Err(de::Error::custom(format!("{:?} != {:?}", self.identifier, actual_identifier)))
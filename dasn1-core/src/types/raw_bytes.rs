@@ -0,0 +1,102 @@
+//! Decoded values kept along with their original encoding.
+
+#[cfg(feature = "serde")]
+use std::{fmt, marker::PhantomData};
+
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserializer, SeqAccess, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+use crate::{
+    identifier::{AsnType, Identifier},
+    types::AnyValue,
+};
+
+/// A decoded value along with the exact encoding it was decoded from, such as
+/// the `tbsCertificate` of an X.509 certificate, whose signature is over its
+/// original bytes rather than a re-encoding of it.
+///
+/// The value is encoded as the kept bytes, so re-encoding it reproduces the
+/// original encoding even if it wasn't canonical.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct WithRawBytes<T> {
+    value: T,
+    raw: AnyValue,
+}
+
+impl<T> WithRawBytes<T> {
+    /// Instantiates a new `WithRawBytes` from a value and its encoding.
+    pub fn new(value: T, raw: AnyValue) -> Self {
+        Self { value, raw }
+    }
+
+    /// The decoded value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The complete encoding (identifier, length, and contents octets) the
+    /// value was decoded from.
+    pub fn raw(&self) -> &[u8] {
+        self.raw.as_ref()
+    }
+
+    /// Consumes `self` and returns the decoded value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Consumes `self` and returns the decoded value and its encoding.
+    pub fn into_parts(self) -> (T, Vec<u8>) {
+        (self.value, self.raw.into_vec())
+    }
+}
+
+impl<T> AsnType for WithRawBytes<T> {
+    fn identifier(&self) -> Identifier {
+        self.raw.identifier()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Serialize for WithRawBytes<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.raw.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for WithRawBytes<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct("ASN.1#WithRawBytes", WithRawBytesVisitor(PhantomData))
+    }
+}
+
+/// Expects the decoded value, followed by the bytes it was decoded from.
+#[cfg(feature = "serde")]
+struct WithRawBytesVisitor<T>(PhantomData<T>);
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Visitor<'de> for WithRawBytesVisitor<T> {
+    type Value = WithRawBytes<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value and its encoding")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let value = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let raw = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        Ok(WithRawBytes { value, raw })
+    }
+}
@@ -0,0 +1,29 @@
+//! Encodes an `Option` field as a component that isn't OPTIONAL, for types
+//! that are only filled in after they're built. Used with
+//! `#[serde(with = "dasn1::types::required")]`, serializing fails if the
+//! field is `None`, rather than leaving out the component and producing a
+//! SEQUENCE the schema doesn't allow. Deserializing always produces `Some`,
+//! and fails the same as any required component if it's missing.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes the value of `Some`, or fails on `None`.
+pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    match value {
+        Some(value) => value.serialize(serializer),
+        None => Err(serde::ser::Error::custom("A required component is absent.")),
+    }
+}
+
+/// Deserializes the value, wrapped in `Some`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,132 @@
+//! The restricted character string types, whose values are limited to a
+//! character set (X.680 41).
+
+use std::{fmt, ops::Deref};
+
+use failure::{ensure, Fallible};
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+macro_rules! restricted_strings {
+    ($($(#[$meta:meta])* $name:ident = $serde_name:expr, $permitted:expr;)+) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+            pub struct $name(String);
+
+            impl $name {
+                /// Instantiates a new instance from `string`, checking that
+                /// every character is permitted in the type's character set.
+                pub fn new<I: Into<String>>(string: I) -> Fallible<Self> {
+                    let string = string.into();
+
+                    ensure!(
+                        string.chars().all(Self::is_permitted),
+                        "{:?} contains characters not permitted in {}.",
+                        string,
+                        stringify!($name)
+                    );
+
+                    Ok(Self(string))
+                }
+
+                /// Whether `character` is a member of the type's
+                /// character set.
+                pub fn is_permitted(character: char) -> bool {
+                    ($permitted)(character)
+                }
+
+                /// Consumes `self` and returns the inner `String`.
+                pub fn into_string(self) -> String {
+                    self.0
+                }
+            }
+
+            impl AsRef<str> for $name {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl Deref for $name {
+                type Target = String;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl Serialize for $name {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    serializer.serialize_newtype_struct($serde_name, &self.0)
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    let string = deserializer.deserialize_newtype_struct(
+                        $serde_name,
+                        RestrictedStringVisitor(stringify!($name)),
+                    )?;
+
+                    Self::new(string).map_err(de::Error::custom)
+                }
+            }
+        )+
+    }
+}
+
+restricted_strings! {
+    /// A representation of the `NumericString` ASN.1 data type, permitting
+    /// the digits `0` to `9` and space.
+    NumericString = "ASN.1#NumericString", |c: char| c == ' ' || c.is_ascii_digit();
+    /// A representation of the `PrintableString` ASN.1 data type, permitting
+    /// latin letters, digits, space, and the characters `'()+,-./:=?`.
+    PrintableString = "ASN.1#PrintableString", |c: char| {
+        c.is_ascii_alphanumeric() || " '()+,-./:=?".contains(c)
+    };
+    /// A representation of the `IA5String` ASN.1 data type, permitting any
+    /// character from the International Alphabet No. 5 (ASCII).
+    Ia5String = "ASN.1#IA5String", |c: char| c.is_ascii();
+}
+
+#[cfg(feature = "serde")]
+pub(crate) struct RestrictedStringVisitor(pub(crate) &'static str);
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for RestrictedStringVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a {}", self.0)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.to_owned())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        String::deserialize(de)
+    }
+}
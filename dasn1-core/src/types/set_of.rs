@@ -0,0 +1,106 @@
+//! The `SET OF` type, for collections whose order doesn't matter.
+
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "serde")]
+use std::{fmt, marker::PhantomData};
+
+#[cfg(feature = "serde")]
+use serde::{
+    de::{value::SeqAccessDeserializer, Deserializer, SeqAccess, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+use crate::identifier::{AsnType, Identifier};
+
+/// A representation of the `SET OF` ASN.1 data type, wrapping any collection
+/// that serializes as a sequence, such as `Vec<T>`, `BTreeSet<T>`, or
+/// `HashSet<T>`. A collection on its own is encoded as a `SEQUENCE OF`.
+///
+/// The elements of a `SET OF` are unordered, so DER sorts them by their
+/// encodings (X.690 11.6) and rejects any not in that order when decoding.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct SetOf<C>(pub C);
+
+impl<C> SetOf<C> {
+    /// Wraps `collection` to be encoded as a `SET OF`.
+    pub fn new(collection: C) -> Self {
+        Self(collection)
+    }
+
+    /// Unwraps the collection.
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<C> From<C> for SetOf<C> {
+    fn from(collection: C) -> Self {
+        Self(collection)
+    }
+}
+
+impl<C> Deref for SetOf<C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<C> DerefMut for SetOf<C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<C> AsnType for SetOf<C> {
+    fn identifier(&self) -> Identifier {
+        Identifier::SET
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C: Serialize> Serialize for SetOf<C> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct("ASN.1#SetOf", &self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, C: Deserialize<'de>> Deserialize<'de> for SetOf<C> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct("ASN.1#SetOf", SetOfVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Hands the elements to the collection's own `Deserialize`, whether the
+/// format visits them directly or through the newtype.
+struct SetOfVisitor<C>(PhantomData<C>);
+
+#[cfg(feature = "serde")]
+impl<'de, C: Deserialize<'de>> Visitor<'de> for SetOfVisitor<C> {
+    type Value = SetOf<C>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a set of values")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        C::deserialize(deserializer).map(SetOf)
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+        C::deserialize(SeqAccessDeserializer::new(seq)).map(SetOf)
+    }
+}
@@ -0,0 +1,362 @@
+//! The `TeletexString` type, whose contents are T.61 encoded.
+
+#[cfg(feature = "serde")]
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+/// A representation of the `TeletexString` ASN.1 data type. The contents are
+/// kept as the raw T.61 bytes, as they generally can't be losslessly
+/// represented as UTF-8. With the `teletex` feature enabled the contents can
+/// be transcoded to and from UTF-8 on demand.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct TeletexString(Vec<u8>);
+
+/// Alias for `TeletexString`, which is also known as `T61String`.
+pub type T61String = TeletexString;
+
+impl TeletexString {
+    /// Instantiates a new `TeletexString` from T.61 encoded bytes.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Consumes `self` and returns the T.61 encoded bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Vec<u8>> for TeletexString {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for TeletexString {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+struct Bytes<'a>(&'a [u8]);
+
+#[cfg(feature = "serde")]
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for TeletexString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct("ASN.1#TeletexString", &Bytes(&self.0))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TeletexString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct("ASN.1#TeletexString", TeletexStringVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct TeletexStringVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for TeletexStringVisitor {
+    type Value = TeletexString;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a T.61 string")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_byte_buf(self)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(TeletexString(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(TeletexString(v))
+    }
+}
+
+#[cfg(feature = "teletex")]
+mod transcoding {
+    use super::TeletexString;
+
+    /// Characters that can't be represented in T.61.
+    const UNREPRESENTABLE: u8 = b'?';
+
+    /// The characters for `0xA0..=0xBF`, where `None` is unassigned.
+    const UPPER_SYMBOLS: [Option<char>; 32] = [
+        None, Some('¡'), Some('¢'), Some('£'), Some('$'), Some('¥'), Some('#'), Some('§'),
+        Some('¤'), None, None, Some('«'), None, None, None, None,
+        Some('°'), Some('±'), Some('²'), Some('³'), Some('×'), Some('µ'), Some('¶'), Some('·'),
+        Some('÷'), None, None, Some('»'), Some('¼'), Some('½'), Some('¾'), Some('¿'),
+    ];
+
+    /// The characters for `0xE0..=0xFF`, where `None` is unassigned.
+    const UPPER_LETTERS: [Option<char>; 32] = [
+        Some('Ω'), Some('Æ'), Some('Đ'), Some('ª'), Some('Ħ'), None, Some('Ĳ'), Some('Ŀ'),
+        Some('Ł'), Some('Ø'), Some('Œ'), Some('º'), Some('Þ'), Some('Ŧ'), Some('Ŋ'), Some('ŉ'),
+        Some('ĸ'), Some('æ'), Some('đ'), Some('ð'), Some('ħ'), Some('ı'), Some('ĳ'), Some('ŀ'),
+        Some('ł'), Some('ø'), Some('œ'), Some('ß'), Some('þ'), Some('ŧ'), Some('ŋ'), Some('\u{AD}'),
+    ];
+
+    /// A non-spacing diacritical mark from `0xC1..=0xCF`. In T.61 these come
+    /// before the letter they modify, where in Unicode the combining
+    /// character comes after it, or is composed with it into one character.
+    struct Diacritic {
+        byte: u8,
+        combining: char,
+        /// Used when the mark is followed by a space, or nothing at all.
+        spacing: char,
+        /// The letters with a precomposed form, and their composed forms at
+        /// the same positions.
+        letters: &'static str,
+        composed: &'static str,
+    }
+
+    const DIACRITICS: &[Diacritic] = &[
+        Diacritic {
+            byte: 0xC1,
+            combining: '\u{300}',
+            spacing: '`',
+            letters: "AEIOUaeiou",
+            composed: "ÀÈÌÒÙàèìòù",
+        },
+        Diacritic {
+            byte: 0xC2,
+            combining: '\u{301}',
+            spacing: '´',
+            letters: "ACEILNORSUYZaceilnorsuyz",
+            composed: "ÁĆÉÍĹŃÓŔŚÚÝŹáćéíĺńóŕśúýź",
+        },
+        Diacritic {
+            byte: 0xC3,
+            combining: '\u{302}',
+            spacing: '^',
+            letters: "ACEGHIJOSUWYaceghijosuwy",
+            composed: "ÂĈÊĜĤÎĴÔŜÛŴŶâĉêĝĥîĵôŝûŵŷ",
+        },
+        Diacritic {
+            byte: 0xC4,
+            combining: '\u{303}',
+            spacing: '~',
+            letters: "AINOUainou",
+            composed: "ÃĨÑÕŨãĩñõũ",
+        },
+        Diacritic {
+            byte: 0xC5,
+            combining: '\u{304}',
+            spacing: '¯',
+            letters: "AEIOUaeiou",
+            composed: "ĀĒĪŌŪāēīōū",
+        },
+        Diacritic {
+            byte: 0xC6,
+            combining: '\u{306}',
+            spacing: '˘',
+            letters: "AEGIOUaegiou",
+            composed: "ĂĔĞĬŎŬăĕğĭŏŭ",
+        },
+        Diacritic {
+            byte: 0xC7,
+            combining: '\u{307}',
+            spacing: '˙',
+            letters: "CEGIZcegz",
+            composed: "ĊĖĠİŻċėġż",
+        },
+        Diacritic {
+            byte: 0xC8,
+            combining: '\u{308}',
+            spacing: '¨',
+            letters: "AEIOUYaeiouy",
+            composed: "ÄËÏÖÜŸäëïöüÿ",
+        },
+        Diacritic {
+            byte: 0xCA,
+            combining: '\u{30A}',
+            spacing: '˚',
+            letters: "AUau",
+            composed: "ÅŮåů",
+        },
+        Diacritic {
+            byte: 0xCB,
+            combining: '\u{327}',
+            spacing: '¸',
+            letters: "CGKLNRSTcgklnrst",
+            composed: "ÇĢĶĻŅŖŞŢçģķļņŗşţ",
+        },
+        Diacritic {
+            byte: 0xCC,
+            combining: '\u{332}',
+            spacing: '_',
+            letters: "",
+            composed: "",
+        },
+        Diacritic {
+            byte: 0xCD,
+            combining: '\u{30B}',
+            spacing: '˝',
+            letters: "OUou",
+            composed: "ŐŰőű",
+        },
+        Diacritic {
+            byte: 0xCE,
+            combining: '\u{328}',
+            spacing: '˛',
+            letters: "AEIUaeiu",
+            composed: "ĄĘĮŲąęįų",
+        },
+        Diacritic {
+            byte: 0xCF,
+            combining: '\u{30C}',
+            spacing: 'ˇ',
+            letters: "CDELNRSTZcdelnrstz",
+            composed: "ČĎĚĽŇŘŠŤŽčďěľňřšťž",
+        },
+    ];
+
+    impl Diacritic {
+        fn from_byte(byte: u8) -> Option<&'static Self> {
+            DIACRITICS.iter().find(|diacritic| diacritic.byte == byte)
+        }
+
+        fn compose(&self, letter: char) -> Option<char> {
+            let index = self.letters.chars().position(|c| c == letter)?;
+            self.composed.chars().nth(index)
+        }
+
+        fn decompose(character: char) -> Option<(&'static Self, char)> {
+            DIACRITICS.iter().find_map(|diacritic| {
+                let index = diacritic.composed.chars().position(|c| c == character)?;
+                Some((diacritic, diacritic.letters.chars().nth(index)?))
+            })
+        }
+    }
+
+    /// Decodes a byte that isn't a diacritical mark.
+    fn decode(byte: u8) -> char {
+        let character = match byte {
+            0x00..=0x9F => Some(byte as char),
+            0xA0..=0xBF => UPPER_SYMBOLS[(byte - 0xA0) as usize],
+            0xE0..=0xFF => UPPER_LETTERS[(byte - 0xE0) as usize],
+            _ => None,
+        };
+
+        character.unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    fn encode(character: char) -> Option<u8> {
+        if character.is_ascii() || ('\u{80}'..='\u{9F}').contains(&character) {
+            return Some(character as u8);
+        }
+
+        let position = |table: &[Option<char>; 32]| {
+            table.iter().position(|&c| c == Some(character)).map(|i| i as u8)
+        };
+
+        position(&UPPER_SYMBOLS)
+            .map(|i| 0xA0 + i)
+            .or_else(|| position(&UPPER_LETTERS).map(|i| 0xE0 + i))
+    }
+
+    impl TeletexString {
+        /// Transcodes the T.61 contents to UTF-8. A diacritical mark and the
+        /// letter following it are composed into a single character where
+        /// Unicode has one, and otherwise become the letter followed by the
+        /// combining form of the mark. Unassigned bytes are replaced with
+        /// `U+FFFD REPLACEMENT CHARACTER`.
+        pub fn to_utf8_lossy(&self) -> String {
+            let mut output = String::with_capacity(self.0.len());
+            let mut bytes = self.0.iter().cloned();
+
+            while let Some(byte) = bytes.next() {
+                let diacritic = match Diacritic::from_byte(byte) {
+                    Some(diacritic) => diacritic,
+                    None => {
+                        output.push(decode(byte));
+                        continue;
+                    }
+                };
+
+                match bytes.next() {
+                    None | Some(b' ') => output.push(diacritic.spacing),
+                    Some(letter) => {
+                        let letter = decode(letter);
+
+                        match diacritic.compose(letter) {
+                            Some(composed) => output.push(composed),
+                            None => {
+                                output.push(letter);
+                                output.push(diacritic.combining);
+                            }
+                        }
+                    }
+                }
+            }
+
+            output
+        }
+
+        /// Transcodes `string` to T.61 on a best-effort basis. Precomposed
+        /// letters and combining marks following a letter are written as a
+        /// diacritical mark before the letter. Characters with no T.61
+        /// equivalent, such as most of those outside of Latin scripts, are
+        /// replaced with `?`, so the conversion is lossy.
+        pub fn from_utf8(string: &str) -> Self {
+            let mut output: Vec<u8> = Vec::with_capacity(string.len());
+            let mut last_letter = None;
+
+            for character in string.chars() {
+                if let Some(diacritic) = DIACRITICS.iter().find(|d| d.combining == character) {
+                    // The mark needs to go in front of the letter written last.
+                    if let Some(position) = last_letter.take() {
+                        output.insert(position, diacritic.byte);
+                    } else {
+                        output.push(UNREPRESENTABLE);
+                    }
+
+                    continue;
+                }
+
+                last_letter = Some(output.len());
+
+                if let Some(byte) = encode(character) {
+                    output.push(byte);
+                } else if let Some((diacritic, letter)) = Diacritic::decompose(character) {
+                    last_letter = None;
+                    output.extend_from_slice(&[diacritic.byte, letter as u8]);
+                } else if let Some(diacritic) = DIACRITICS.iter().find(|d| d.spacing == character) {
+                    last_letter = None;
+                    output.extend_from_slice(&[diacritic.byte, b' ']);
+                } else {
+                    output.push(UNREPRESENTABLE);
+                }
+            }
+
+            TeletexString(output)
+        }
+    }
+}
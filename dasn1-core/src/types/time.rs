@@ -0,0 +1,260 @@
+//! The `UTCTime` and `GeneralizedTime` types, holding a time in the form
+//! DER encodes it in (X.690 11.7, 11.8).
+
+use std::{fmt, ops::Deref};
+#[cfg(feature = "std-time")]
+use std::{
+    convert::TryFrom,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use failure::{ensure, Fallible};
+#[cfg(feature = "std-time")]
+use failure::format_err;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "serde")]
+use super::restricted_string::RestrictedStringVisitor;
+
+macro_rules! times {
+    ($($(#[$meta:meta])* $name:ident = $serde_name:expr, $validate:expr;)+) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+            pub struct $name(String);
+
+            impl $name {
+                /// Instantiates a new instance from `time`, checking that it
+                /// is in the type's DER format.
+                pub fn new<I: Into<String>>(time: I) -> Fallible<Self> {
+                    let time = time.into();
+
+                    ensure!(
+                        ($validate)(time.as_bytes()),
+                        "{:?} is not a valid {}.",
+                        time,
+                        stringify!($name)
+                    );
+
+                    Ok(Self(time))
+                }
+
+                /// Consumes `self` and returns the inner `String`.
+                pub fn into_string(self) -> String {
+                    self.0
+                }
+            }
+
+            impl AsRef<str> for $name {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl Deref for $name {
+                type Target = str;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl Serialize for $name {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    serializer.serialize_newtype_struct($serde_name, &self.0)
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    let time = deserializer.deserialize_newtype_struct(
+                        $serde_name,
+                        RestrictedStringVisitor(stringify!($name)),
+                    )?;
+
+                    Self::new(time).map_err(de::Error::custom)
+                }
+            }
+        )+
+    }
+}
+
+times! {
+    /// A representation of the `UTCTime` ASN.1 data type, in the form
+    /// `YYMMDDhhmmssZ`.
+    UtcTime = "ASN.1#UTCTime", |time: &[u8]| {
+        time.len() == 13 && all_digits(&time[..12]) && time[12] == b'Z'
+    };
+    /// A representation of the `GeneralizedTime` ASN.1 data type, in the form
+    /// `YYYYMMDDhhmmss[.f]Z`, where the optional fraction of a second has no
+    /// trailing zeros.
+    GeneralizedTime = "ASN.1#GeneralizedTime", |time: &[u8]| {
+        let len = time.len();
+
+        if len < 15 {
+            return false;
+        }
+
+        let valid_fraction = match &time[14..len - 1] {
+            [] => true,
+            [b'.', fraction @ ..] => {
+                !fraction.is_empty() && all_digits(fraction) && fraction.last() != Some(&b'0')
+            }
+            _ => false,
+        };
+
+        all_digits(&time[..14]) && time[len - 1] == b'Z' && valid_fraction
+    };
+}
+
+fn all_digits(bytes: &[u8]) -> bool {
+    bytes.iter().all(u8::is_ascii_digit)
+}
+
+#[cfg(feature = "std-time")]
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Converts a time to its `GeneralizedTime` in UTC, keeping as much of the
+/// fraction of a second as is needed. Times before the Unix epoch, or after
+/// the year 9999, can't be converted.
+#[cfg(feature = "std-time")]
+impl TryFrom<SystemTime> for GeneralizedTime {
+    type Error = failure::Error;
+
+    fn try_from(time: SystemTime) -> Fallible<Self> {
+        let since_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| format_err!("{:?} is before the Unix epoch.", time))?;
+
+        let seconds = since_epoch.as_secs();
+        let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+        let seconds = seconds % SECONDS_PER_DAY;
+
+        ensure!(year <= 9999, "{:?} is after the year 9999.", time);
+
+        let mut string = format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+
+        let nanos = since_epoch.subsec_nanos();
+        if nanos != 0 {
+            string.push('.');
+            string.push_str(format!("{:09}", nanos).trim_end_matches('0'));
+        }
+
+        string.push('Z');
+        Self::new(string)
+    }
+}
+
+/// Converts a `GeneralizedTime` to the time it represents. Any fraction of a
+/// second beyond nanoseconds is truncated, as `SystemTime` can't represent
+/// it, and times before the Unix epoch are rejected.
+#[cfg(feature = "std-time")]
+impl TryFrom<&GeneralizedTime> for SystemTime {
+    type Error = failure::Error;
+
+    fn try_from(time: &GeneralizedTime) -> Fallible<Self> {
+        // The digits were already checked by `GeneralizedTime::new`.
+        let number = |range: std::ops::Range<usize>| -> u64 { time[range].parse().unwrap() };
+        let (year, month, day) = (number(0..4), number(4..6), number(6..8));
+        let (hour, minute, second) = (number(8..10), number(10..12), number(12..14));
+
+        ensure!(year >= 1970, "{} is before the Unix epoch.", time);
+        ensure!(
+            (1..=12).contains(&month) && day >= 1 && hour < 24 && minute < 60 && second < 60,
+            "{} isn't a valid date and time.",
+            time
+        );
+
+        let days = days_from_civil(year, month, day);
+        // Days past the end of the month roll over into the next one.
+        ensure!(civil_from_days(days) == (year, month, day), "{} isn't a valid date.", time);
+
+        let fraction = time[14..time.len() - 1].trim_start_matches('.');
+        let nanos = format!("{:0<9.9}", fraction).parse().unwrap();
+        let seconds = days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+
+        UNIX_EPOCH
+            .checked_add(Duration::new(seconds, nanos))
+            .ok_or_else(|| format_err!("{} can't be represented as a SystemTime.", time))
+    }
+}
+
+/// `serde` functions encoding a `SystemTime` as a `GeneralizedTime`, for
+/// fields marked with
+/// `#[serde(with = "dasn1_core::types::time::system_time")]`.
+#[cfg(all(feature = "std-time", feature = "serde"))]
+pub mod system_time {
+    use std::{convert::TryFrom, time::SystemTime};
+
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::GeneralizedTime;
+
+    /// Serializes `time` as a `GeneralizedTime`.
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        GeneralizedTime::try_from(*time)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    /// Deserializes a `GeneralizedTime` as the `SystemTime` it represents.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let time = GeneralizedTime::deserialize(deserializer)?;
+        SystemTime::try_from(&time).map_err(de::Error::custom)
+    }
+}
+
+/// The number of days from 1970-01-01 to the given date in the proleptic
+/// Gregorian calendar, from Howard Hinnant's `days_from_civil`. Only dates
+/// from 1970 are needed, so this is unsigned.
+#[cfg(feature = "std-time")]
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    // Years start in March, so leap days fall at the end of the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of `days_from_civil`, returning the year, month, and day.
+#[cfg(feature = "std-time")]
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
@@ -0,0 +1,20 @@
+use dasn1_core::{
+    identifier::{AsnType, Identifier},
+    types::AsnString,
+};
+
+#[test]
+fn narrowest_type() {
+    let numeric = AsnString::new("12345");
+    assert!(matches!(numeric, AsnString::Numeric(_)));
+    assert_eq!(Identifier::NUMERIC_STRING, numeric.identifier());
+
+    let printable = AsnString::new("Hello");
+    assert!(matches!(printable, AsnString::Printable(_)));
+    assert_eq!(Identifier::PRINTABLE_STRING, printable.identifier());
+
+    let utf8 = AsnString::new("café");
+    assert!(matches!(utf8, AsnString::Utf8(_)));
+    assert_eq!(Identifier::UTF8_STRING, utf8.identifier());
+    assert_eq!("café", &*utf8);
+}
@@ -0,0 +1,59 @@
+use dasn1_core::types::BitString;
+
+#[test]
+fn parse_hstring() {
+    let string: BitString = "'A98A'H".parse().unwrap();
+
+    assert_eq!(16, string.len());
+    assert_eq!(&[0xA9, 0x8A][..], &*string.to_bytes());
+    assert_eq!("'1010100110001010'B", string.to_string());
+}
+
+#[test]
+fn parse_bstring() {
+    let string: BitString = "'1101'B".parse().unwrap();
+    let bits: Vec<_> = string.iter_bits().collect();
+
+    assert_eq!(vec![true, true, false, true], bits);
+    assert_eq!(string, "' 11 01 'B".parse().unwrap());
+}
+
+#[test]
+fn empty_bit_string() {
+    let string: BitString = "''B".parse().unwrap();
+
+    assert!(string.is_empty());
+    assert_eq!("''B", string.to_string());
+    assert_eq!(string, "''H".parse().unwrap());
+}
+
+#[test]
+fn display_round_trip() {
+    let string = BitString::from_bytes(&[0b1011_0001]);
+    let mut shorter = string.clone();
+    shorter.truncate(5);
+
+    for string in [string, shorter].iter() {
+        assert_eq!(*string, string.to_string().parse().unwrap());
+    }
+}
+
+#[test]
+fn invalid_notation() {
+    assert!("1101".parse::<BitString>().is_err());
+    assert!("'1102'B".parse::<BitString>().is_err());
+    assert!("'a98a'H".parse::<BitString>().is_err());
+    assert!("'1101'O".parse::<BitString>().is_err());
+}
+
+#[test]
+fn extend_from_bytes() {
+    let mut string: BitString = "'101'B".parse().unwrap();
+    string.extend_from_bytes(&[0xF0]);
+
+    assert_eq!("'10111110000'B", string.to_string());
+
+    let mut empty = BitString::new();
+    empty.extend_from_bytes(&[0xA9, 0x8A]);
+    assert_eq!(empty, "'A98A'H".parse().unwrap());
+}
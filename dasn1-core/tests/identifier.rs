@@ -0,0 +1,61 @@
+use dasn1_core::identifier::{Class, Identifier};
+
+#[test]
+fn universal_tag_numbers() {
+    let constants = [
+        (Identifier::EOC, 0),
+        (Identifier::BOOL, 1),
+        (Identifier::INTEGER, 2),
+        (Identifier::BIT_STRING, 3),
+        (Identifier::OCTET_STRING, 4),
+        (Identifier::NULL, 5),
+        (Identifier::OBJECT_IDENTIFIER, 6),
+        (Identifier::OBJECT_DESCRIPTOR, 7),
+        (Identifier::EXTERNAL, 8),
+        (Identifier::REAL, 9),
+        (Identifier::ENUMERATED, 10),
+        (Identifier::EMBEDDED_PDV, 11),
+        (Identifier::UTF8_STRING, 12),
+        (Identifier::RELATIVE_OID, 13),
+        (Identifier::TIME, 14),
+        (Identifier::SEQUENCE, 16),
+        (Identifier::SET, 17),
+        (Identifier::NUMERIC_STRING, 18),
+        (Identifier::PRINTABLE_STRING, 19),
+        (Identifier::TELETEX_STRING, 20),
+        (Identifier::VIDEOTEX_STRING, 21),
+        (Identifier::IA5_STRING, 22),
+        (Identifier::UTC_TIME, 23),
+        (Identifier::GENERALIZED_TIME, 24),
+        (Identifier::GRAPHIC_STRING, 25),
+        (Identifier::VISIBLE_STRING, 26),
+        (Identifier::GENERAL_STRING, 27),
+        (Identifier::UNIVERSAL_STRING, 28),
+        (Identifier::CHARACTER_STRING, 29),
+        (Identifier::BMP_STRING, 30),
+        (Identifier::DATE, 31),
+        (Identifier::TIME_OF_DAY, 32),
+        (Identifier::DATE_TIME, 33),
+        (Identifier::DURATION, 34),
+        (Identifier::OID_IRI, 35),
+        (Identifier::RELATIVE_OID_IRI, 36),
+    ];
+
+    for (identifier, tag) in constants.iter() {
+        assert_eq!(Identifier::new(Class::Universal, *tag), *identifier);
+    }
+}
+
+#[test]
+fn universal_type_names() {
+    assert_eq!(Some("SEQUENCE"), Identifier::from_universal(16));
+    assert_eq!(Some("BOOLEAN"), Identifier::from_universal(1));
+    assert_eq!(Some("UTF8String"), Identifier::from_universal(12));
+    assert_eq!(Some("BMPString"), Identifier::from_universal(30));
+    assert_eq!(Some("RELATIVE-OID-IRI"), Identifier::from_universal(36));
+
+    // Reserved for the encoding rules, and not yet assigned.
+    assert_eq!(None, Identifier::from_universal(0));
+    assert_eq!(None, Identifier::from_universal(15));
+    assert_eq!(None, Identifier::from_universal(37));
+}
@@ -0,0 +1,27 @@
+use std::convert::TryFrom;
+
+use dasn1_core::types::Integer;
+
+#[test]
+fn try_from_checks_range() {
+    let large = Integer::from(u128::from(u64::MAX) * 2);
+
+    assert!(u32::try_from(large.clone()).is_err());
+    assert_eq!(u128::from(u64::MAX) * 2, u128::try_from(large).unwrap());
+
+    assert!(u8::try_from(Integer::from(-1)).is_err());
+    assert_eq!(-1, i8::try_from(Integer::from(-1)).unwrap());
+    assert_eq!(255, u8::try_from(Integer::from(255)).unwrap());
+    assert!(i8::try_from(Integer::from(255)).is_err());
+}
+
+#[test]
+fn bytes_round_trip() {
+    for value in &[0i64, 127, 128, -128, -129, i64::MAX, i64::MIN] {
+        let integer = Integer::from(*value);
+        assert_eq!(integer, Integer::from_bytes_be(&integer.to_bytes_be()));
+    }
+
+    assert_eq!(vec![0, 0x80], Integer::from(128).to_bytes_be());
+    assert_eq!(vec![0xff, 0x7f], Integer::from(-129).to_bytes_be());
+}
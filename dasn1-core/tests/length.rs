@@ -0,0 +1,48 @@
+use dasn1_core::length::{decode_length, encode_length, Length, LengthError};
+
+fn encode(length: usize) -> Vec<u8> {
+    let mut output = Vec::new();
+    encode_length(length, &mut output).unwrap();
+    output
+}
+
+#[test]
+fn short_form() {
+    assert_eq!(vec![0], encode(0));
+    assert_eq!(vec![0x7F], encode(0x7F));
+
+    assert_eq!(Ok((Length::Definite(0x7F), &[0xAB][..])), decode_length(&[0x7F, 0xAB]));
+}
+
+#[test]
+fn long_form() {
+    assert_eq!(vec![0x81, 0x80], encode(0x80));
+    assert_eq!(vec![0x82, 0x01, 0x00], encode(0x100));
+    assert_eq!(vec![0x83, 0x01, 0x00, 0x00], encode(0x1_00_00));
+
+    assert_eq!(Ok((Length::Definite(0x80), &[][..])), decode_length(&[0x81, 0x80]));
+    assert_eq!(Ok((Length::Definite(0x100), &[][..])), decode_length(&[0x82, 0x01, 0x00]));
+    assert_eq!(
+        Ok((Length::Definite(0x1_00_00), &[][..])),
+        decode_length(&[0x83, 0x01, 0x00, 0x00])
+    );
+    assert_eq!(Err(LengthError::Incomplete(2)), decode_length(&[0x83, 0x01]));
+}
+
+#[test]
+fn indefinite_form() {
+    assert_eq!(Ok((Length::Indefinite, &[0, 0][..])), decode_length(&[0x80, 0, 0]));
+}
+
+#[test]
+fn reserved_form() {
+    assert_eq!(Err(LengthError::Reserved), decode_length(&[0xFF, 0x01]));
+}
+
+#[test]
+fn overflowing_length() {
+    let mut input = vec![0x80 | (std::mem::size_of::<usize>() as u8 + 1), 0x01];
+    input.resize(input.len() + std::mem::size_of::<usize>(), 0);
+
+    assert_eq!(Err(LengthError::Overflow), decode_length(&input));
+}
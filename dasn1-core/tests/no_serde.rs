@@ -0,0 +1,53 @@
+//! The types have to be usable without `serde`, which is checked by running
+//! `cargo test -p dasn1-core --no-default-features --test no_serde`.
+#![cfg(not(feature = "serde"))]
+
+use dasn1_core::{
+    identifier::{constant::Context, Identifier},
+    types::*,
+};
+use typenum::consts::U1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Color {
+    Red,
+    Blue,
+}
+
+impl Enumerable for Color {
+    fn to_enumerated(&self) -> i64 {
+        *self as i64
+    }
+
+    fn from_enumerated(value: i64) -> Option<Self> {
+        match value {
+            0 => Some(Color::Red),
+            1 => Some(Color::Blue),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn types_without_serde() {
+    let octets = OctetString::concat(&[OctetString::from(vec![1, 2]), OctetString::from(vec![3])]);
+    assert_eq!(&[2, 3][..], &*octets.slice(1..));
+
+    let bits = BitString::from_bytes(&[0b1100_0000]);
+    assert_eq!(2, bits.count_ones());
+
+    let oid = ObjectIdentifier::new(vec![1, 2, 840]).unwrap();
+    assert_eq!(&[1, 2, 840][..], &*oid);
+
+    let color = Enumerated::new(Color::Blue);
+    assert_eq!(Color::Blue, color.into_inner());
+
+    let implicit = Implicit::<Context, U1, bool>::new(true);
+    assert!(implicit.into_inner());
+
+    let explicit = Explicit::<Context, U1, u8>::new(5);
+    assert_eq!(5, explicit.into_inner());
+
+    let tagged = Tagged::implicit(Identifier::BOOL, false);
+    assert_eq!(Tagging::Implicit, tagged.tagging());
+}
@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap, HashMap};
+
+use dasn1_core::types::ObjectIdentifier;
+
+fn oid(arcs: &[u32]) -> ObjectIdentifier {
+    ObjectIdentifier::new(arcs.to_vec()).unwrap()
+}
+
+#[test]
+fn sort_order() {
+    let mut oids = vec![
+        oid(&[2, 5, 4, 3]),
+        oid(&[1, 2, 840, 113549]),
+        oid(&[1, 3]),
+        oid(&[1, 2]),
+        oid(&[1, 2, 3]),
+    ];
+    oids.sort();
+
+    let expected = vec![
+        oid(&[1, 2]),
+        oid(&[1, 2, 3]),
+        oid(&[1, 2, 840, 113549]),
+        oid(&[1, 3]),
+        oid(&[2, 5, 4, 3]),
+    ];
+
+    assert_eq!(expected, oids);
+}
+
+#[test]
+fn map_keys() {
+    let sha256 = oid(&[2, 16, 840, 1, 101, 3, 4, 2, 1]);
+    let rsa = oid(&[1, 2, 840, 113549, 1, 1, 1]);
+    let common_name = oid(&[2, 5, 4, 3]);
+
+    let mut tree = BTreeMap::new();
+    let mut hash = HashMap::new();
+
+    for (name, key) in [("sha256", &sha256), ("rsa", &rsa), ("cn", &common_name)].iter() {
+        tree.insert((*key).clone(), *name);
+        hash.insert((*key).clone(), *name);
+    }
+
+    assert_eq!(Some(&"rsa"), tree.get(&rsa));
+    assert_eq!(Some(&"cn"), hash.get(&common_name));
+    assert_eq!(None, tree.get(&oid(&[2, 5, 4, 6])));
+    assert_eq!(vec![&rsa, &common_name, &sha256], tree.keys().collect::<Vec<_>>());
+}
+
+#[test]
+fn arc_conversions() {
+    use std::convert::TryFrom;
+
+    let arcs: Vec<u32> = vec![1, 2, 840, 113549];
+    let rsadsi = ObjectIdentifier::try_from(arcs.clone()).unwrap();
+
+    assert_eq!(&arcs[..], rsadsi.as_arcs());
+    assert_eq!(rsadsi, ObjectIdentifier::try_from(&arcs[..]).unwrap());
+    assert_eq!(arcs, Vec::from(rsadsi));
+
+    assert!(ObjectIdentifier::try_from(vec![1]).is_err());
+    assert!(ObjectIdentifier::try_from(&[][..]).is_err());
+}
+
+#[test]
+fn navigation() {
+    let rsa_encryption = oid(&[1, 2, 840, 113549, 1, 1, 1]);
+
+    assert!(rsa_encryption.starts_with(&oid(&[1, 2, 840])));
+    assert!(rsa_encryption.starts_with(&rsa_encryption));
+    assert!(!rsa_encryption.starts_with(&oid(&[1, 2, 841])));
+    assert!(!oid(&[1, 2, 840]).starts_with(&rsa_encryption));
+
+    assert_eq!(Some(oid(&[1, 2])), oid(&[1, 2, 840]).parent());
+    assert_eq!(None, oid(&[1, 2]).parent());
+
+    let pkcs9 = oid(&[1, 2, 840, 113549, 1, 9]);
+    let email_address = pkcs9.child(1);
+    assert_eq!(oid(&[1, 2, 840, 113549, 1, 9, 1]), email_address);
+    assert_eq!(Some(pkcs9), email_address.parent());
+}
@@ -0,0 +1,59 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::Hasher,
+    io::{self, Read, Write},
+};
+
+use dasn1_core::types::OctetString;
+
+#[test]
+fn write_then_read() {
+    let name = "World";
+    let mut string = OctetString::new();
+    string.write_all(b"Hello, ").unwrap();
+    write!(string, "{}!", name).unwrap();
+
+    assert_eq!(b"Hello, World!", &string[..]);
+
+    let mut read = Vec::new();
+    string.as_read().read_to_end(&mut read).unwrap();
+    assert_eq!(string.into_vec(), read);
+}
+
+/// Feeds everything written into a `Hasher`.
+struct HashWriter<H>(H);
+
+impl<H: Hasher> Write for HashWriter<H> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn hash_through_copy() {
+    let string = OctetString::from((0..=255).collect::<Vec<u8>>());
+
+    let mut writer = HashWriter(DefaultHasher::new());
+    let copied = io::copy(&mut string.as_read(), &mut writer).unwrap();
+
+    let mut expected = DefaultHasher::new();
+    expected.write(&string);
+
+    assert_eq!(256, copied);
+    assert_eq!(expected.finish(), writer.0.finish());
+}
+
+#[test]
+fn byte_conversions() {
+    let bytes: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF];
+    let string = OctetString::from(bytes);
+
+    assert_eq!(bytes, string.as_slice());
+    assert_eq!(string, OctetString::from(bytes.to_vec()));
+    assert_eq!(bytes.to_vec(), Vec::from(string));
+}
@@ -0,0 +1,32 @@
+//! The conversions are only available with the `std-time` feature, which is
+//! checked by running `cargo test -p dasn1-core --features std-time --test system_time`.
+#![cfg(feature = "std-time")]
+
+use std::{
+    convert::TryFrom,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use dasn1_core::types::GeneralizedTime;
+
+#[test]
+fn round_trip() {
+    let time = UNIX_EPOCH + Duration::new(951_782_400 + 3723, 250_000_000);
+    let generalized = GeneralizedTime::try_from(time).unwrap();
+
+    assert_eq!("20000229010203.25Z", &*generalized);
+    assert_eq!(time, SystemTime::try_from(&generalized).unwrap());
+
+    let epoch = GeneralizedTime::try_from(UNIX_EPOCH).unwrap();
+    assert_eq!("19700101000000Z", &*epoch);
+}
+
+#[test]
+fn out_of_range() {
+    let before_epoch = GeneralizedTime::new("19691231235959Z").unwrap();
+    assert!(SystemTime::try_from(&before_epoch).is_err());
+    assert!(GeneralizedTime::try_from(UNIX_EPOCH - Duration::from_secs(1)).is_err());
+
+    let invalid_date = GeneralizedTime::new("20190229000000Z").unwrap();
+    assert!(SystemTime::try_from(&invalid_date).is_err());
+}
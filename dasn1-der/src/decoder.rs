@@ -1,30 +1,50 @@
 mod bit_string;
+mod canonical;
+mod config;
+mod default;
 mod object_identifier;
-mod octet_string;
 mod option;
 mod prefix;
+mod raw_bytes;
+mod reader;
+mod real;
+mod tlv;
+mod walk;
 pub(crate) mod parser;
 
-use std::{fmt, num, result};
+use std::{borrow::Cow, convert::TryFrom, fmt, num, rc::Rc, result, str};
 
-use core::identifier::Identifier;
+use core::{
+    identifier::{Class, Identifier},
+    types::Constrained,
+};
 use num_bigint::BigInt;
 use num_traits::ToPrimitive;
 use serde::{
-    de::{self, Deserialize, DeserializeSeed, EnumAccess, SeqAccess, VariantAccess, Visitor},
+    de::{self, Deserialize, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor},
     forward_to_deserialize_any,
 };
 
 use crate::{
     error::{Error, Result},
     identifier::BerIdentifier,
+    EncodingRules,
 };
 use self::{
     bit_string::BitString,
+    default::DefaultValue,
     object_identifier::ObjectIdentifier,
-    option::IdentifierDeserializer,
-    octet_string::OctetString,
+    option::{Absences, IdentifierDeserializer},
     prefix::Prefix,
+    raw_bytes::RawBytes,
+};
+
+pub use self::{
+    canonical::{canonicalize, is_canonical_der},
+    config::DecoderConfig,
+    reader::{read_one, ContentReader, FrameReader},
+    tlv::{children, split_tlv, Tlv},
+    walk::{walk_tlv, Event},
 };
 
 /// Deserialize an instance of `T` from bytes of ASN.1 DER.
@@ -32,10 +52,132 @@ pub fn from_slice<'a, T>(bytes: &'a [u8]) -> Result<T>
 where
     T: Deserialize<'a>,
 {
-    log::trace!("Starting deserialisation: {:?}", bytes);
+    from_slice_with(bytes, DecoderConfig::default())
+}
+
+/// Deserialize an instance of `T` from bytes of ASN.1, following the
+/// encoding rules set in `config`.
+pub fn from_slice_with<'a, T>(bytes: &'a [u8], config: DecoderConfig) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    log::trace!("Starting deserialisation with {:?}: {:?}", config, bytes);
+
+    // Checked up front as some types, such as `Option`, would otherwise
+    // accept having nothing to decode.
+    if bytes.is_empty() {
+        return Err(Error::UnexpectedEof);
+    }
+
+    let mut deserializer = Deserializer::with_rules(bytes, config.rules);
+
+    loop {
+        match T::deserialize(&mut deserializer) {
+            // An OPTIONAL value was only found to be absent after its `Option`
+            // was committed to holding it, so the value is decoded again.
+            Err(_) if deserializer.absences.restart() => {
+                let absences = deserializer.absences;
+                deserializer = Deserializer { absences, ..Deserializer::with_rules(bytes, config.rules) };
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Deserialize an instance of `T` from bytes of ASN.1 DER, first checking
+/// that the outermost value is tagged with `expected`. Useful for rejecting
+/// the wrong kind of message before decoding any of it, where a protocol's
+/// messages are told apart by their tags.
+pub fn from_slice_expecting<'a, T>(bytes: &'a [u8], expected: Identifier) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let actual = parser::parse_identifier_octet(bytes)?.1.identifier;
+
+    if actual != expected {
+        return Err(Error::IncorrectType { expected, actual });
+    }
+
+    from_slice(bytes)
+}
+
+/// Deserialize an instance of `T` from bytes of ASN.1 DER, then check that
+/// the decoded value satisfies the constraints of its type.
+pub fn from_slice_validated<'a, T>(bytes: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a> + Constrained,
+{
+    let value: T = from_slice(bytes)?;
+    value.validate()?;
+
+    Ok(value)
+}
+
+/// The index of an element that failed to decode, and why.
+pub type ElementError = (usize, Error);
+
+/// Deserialize a SEQUENCE OF `T` from bytes of ASN.1 DER, without stopping at
+/// elements that fail to decode. Returns the elements that were decoded, and
+/// the index and error of each element that wasn't. Elements are skipped
+/// using their length octets, so an error in the framing of the SEQUENCE OF
+/// itself still fails the whole decode.
+pub fn from_slice_lenient<'a, T>(bytes: &'a [u8]) -> Result<(Vec<T>, Vec<ElementError>)>
+where
+    T: Deserialize<'a>,
+{
     let mut deserializer = Deserializer::from_slice(bytes);
+    let mut input = deserializer.parse_value(Some(Identifier::SEQUENCE))?.contents;
+
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    let mut index = 0;
+
+    while !input.is_empty() {
+        let (rest, _) = parser::parse_value(input)?;
+        let element = &input[..input.len() - rest.len()];
+        input = rest;
+
+        match from_slice(element) {
+            Ok(value) => values.push(value),
+            Err(error) => {
+                log::trace!("Skipping element {}: {}", index, error);
+                errors.push((index, error));
+            }
+        }
+
+        index += 1;
+    }
 
-    T::deserialize(&mut deserializer)
+    Ok((values, errors))
+}
+
+/// The result of decoding from a buffer that may not hold a complete value
+/// yet, such as one being filled from a network socket.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeState<T> {
+    /// The decoded value, and the number of bytes of the buffer it used.
+    Complete(T, usize),
+    /// The buffer ends before the value does. Contains how many more bytes
+    /// are needed, if that's known from the identifier and length octets.
+    Incomplete(Option<usize>),
+}
+
+/// Attempts to decode an instance of `T` from the start of `bytes`, returning
+/// `DecodeState::Incomplete` rather than an error if `bytes` only holds part
+/// of the value. Any bytes after the value are left unread.
+pub fn try_decode<'a, T>(bytes: &'a [u8]) -> Result<DecodeState<T>>
+where
+    T: Deserialize<'a>,
+{
+    match parser::parse_value(bytes) {
+        Ok((rest, _)) => {
+            let consumed = bytes.len() - rest.len();
+            Ok(DecodeState::Complete(from_slice(&bytes[..consumed])?, consumed))
+        }
+        Err(nom::Err::Incomplete(nom::Needed::Size(needed))) => Ok(DecodeState::Incomplete(Some(needed))),
+        Err(nom::Err::Incomplete(nom::Needed::Unknown)) => Ok(DecodeState::Incomplete(None)),
+        Err(error) => Err(error.into()),
+    }
 }
 
 /// An untyped ASN.1 value.
@@ -53,14 +195,46 @@ impl<'a> Value<'a> {
 
 pub(crate) struct Deserializer<'de> {
     input: &'de [u8],
-    enumerated: bool,
     type_check: bool,
+    /// The implicit tag of the automatically tagged field being decoded,
+    /// which decides whether an OPTIONAL field is present.
+    field_tag: Option<Identifier>,
+    rules: EncodingRules,
+    /// The struct and fields enclosing the value being decoded, outermost
+    /// first, for pointing errors at the field they came from.
+    path: Vec<&'de str>,
+    /// The fields of the SET whose elements are being decoded, which are
+    /// identified by their tags rather than their position.
+    set_fields: &'static [&'static str],
+    /// The OPTIONAL values known to be absent.
+    absences: Rc<Absences>,
 }
 
 impl<'de> Deserializer<'de> {
     fn from_slice(input: &'de [u8]) -> Self {
+        Self::with_rules(input, EncodingRules::Der)
+    }
+
+    fn with_rules(input: &'de [u8], rules: EncodingRules) -> Self {
         log::trace!("New Deserializer with input: {:?}", input);
-        Self { input, enumerated: false, type_check: true, }
+        Self {
+            input,
+            type_check: true,
+            field_tag: None,
+            rules,
+            path: Vec::new(),
+            set_fields: &[],
+            absences: Rc::default(),
+        }
+    }
+
+    /// A deserializer for the contents of a value, following the same rules.
+    fn nested(&self, input: &'de [u8]) -> Self {
+        Self {
+            path: self.path.clone(),
+            absences: Rc::clone(&self.absences),
+            ..Self::with_rules(input, self.rules)
+        }
     }
 
     /// Looks for the next tag but doesn't advance the slice.
@@ -70,21 +244,45 @@ impl<'de> Deserializer<'de> {
         Ok(identifier)
     }
 
+    /// The position of the SET field named after the next element's tag.
+    fn set_field_index(&self) -> Result<usize> {
+        let identifier = self.peek_at_identifier()?.identifier;
+
+        self.set_fields
+            .iter()
+            .position(|field| crate::identifier::tag_from_field_name(field).map(|(tag, _)| tag) == Some(identifier))
+            .ok_or(Error::UnexpectedField)
+    }
+
     fn _peek_value(&self) -> Result<Value<'de>> {
         Ok(parser::parse_value(self.input)?.1)
     }
 
     fn parse_value(&mut self, expected: Option<Identifier>) -> Result<Value<'de>> {
         log::trace!("Attempting to parse: {:?}", self.input);
-        let (slice, value) = parser::parse_value(self.input)?;
+        let (rest, identifier) = parser::parse_identifier_octet(self.input)?;
+
+        // Checked before looking for the end of the contents, which for an
+        // indefinite length means scanning all of them.
+        if self.rules == EncodingRules::Der && rest.first() == Some(&0x80) {
+            return Err(Error::IndefiniteLengthInDer);
+        }
+
+        let (slice, contents) = parser::parse_contents(rest, identifier.is_constructed)?;
+        let value = Value::new(identifier, contents);
         self.input = slice;
 
         if self.type_check {
             if let Some(expected) = expected {
-                let actual = *value.identifier;
-                if expected != actual {
+                if value.identifier != expected {
+                    let actual = *value.identifier;
                     return Err(Error::IncorrectType { expected, actual })
                 }
+
+                let constructed = value.identifier.is_constructed;
+                if expected_form(expected, self.rules).is_some_and(|form| form != constructed) {
+                    return Err(Error::IncorrectForm { identifier: expected, constructed })
+                }
             }
         } else {
             self.type_check = true;
@@ -119,7 +317,83 @@ impl<'de> Deserializer<'de> {
 
         let value = self.parse_value(expected)?;
 
-        Ok(BigInt::from_signed_bytes_be(&value.contents))
+        integer_contents(value.contents, "INTEGER")
+    }
+
+    /// Parses an INTEGER into a primitive integer type, named `kind` in the
+    /// error if it doesn't fit.
+    fn parse_primitive_integer<T>(&mut self, kind: &str) -> Result<T>
+    where
+        T: TryFrom<i128> + TryFrom<u128>,
+    {
+        let value = self.parse_value(Some(Identifier::INTEGER))?;
+
+        primitive_integer(value.contents)?.ok_or_else(|| Error::IntegerOverflow(kind.into()))
+    }
+
+    /// Parses a value of the string type `identifier`, borrowing its
+    /// contents from the input unless they're split into segments.
+    fn parse_string(&mut self, identifier: Identifier) -> Result<Cow<'de, [u8]>> {
+        let value = self.parse_value(Some(identifier))?;
+
+        string_contents(&value, identifier)
+    }
+
+    /// Decodes an enum named `ASN.1#TaggedSequence` from a SEQUENCE of the
+    /// variant's index as an ENUMERATED, followed by the variant's value if
+    /// it has one.
+    fn deserialize_tagged_sequence<V: Visitor<'de>>(
+        &mut self,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let value = self.parse_value(Some(Identifier::SEQUENCE))?;
+        let mut contents = self.nested(value.contents);
+
+        let index = contents.parse_value(Some(Identifier::ENUMERATED))?;
+        let index = integer_contents(index.contents, "ENUMERATED")?
+            .to_u32()
+            .ok_or_else(|| Error::IntegerOverflow("u32".into()))?;
+        let variant = variants.get(index as usize).ok_or(Error::NoVariantFound(index))?;
+
+        log::trace!("Attempting to deserialise to tagged SEQUENCE variant {}", variant);
+        let value = visitor.visit_enum(Enum::new(variant, &mut contents))?;
+
+        if contents.input.is_empty() {
+            Ok(value)
+        } else {
+            Err(Error::UnexpectedField)
+        }
+    }
+
+    fn deserialize_tagged_str<V: Visitor<'de>>(
+        &mut self,
+        identifier: Identifier,
+        visitor: V,
+    ) -> Result<V::Value> {
+        log::trace!("Deserialising {:?} string.", identifier);
+        visit_str_contents(self.parse_string(identifier)?, visitor)
+    }
+
+    /// BMPStrings are UCS-2, two octets to a character, which is read as
+    /// UTF-16.
+    fn deserialize_bmp_str<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value> {
+        log::trace!("Deserialising BMPString.");
+        let value = self.parse_value(Some(Identifier::BMP_STRING))?;
+
+        if value.contents.len() % 2 != 0 {
+            return Err(Error::IncorrectLength(String::from("BMPString")));
+        }
+
+        let units = value
+            .contents
+            .chunks(2)
+            .map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+        let string: String = std::char::decode_utf16(units)
+            .map(|c| c.unwrap_or(std::char::REPLACEMENT_CHARACTER))
+            .collect();
+
+        visitor.visit_str(&string)
     }
 }
 
@@ -135,13 +409,34 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             Identifier::OCTET_STRING => self.deserialize_bytes(visitor),
             Identifier::NULL => self.deserialize_unit(visitor),
             Identifier::SEQUENCE => self.deserialize_seq(visitor),
+            Identifier::SET => self.deserialize_newtype_struct("ASN.1#SetOf", visitor),
             Identifier::OBJECT_IDENTIFIER => {
                 self.deserialize_newtype_struct("ASN.1#ObjectIdentifier", visitor)
             }
-            // Identifier::REAL,
-            // Identifier::ENUMERATED => self.deserialize_,
-            Identifier::UTF8_STRING => self.deserialize_str(visitor),
-            _ => self.deserialize_seq(visitor),
+            Identifier::REAL => self.deserialize_f64(visitor),
+            Identifier::ENUMERATED => self.deserialize_newtype_struct("ASN.1#Enumerated", visitor),
+            // `str`s are encoded as UniversalStrings holding UTF-8.
+            Identifier::UNIVERSAL_STRING => self.deserialize_str(visitor),
+            Identifier::TELETEX_STRING => {
+                self.deserialize_newtype_struct("ASN.1#TeletexString", visitor)
+            }
+            Identifier::BMP_STRING => self.deserialize_bmp_str(visitor),
+            identifier @ Identifier::UTF8_STRING
+            | identifier @ Identifier::NUMERIC_STRING
+            | identifier @ Identifier::PRINTABLE_STRING
+            | identifier @ Identifier::VIDEOTEX_STRING
+            | identifier @ Identifier::IA5_STRING
+            | identifier @ Identifier::UTC_TIME
+            | identifier @ Identifier::GENERALIZED_TIME
+            | identifier @ Identifier::GRAPHIC_STRING
+            | identifier @ Identifier::VISIBLE_STRING
+            | identifier @ Identifier::GENERAL_STRING => {
+                self.deserialize_tagged_str(identifier, visitor)
+            }
+            // Without a type to go by, any other value (including every
+            // context, application, and private tagged value) is passed on
+            // as its complete encoding, as an `AnyValue` would hold it.
+            _ => self.deserialize_newtype_struct("ASN.1#Any", visitor),
         }
     }
 
@@ -152,102 +447,83 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
     fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising i8.");
-        let value = self.parse_integer(true)?
-                       .to_i8()
-                       .ok_or_else(|| Error::IntegerOverflow("i8".into()))?;
+        let value = self.parse_primitive_integer("i8")?;
 
         visitor.visit_i8(value)
     }
 
     fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising i16.");
-        let value = self.parse_integer(true)?
-                        .to_i16()
-                        .ok_or_else(|| Error::IntegerOverflow("i16".into()))?;
+        let value = self.parse_primitive_integer("i16")?;
 
         visitor.visit_i16(value)
     }
 
     fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising i32.");
-        let value = self.parse_integer(true)?
-                        .to_i32()
-                        .ok_or_else(|| Error::IntegerOverflow("i32".into()))?;
+        let value = self.parse_primitive_integer("i32")?;
 
         visitor.visit_i32(value)
     }
 
     fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising i64.");
-        let value = self.parse_integer(true)?
-                        .to_i64()
-                        .ok_or_else(|| Error::IntegerOverflow("i64".into()))?;
+        let value = self.parse_primitive_integer("i64")?;
 
         visitor.visit_i64(value)
     }
 
     fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising i128.");
-        let value = self.parse_integer(true)?
-                        .to_i128()
-                        .ok_or_else(|| Error::IntegerOverflow("i128".into()))?;
+        let value = self.parse_primitive_integer("i128")?;
 
         visitor.visit_i128(value)
     }
 
     fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising u8.");
-        let value = self.parse_integer(true)?
-                        .to_u8()
-                        .ok_or_else(|| Error::IntegerOverflow("u8".into()))?;
+        let value = self.parse_primitive_integer("u8")?;
 
         visitor.visit_u8(value)
     }
 
     fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising u16.");
-        let value = self.parse_integer(true)?
-                        .to_u16()
-                        .ok_or_else(|| Error::IntegerOverflow("u16".into()))?;
+        let value = self.parse_primitive_integer("u16")?;
 
         visitor.visit_u16(value)
     }
 
     fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising u32.");
-        let value = self.parse_integer(true)?
-                        .to_u32()
-                        .ok_or_else(|| Error::IntegerOverflow("u32".into()))?;
+        let value = self.parse_primitive_integer("u32")?;
 
         visitor.visit_u32(value)
     }
 
     fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising u64.");
-        let value = self.parse_integer(true)?
-                        .to_u64()
-                        .ok_or_else(|| Error::IntegerOverflow("u64".into()))?;
+        let value = self.parse_primitive_integer("u64")?;
 
         visitor.visit_u64(value)
     }
 
     fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising u128.");
-        let value = self.parse_integer(true)?
-                        .to_u128()
-                        .ok_or_else(|| Error::IntegerOverflow("u128".into()))?;
+        let value = self.parse_primitive_integer("u128")?;
 
         visitor.visit_u128(value)
     }
 
-    fn deserialize_f32<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising f32.");
-        unimplemented!() // visitor.visit_u128(self.parse_integer()?)
+        self.deserialize_f64(visitor)
     }
 
-    fn deserialize_f64<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising f64.");
-        unimplemented!() // visitor.visit_u128(self.parse_integer()?)
+        let value = self.parse_value(Some(Identifier::REAL))?;
+        visitor.visit_f64(real::decode_real(value.contents)?)
     }
 
     fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
@@ -257,9 +533,7 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
     fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising str.");
-        let value = self.parse_value(Some(Identifier::UNIVERSAL_STRING))?;
-
-        visitor.visit_str(&*String::from_utf8_lossy(value.contents))
+        visit_str_contents(self.parse_string(Identifier::UNIVERSAL_STRING)?, visitor)
     }
 
     fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
@@ -275,33 +549,43 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising option.");
 
+        if let Some(tag) = self.field_tag.take() {
+            let present = !self.input.is_empty() && self.peek_at_identifier()? == tag;
+            return if present { visitor.visit_some(self) } else { visitor.visit_none() };
+        }
+
+        // Absent trailing components leave nothing left to decode.
+        if self.input.is_empty() {
+            return visitor.visit_none();
+        }
+
+        let (position, absent) = self.absences.next(self.input);
+
+        if absent {
+            return visitor.visit_none();
+        }
+
+        // A NULL is a value like any other, so it's present rather than
+        // `None`, which is how `Option<Null>` tells the two apart.
         let ident = self.peek_at_identifier().map(|i| i.identifier).ok();
-        let mut identifier_de = IdentifierDeserializer::new(ident, self);
-        let result = visitor.visit_some(&mut identifier_de);
-
-        result
-        /*
-        let is_none = if self
-            .peek_at_identifier()
-            .map(|i| i.identifier == Identifier::NULL)
-            .unwrap_or(false)
-        {
-            self.parse_value(Some(Identifier::NULL))?;
-            true
-        } else {
-            self.input.is_empty()
-        };
+        let mut optional = IdentifierDeserializer::new(ident, self);
+        let value = visitor.visit_some(&mut optional);
+        let value = optional.finish(value);
 
-        if is_none {
-            visitor.visit_none()
-        } else {
-            visitor.visit_some(self)
+        if optional.mismatched() {
+            self.absences.insert(position);
         }
-        */
+
+        value
     }
 
     fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &str, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising unit struct {:?}.", name);
+
+        if name == crate::identifier::PHANTOM_DATA {
+            return visitor.visit_unit();
+        }
+
         self.deserialize_unit(visitor)
     }
 
@@ -313,18 +597,80 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         match name {
             "ASN.1#OctetString" => {
                 log::trace!("Deserialising OCTET STRING.");
-                let value = self.parse_value(Some(Identifier::OCTET_STRING))?;
-                visitor.visit_seq(OctetString::new(value.contents))
+                visit_contents(self.parse_string(Identifier::OCTET_STRING)?, visitor)
             }
             "ASN.1#ObjectIdentifier" => {
                 log::trace!("Deserialising OBJECT IDENTIFIER.");
                 let value = self.parse_value(Some(Identifier::OBJECT_IDENTIFIER))?;
-                visitor.visit_seq(ObjectIdentifier::new(value.contents))
+
+                // Every OBJECT IDENTIFIER has at least its root arcs.
+                if value.contents.is_empty() {
+                    return Err(Error::IncorrectLength(String::from("OBJECT IDENTIFIER")));
+                }
+
+                visitor.visit_seq(ObjectIdentifier::new(value.contents, self.rules))
             }
             "ASN.1#BitString" => {
                 log::trace!("Deserialising BIT STRING.");
                 let value = self.parse_value(Some(Identifier::BIT_STRING))?;
-                visitor.visit_seq(BitString::new(value.contents))
+                visitor.visit_seq(BitString::new(value.contents, self.rules)?)
+            }
+            "ASN.1#SetOf" => {
+                log::trace!("Deserialising SET OF.");
+                let value = self.parse_value(Some(Identifier::SET))?;
+
+                if self.rules == EncodingRules::Der {
+                    check_set_of_order(value.contents)?;
+                }
+
+                visitor.visit_seq(Sequence::new(self.nested(value.contents), None))
+            }
+            "ASN.1#NumericString" => self.deserialize_tagged_str(Identifier::NUMERIC_STRING, visitor),
+            "ASN.1#PrintableString" => {
+                self.deserialize_tagged_str(Identifier::PRINTABLE_STRING, visitor)
+            }
+            "ASN.1#IA5String" => self.deserialize_tagged_str(Identifier::IA5_STRING, visitor),
+            "ASN.1#TeletexString" => {
+                log::trace!("Deserialising TeletexString.");
+                visit_contents(self.parse_string(Identifier::TELETEX_STRING)?, visitor)
+            }
+            "ASN.1#UTCTime" => self.deserialize_tagged_str(Identifier::UTC_TIME, visitor),
+            "ASN.1#GeneralizedTime" => {
+                self.deserialize_tagged_str(Identifier::GENERALIZED_TIME, visitor)
+            }
+            "ASN.1#Any" => {
+                log::trace!("Deserialising ANY.");
+                let start = self.input;
+                self.parse_value(None)?;
+                let encoded = &start[..start.len() - self.input.len()];
+
+                // The elements of the value are walked rather than recursed
+                // into, so that it's checked to be well formed however
+                // deeply they're nested.
+                walk_tlv(encoded, &mut |_| Ok(()))?;
+                visitor.visit_borrowed_bytes(encoded)
+            }
+            "ASN.1#Default" => {
+                log::trace!("Deserialising component with a DEFAULT value.");
+                visitor.visit_seq(DefaultValue::new(self))
+            }
+            "ASN.1#WithRawBytes" => {
+                log::trace!("Deserialising value with its encoding.");
+                visitor.visit_seq(RawBytes::new(self))
+            }
+            "ASN.1#Integer" => {
+                log::trace!("Deserialising INTEGER.");
+                let integer = self.parse_integer(true)?;
+                visitor.visit_bytes(&integer.to_signed_bytes_be())
+            }
+            "ASN.1#Enumerated" => {
+                log::trace!("Deserialising ENUMERATED.");
+                let value = self.parse_value(Some(Identifier::ENUMERATED))?;
+                let value = integer_contents(value.contents, "ENUMERATED")?
+                    .to_i64()
+                    .ok_or_else(|| Error::IntegerOverflow("i64".into()))?;
+
+                visitor.visit_i64(value)
             }
             "ASN.1#Implicit" => {
                 log::trace!("Using implicit deserialisation.");
@@ -336,11 +682,6 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             }
             name => {
                 log::trace!("Deserialising newtype struct {:?}.", name);
-
-                if name == "ASN.1#Enumerated" {
-                    log::trace!("Enabled ENUMERATED decoding");
-                    self.enumerated = true;
-                }
                 visitor.visit_newtype_struct(self)
             }
         }
@@ -348,7 +689,17 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
     fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising {} length tuple.", len);
-        self.deserialize_seq(visitor)
+        let value = self.parse_value(Some(Identifier::SEQUENCE))?;
+        let mut sequence = Sequence::new(self.nested(value.contents), None);
+        let tuple = visitor.visit_seq(&mut sequence)?;
+
+        // Tuples and arrays have a fixed length, so any elements left over
+        // mean the SEQUENCE doesn't match the type.
+        if sequence.de.input.is_empty() {
+            Ok(tuple)
+        } else {
+            Err(Error::IncorrectLength(format!("{} element tuple", len)))
+        }
     }
 
     fn deserialize_tuple_struct<V: Visitor<'de>>(
@@ -363,24 +714,58 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
     fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising map.");
-        self.deserialize_seq(visitor)
+        let value = self.parse_value(Some(Identifier::SEQUENCE))?;
+        visitor.visit_map(Map::new(self.nested(value.contents)))
     }
 
     fn deserialize_struct<V: Visitor<'de>>(
         self,
-        name: &str,
-        fields: &[&str],
+        name: &'static str,
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
         log::trace!("Deserialising struct {:?} with fields {:?}.", name, fields);
-        let value = self.parse_value(Some(Identifier::SEQUENCE))?;
-        visitor.visit_seq(Sequence::new(value.contents, fields.len()))
+        let (name, extensible) = crate::identifier::strip_extension_marker(name);
+        let is_set = name == crate::identifier::SET_STRUCT;
+        let expected = match crate::identifier::tag_from_struct_name(name) {
+            Some(tag) => tag,
+            None if is_set => Identifier::SET,
+            None => Identifier::SEQUENCE,
+        };
+        let value = self.parse_value(Some(expected))?;
+        let mut sequence = Sequence::new(self.nested(value.contents), fields.len());
+        sequence.fields = fields;
+
+        // Nested structs are named by the field holding them.
+        if sequence.de.path.is_empty() {
+            sequence.de.path.extend(crate::identifier::path_name(name));
+        }
+
+        if name == crate::identifier::AUTOMATIC_STRUCT {
+            sequence.next_field_tag = Some(0);
+        } else {
+            sequence.field_tags = fields.iter().map(|f| crate::identifier::tag_from_field_name(f)).collect();
+        }
+
+        let value = if is_set {
+            sequence.de.set_fields = fields;
+            sequence.extensible = extensible;
+            visitor.visit_map(&mut sequence)?
+        } else {
+            visitor.visit_seq(&mut sequence)?
+        };
+
+        if extensible || sequence.de.input.is_empty() {
+            Ok(value)
+        } else {
+            Err(Error::UnexpectedField)
+        }
     }
 
     fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         log::trace!("Deserialising sequence.");
         let value = self.parse_value(Some(Identifier::SEQUENCE))?;
-        visitor.visit_seq(Sequence::new(value.contents, None))
+        visitor.visit_seq(Sequence::new(self.nested(value.contents), None))
     }
 
     fn deserialize_enum<V>(
@@ -398,13 +783,13 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             variants
         );
 
-        let variant_index = if self.enumerated {
-            self.enumerated = false;
-            self.parse_integer(false)?.to_u32().unwrap()
-        } else {
-            let identifier = self.peek_at_identifier()?;
-            identifier.tag
-        };
+        if name == crate::identifier::TAGGED_SEQUENCE_ENUM {
+            return self.deserialize_tagged_sequence(variants, visitor);
+        }
+
+        let identifier = self.peek_at_identifier()?.identifier;
+        let variant_index = choice_variant(identifier, variants)
+            .ok_or(Error::NoVariantFound(identifier.tag))? as u32;
 
         let variant = variants.get(variant_index as usize)
                               .ok_or(Error::NoVariantFound(variant_index))?;
@@ -418,7 +803,13 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         V: Visitor<'de>,
     {
         log::trace!("Deserialising unit");
-        self.parse_value(Some(Identifier::NULL))?;
+        let value = self.parse_value(Some(Identifier::NULL))?;
+
+        // A NULL has no contents (X.690 8.8.2).
+        if !value.contents.is_empty() {
+            return Err(Error::IncorrectLength(String::from("NULL")));
+        }
+
         visitor.visit_unit()
     }
 
@@ -427,26 +818,61 @@ impl<'a, 'de> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         V: Visitor<'de>,
     {
         log::trace!("Deserialising bytes");
-        let value = self.parse_value(Some(Identifier::OCTET_STRING))?;
-        visitor.visit_seq(OctetString::new(value.contents))
+        visit_contents(self.parse_string(Identifier::OCTET_STRING)?, visitor)
+    }
+
+    /// Identifies the SET field the next element is for by its tag, as the
+    /// elements of a SET can be in any order.
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.set_fields.is_empty() {
+            return self.deserialize_any(visitor);
+        }
+
+        let index = self.set_field_index()?;
+        log::trace!("Identified SET field {}", self.set_fields[index]);
+        visitor.visit_str(self.set_fields[index])
     }
 
     forward_to_deserialize_any! {
-        ignored_any identifier
+        ignored_any
     }
 }
 
 struct Sequence<'de> {
     de: Deserializer<'de>,
     elements: Option<usize>,
+    /// The position of the next element.
+    index: usize,
+    /// The number of the context-specific tag of the next element, if the
+    /// elements are automatically tagged.
+    next_field_tag: Option<u32>,
+    /// The tags of fields renamed after one (e.g. `ASN.1#[0]`), by position,
+    /// and whether each tag is explicit.
+    field_tags: Vec<Option<(Identifier, bool)>>,
+    /// The names of the fields, if the elements are those of a struct.
+    fields: &'static [&'static str],
+    /// Whether elements with tags that aren't any field's are skipped, if
+    /// the elements are those of a SET.
+    extensible: bool,
+    /// The tag of the previous element of a SET, which DER requires to come
+    /// before the next one's.
+    previous_tag: Option<Identifier>,
 }
 
 impl<'de> Sequence<'de> {
-    fn new<I: Into<Option<usize>>>(input: &'de [u8], elements: I) -> Self {
-        let de = Deserializer::from_slice(input);
+    fn new<I: Into<Option<usize>>>(de: Deserializer<'de>, elements: I) -> Self {
         let elements = elements.into();
 
-        Self { de, elements }
+        Self {
+            de,
+            elements,
+            index: 0,
+            next_field_tag: None,
+            field_tags: Vec::new(),
+            fields: &[],
+            extensible: false,
+            previous_tag: None,
+        }
     }
 }
 
@@ -467,10 +893,376 @@ impl<'de> SeqAccess<'de> for Sequence<'de> {
             return Ok(None);
         }
 
-        seed.deserialize(&mut self.de).map(Some)
+        let index = self.index;
+        self.index += 1;
+
+        self.next_field(index, seed).map(Some)
+    }
+}
+
+/// The elements of a SET, keyed by the fields their tags are named after.
+impl<'de> MapAccess<'de> for Sequence<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        while !self.de.input.is_empty() {
+            let tag = self.de.peek_at_identifier()?.identifier;
+            let order = |tag: Identifier| (tag.class, tag.tag);
+
+            if self.de.rules == EncodingRules::Der && self.previous_tag.is_some_and(|p| order(p) > order(tag)) {
+                return Err(Error::UnsortedSet);
+            }
+
+            self.previous_tag = Some(tag);
+
+            match self.de.set_field_index() {
+                Ok(_) => return seed.deserialize(&mut self.de).map(Some),
+                // Elements added in later versions of an extensible SET are
+                // skipped, as they would be at the end of a SEQUENCE.
+                Err(Error::UnexpectedField) if self.extensible => {
+                    self.de.parse_value(None)?;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let index = self.de.set_field_index()?;
+        self.next_field(index, seed)
+    }
+}
+
+impl<'de> Sequence<'de> {
+    /// Decodes the element for the field at `index`, pointing any error at
+    /// the field.
+    fn next_field<T>(&mut self, index: usize, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        // Absent optional fields decode from nothing, so running out of
+        // elements only means a field is missing if it's required.
+        let exhausted = self.de.input.is_empty();
+        let field = self.fields.get(index).and_then(|name| crate::identifier::path_name(name));
+        self.de.path.extend(field);
+        let element = self.next_element(index, seed);
+
+        let element = match element {
+            Err(Error::UnexpectedEof) if exhausted => Err(Error::MissingField { index }),
+            // Errors from structs within the field already have their path.
+            Err(error @ Error::Field { .. }) => Err(error),
+            Err(error) if field.is_some() => {
+                Err(Error::Field { path: self.de.path.join("."), error: Box::new(error) })
+            }
+            element => element,
+        };
+
+        if field.is_some() {
+            self.de.path.pop();
+        }
+
+        element
+    }
+
+    /// Decodes the element at `index`. A tagged field is only present if the
+    /// next element has its tag, so an absent OPTIONAL field leaves the
+    /// element for the fields after it.
+    fn next_element<T>(&mut self, index: usize, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let (tag, explicit) = match self.next_field_tag {
+            Some(number) => {
+                self.next_field_tag = Some(number + 1);
+                (Identifier::new(Class::Context, number), false)
+            }
+            None => match self.field_tags.get(index) {
+                Some(Some(tag)) => *tag,
+                _ => return seed.deserialize(&mut self.de),
+            },
+        };
+
+        let present = !self.de.input.is_empty() && self.de.peek_at_identifier()? == tag;
+
+        // An explicit tag is always constructed, and holds the complete
+        // encoding of the value (X.690 8.14.3).
+        if explicit && present {
+            let value = self.de.parse_value(Some(tag))?;
+
+            if !value.identifier.is_constructed {
+                return Err(Error::IncorrectForm { identifier: tag, constructed: false });
+            }
+
+            return seed.deserialize(&mut self.de.nested(value.contents));
+        }
+
+        // The type's own tag is only checked if the field's tag is missing,
+        // so that a required field reports the mismatch.
+        self.de.type_check = !present;
+        self.de.field_tag = Some(tag);
+
+        let element = seed.deserialize(&mut self.de);
+        self.de.type_check = true;
+        self.de.field_tag = None;
+
+        element
+    }
+}
+
+/// A `SEQUENCE OF SEQUENCE { key, value }`, accessed as a map.
+struct Map<'de> {
+    de: Deserializer<'de>,
+    entry: Option<Deserializer<'de>>,
+}
+
+impl<'de> Map<'de> {
+    fn new(de: Deserializer<'de>) -> Self {
+        Self { de, entry: None }
+    }
+}
+
+impl<'de> MapAccess<'de> for Map<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.de.input.is_empty() {
+            return Ok(None);
+        }
+
+        let contents = self.de.parse_value(Some(Identifier::SEQUENCE))?.contents;
+        let entry = self.entry.insert(self.de.nested(contents));
+
+        seed.deserialize(entry).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let mut entry = self
+            .entry
+            .take()
+            .ok_or_else(|| Error::Custom(String::from("Map value requested before its key.")))?;
+
+        let value = seed.deserialize(&mut entry)?;
+
+        if entry.input.is_empty() {
+            Ok(value)
+        } else {
+            Err(Error::IncorrectLength(String::from("map entry")))
+        }
     }
 }
 
+/// Reads the contents of an INTEGER or ENUMERATED, which must be at least one
+/// octet long. Unlike some decoders this is also enforced for BER, as X.690
+/// (8.3.1) requires it regardless of the encoding rules.
+fn integer_contents(contents: &[u8], kind: &str) -> Result<BigInt> {
+    if contents.is_empty() {
+        Err(Error::IncorrectLength(String::from(kind)))
+    } else {
+        Ok(BigInt::from_signed_bytes_be(contents))
+    }
+}
+
+/// Reads the contents of an INTEGER into a primitive integer type, which is
+/// `None` if it doesn't fit. Unlike `integer_contents` this doesn't allocate,
+/// which matters for long SEQUENCE OFs of small integers.
+fn primitive_integer<T>(contents: &[u8]) -> Result<Option<T>>
+where
+    T: TryFrom<i128> + TryFrom<u128>,
+{
+    let mut contents = match contents.first() {
+        Some(_) => contents,
+        None => return Err(Error::IncorrectLength(String::from("INTEGER"))),
+    };
+
+    // Octets that only repeat the sign bit don't change the value, and BER
+    // doesn't require them to be left out.
+    while let [first, second, ..] = *contents {
+        let redundant = (first == 0 && second & 0x80 == 0) || (first == 0xFF && second & 0x80 != 0);
+
+        if !redundant {
+            break;
+        }
+
+        contents = &contents[1..];
+    }
+
+    Ok(match contents.len() {
+        0..=16 => {
+            let sign = if contents[0] & 0x80 != 0 { -1 } else { 0 };
+            let value = contents.iter().fold(sign, |value: i128, &octet| value << 8 | i128::from(octet));
+            T::try_from(value).ok()
+        }
+        // Only the largest `u128`s need a leading zero octet on top of
+        // their sixteen octets.
+        17 if contents[0] == 0 => {
+            let value = contents[1..].iter().fold(0, |value: u128, &octet| value << 8 | u128::from(octet));
+            T::try_from(value).ok()
+        }
+        _ => None,
+    })
+}
+
+/// The contents of a string, which BER allows to be split into segments of
+/// the string's universal type (X.690 8.7.3, 8.23.6). Primitive contents are
+/// borrowed, while segments are joined into a new buffer.
+fn string_contents<'de>(value: &Value<'de>, identifier: Identifier) -> Result<Cow<'de, [u8]>> {
+    if !value.identifier.is_constructed {
+        return Ok(Cow::Borrowed(value.contents));
+    }
+
+    let mut contents = Vec::new();
+
+    // Segments can be nested to any depth, so they're walked rather than
+    // recursed into.
+    walk_tlv(value.contents, &mut |event| match event {
+        Event::Enter(actual, _) | Event::Primitive(actual, _) if actual != identifier => {
+            Err(Error::IncorrectType { expected: identifier, actual })
+        }
+        Event::Primitive(_, segment) => {
+            contents.extend_from_slice(segment);
+            Ok(())
+        }
+        _ => Ok(()),
+    })?;
+
+    Ok(Cow::Owned(contents))
+}
+
+fn visit_contents<'de, V: Visitor<'de>>(contents: Cow<'de, [u8]>, visitor: V) -> Result<V::Value> {
+    match contents {
+        Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+        Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+    }
+}
+
+/// Visits the contents as a string, replacing any invalid UTF-8. Only valid
+/// primitive contents can be borrowed.
+fn visit_str_contents<'de, V: Visitor<'de>>(contents: Cow<'de, [u8]>, visitor: V) -> Result<V::Value> {
+    match contents {
+        Cow::Borrowed(bytes) => match str::from_utf8(bytes) {
+            Ok(string) => visitor.visit_borrowed_str(string),
+            Err(_) => visitor.visit_string(String::from_utf8_lossy(bytes).into_owned()),
+        },
+        Cow::Owned(bytes) => visitor.visit_string(match String::from_utf8(bytes) {
+            Ok(string) => string,
+            Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+        }),
+    }
+}
+
+/// Checks the elements in the contents of a SET OF are sorted by their
+/// encodings, as DER requires (X.690 11.6). Elements with the same encoding
+/// may appear in either order, so they're allowed.
+fn check_set_of_order(mut contents: &[u8]) -> Result<()> {
+    let mut previous: Option<&[u8]> = None;
+
+    while !contents.is_empty() {
+        let rest = parser::parse_value(contents)?.0;
+        let element = &contents[..contents.len() - rest.len()];
+
+        if previous.is_some_and(|previous| previous > element) {
+            return Err(Error::UnsortedSetOf);
+        }
+
+        previous = Some(element);
+        contents = rest;
+    }
+
+    Ok(())
+}
+
+/// Whether a universal type must use the constructed (`true`) or primitive
+/// (`false`) form under `rules`, or `None` if either is allowed. BER lets
+/// strings be split into constructed segments (X.690 8.23), while DER
+/// requires all of them to be primitive (10.2).
+fn expected_form(identifier: Identifier, rules: EncodingRules) -> Option<bool> {
+    match identifier {
+        Identifier::BOOL
+        | Identifier::INTEGER
+        | Identifier::NULL
+        | Identifier::OBJECT_IDENTIFIER
+        | Identifier::REAL
+        | Identifier::ENUMERATED
+        | Identifier::RELATIVE_OID => Some(false),
+        Identifier::EXTERNAL
+        | Identifier::EMBEDDED_PDV
+        | Identifier::SEQUENCE
+        | Identifier::SET
+        | Identifier::CHARACTER_STRING => Some(true),
+        Identifier::BIT_STRING
+        | Identifier::OCTET_STRING
+        | Identifier::OBJECT_DESCRIPTOR
+        | Identifier::UTF8_STRING
+        | Identifier::NUMERIC_STRING
+        | Identifier::PRINTABLE_STRING
+        | Identifier::TELETEX_STRING
+        | Identifier::VIDEOTEX_STRING
+        | Identifier::IA5_STRING
+        | Identifier::UTC_TIME
+        | Identifier::GENERALIZED_TIME
+        | Identifier::GRAPHIC_STRING
+        | Identifier::VISIBLE_STRING
+        | Identifier::GENERAL_STRING
+        | Identifier::UNIVERSAL_STRING
+        | Identifier::BMP_STRING if rules == EncodingRules::Der => Some(false),
+        _ => None,
+    }
+}
+
+/// Finds the index of the CHOICE variant a value with `identifier` decodes
+/// as, if there is one.
+pub(crate) fn choice_variant(identifier: Identifier, variants: &[&str]) -> Option<usize> {
+    let tag = identifier.tag;
+    let is_untagged = |variant: &&str| variant.starts_with(UNTAGGED_VARIANT);
+    let named_tag = |variant: &&str| crate::identifier::tag_from_struct_name(variant);
+    let named_variant = variants.iter().position(|v| named_tag(v) == Some(identifier));
+
+    match (named_variant, variants.get(tag as usize)) {
+        (Some(index), _) => Some(index),
+        // Otherwise the context-specific tag number is the variant's
+        // index, for variants that aren't named after another tag.
+        (None, Some(variant))
+            if identifier.class == Class::Context
+                && !is_untagged(variant)
+                && named_tag(variant).is_none() => Some(tag as usize),
+        // Tags not claimed by a tagged alternative are delegated to
+        // the untagged alternative, which must be unambiguous.
+        _ => {
+            let mut untagged = variants.iter().enumerate().filter(|(_, v)| is_untagged(v));
+
+            match (untagged.next(), untagged.next()) {
+                (Some((index, _)), None) => Some(index),
+                // Failing that, they're kept as they are by the unknown
+                // alternative, if there is one.
+                _ => variants.iter().position(|v| v.starts_with(UNKNOWN_VARIANT)),
+            }
+        }
+    }
+}
+
+/// The prefix of a CHOICE variant's name marking it as untagged, such as a
+/// variant containing another CHOICE, which is matched by the inner type's tags.
+const UNTAGGED_VARIANT: &str = "ASN.1#Untagged";
+
+/// The prefix of a CHOICE variant's name marking it as the fallback for tags
+/// not matching any other variant. The variant should contain an `AnyValue`,
+/// which holds the unrecognised value's complete encoding.
+const UNKNOWN_VARIANT: &str = "ASN.1#Unknown";
+
 struct Enum<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     variant: &'static str,
@@ -509,6 +1301,10 @@ impl<'a, 'de> VariantAccess<'de> for Enum<'a, 'de> {
     where
         T: DeserializeSeed<'de>,
     {
+        if crate::identifier::tag_from_struct_name(self.variant).is_some() {
+            self.de.type_check = false;
+        }
+
         seed.deserialize(self.de)
     }
 
@@ -575,6 +1371,62 @@ mod tests {
     use typenum::consts::*;
     use serde_derive::Deserialize;
 
+    /// A self-describing value, built from whatever `deserialize_any` finds.
+    #[derive(Debug, PartialEq)]
+    enum Value {
+        Bool(bool),
+        Integer(i64),
+        Bytes(Vec<u8>),
+        String(String),
+        Null,
+        Sequence(Vec<Value>),
+    }
+
+    impl<'de> serde::Deserialize<'de> for Value {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(ValueVisitor)
+        }
+    }
+
+    struct ValueVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+        type Value = Value;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("any value")
+        }
+
+        fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+            Ok(Value::Bool(v))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+            Ok(Value::Integer(v))
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+            Ok(Value::Bytes(v.to_vec()))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+            Ok(Value::String(v.to_owned()))
+        }
+
+        fn visit_unit<E>(self) -> Result<Value, E> {
+            Ok(Value::Null)
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+            let mut values = Vec::new();
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+
+            Ok(Value::Sequence(values))
+        }
+    }
+
     #[test]
     fn bool() {
         let yes: bool = super::from_slice(&[0x1, 0x1, 0xFF][..]).unwrap();
@@ -584,6 +1436,22 @@ mod tests {
         assert!(!no);
     }
 
+    #[test]
+    fn decimal_real() {
+        let nr1: f64 = from_slice(b"\x09\x04\x01-42").unwrap();
+        let nr2: f64 = from_slice(b"\x09\x06\x02 0,25").unwrap();
+        let nr3: f64 = from_slice(b"\x09\x07\x033.14E0").unwrap();
+
+        assert_eq!(-42.0, nr1);
+        assert_eq!(0.25, nr2);
+        assert_eq!("3.14".parse::<f64>().unwrap(), nr3);
+        assert_eq!(1.5e-3, from_slice::<f64>(b"\x09\x06\x0315E-4").unwrap());
+
+        // NR1 is an integer, and NR3 needs an exponent.
+        assert!(from_slice::<f64>(b"\x09\x04\x011.5").is_err());
+        assert!(from_slice::<f64>(b"\x09\x04\x033.1").is_err());
+    }
+
     #[test]
     fn choice() {
         #[derive(Clone, Debug, Deserialize, PartialEq)]
@@ -605,6 +1473,93 @@ mod tests {
         assert_eq!(array, from_slice::<[u8; 4]>(&raw).unwrap());
     }
 
+    #[test]
+    fn empty_contents() {
+        assert!(from_slice::<u8>(&[0x2, 0]).is_err());
+        assert!(from_slice::<i64>(&[0x2, 0]).is_err());
+        assert!(from_slice::<Integer>(&[0x2, 0]).is_err());
+        assert!(from_slice::<Integer>(&[0x2, 1, 0xff]).is_ok());
+        assert!(from_slice::<bool>(&[0x1, 0]).is_err());
+        assert!(from_slice::<ObjectIdentifier>(&[0x6, 0]).is_err());
+
+        // Other types can legitimately be empty.
+        assert!(from_slice::<OctetString>(&[0x4, 0]).unwrap().is_empty());
+        assert!(from_slice::<Vec<u8>>(&[0x30, 0]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fixed_array_round_trip() {
+        let mut array = [0u16; 32];
+
+        for (i, element) in array.iter_mut().enumerate() {
+            *element = (i as u16) << 8;
+        }
+
+        let encoded = crate::to_vec(&array).unwrap();
+        assert_eq!(array, from_slice::<[u16; 32]>(&encoded).unwrap());
+    }
+
+    #[test]
+    fn long_array_round_trip() {
+        #[derive(Debug, Deserialize, PartialEq, serde_derive::Serialize)]
+        struct Digests {
+            #[serde(with = "core::types::array")]
+            digests: [u8; 40],
+        }
+
+        let mut digests = Digests { digests: [0; 40] };
+
+        for (i, element) in digests.digests.iter_mut().enumerate() {
+            *element = i as u8;
+        }
+
+        let encoded = crate::to_vec(&digests).unwrap();
+        assert_eq!(&[0x30, 122, 0x30, 120, 2, 1, 0][..], &encoded[..7]);
+        assert_eq!(digests, from_slice(&encoded).unwrap());
+
+        // One element short, and one too many.
+        let mut short = encoded.clone();
+        short.truncate(short.len() - 3);
+        short[1] -= 3;
+        short[3] -= 3;
+        assert!(from_slice::<Digests>(&short).is_err());
+
+        let mut long = encoded;
+        long.extend_from_slice(&[2, 1, 40]);
+        long[1] += 3;
+        long[3] += 3;
+        assert!(from_slice::<Digests>(&long).is_err());
+    }
+
+    #[test]
+    fn deeply_nested_any_value() {
+        const DEPTH: usize = 10_000;
+
+        let mut encoded = Vec::new();
+        for _ in 0..DEPTH {
+            let mut header = vec![0x30];
+            core::length::encode_length(encoded.len(), &mut header).unwrap();
+            header.append(&mut encoded);
+            encoded = header;
+        }
+
+        let any: core::types::AnyValue = from_slice(&encoded).unwrap();
+        assert_eq!(&encoded[..], any.as_ref());
+
+        // An element running past the end of the SEQUENCE around it.
+        let malformed = [0x30, 0x03, 0x04, 0x02, 0x00, 0x00];
+        assert!(from_slice::<core::types::AnyValue>(&malformed).is_err());
+    }
+
+    #[test]
+    fn fixed_array_length_mismatch() {
+        let raw = &[48, 3 * 3, 2, 1, 8, 2, 1, 8, 2, 1, 8][..];
+
+        assert!(from_slice::<[u8; 2]>(raw).is_err());
+        assert!(from_slice::<[u8; 4]>(raw).is_err());
+        assert!(from_slice::<[u8; 3]>(raw).is_ok());
+    }
+
     #[test]
     fn choice_newtype_variant() {
         #[derive(Clone, Debug, Deserialize, PartialEq)]
@@ -619,6 +1574,423 @@ mod tests {
         assert_eq!(Foo::Baz(Implicit::new(os)), from_slice(&[0x81, 5, 1, 2, 3, 4, 5][..]).unwrap());
     }
 
+    #[test]
+    fn large_octet_string() {
+        let data: Vec<u8> = (0..1 << 20).map(|i: u32| (i % 251) as u8).collect();
+
+        let mut raw = vec![0x4, 0x83, 0x10, 0x00, 0x00];
+        raw.extend_from_slice(&data);
+
+        let os: OctetString = from_slice(&raw).unwrap();
+        assert_eq!(data, os.into_vec());
+
+        let bytes: &[u8] = from_slice(&raw).unwrap();
+        assert_eq!(&*data, bytes);
+    }
+
+    #[test]
+    fn truncated_input() {
+        fn is_eof<T>(result: crate::Result<T>) -> bool {
+            matches!(result, Err(crate::error::Error::UnexpectedEof))
+        }
+
+        assert!(is_eof(from_slice::<bool>(&[])));
+        assert!(is_eof(from_slice::<()>(&[])));
+        assert!(is_eof(from_slice::<Option<u8>>(&[])));
+        assert!(is_eof(from_slice::<Vec<u8>>(&[])));
+        // A lone identifier with no length octet.
+        assert!(is_eof(from_slice::<bool>(&[0x1])));
+        // A long form length missing its length octets.
+        assert!(is_eof(from_slice::<u32>(&[0x2, 0x82, 0x1])));
+        // A length claiming more contents than there are.
+        assert!(is_eof(from_slice::<u32>(&[0x2, 0x4, 0x1, 0x2])));
+    }
+
+    #[test]
+    fn incremental_decoding() {
+        use super::{try_decode, DecodeState};
+
+        let raw = &[0x30, 6, 0x1, 0x1, 0xff, 0x2, 0x1, 5, 0x4][..];
+
+        assert_eq!(DecodeState::Incomplete(Some(1)), try_decode::<(bool, u8)>(&[]).unwrap());
+        assert_eq!(DecodeState::Incomplete(Some(6)), try_decode::<(bool, u8)>(&raw[..2]).unwrap());
+
+        let mut end = 0;
+        let state = loop {
+            match try_decode::<(bool, u8)>(&raw[..end]).unwrap() {
+                DecodeState::Incomplete(Some(needed)) => {
+                    assert!(end + needed <= 8);
+                    end += 1;
+                }
+                state => break state,
+            }
+        };
+
+        assert_eq!(8, end);
+        assert_eq!(DecodeState::Complete((true, 5), 8), state);
+        assert!(try_decode::<u8>(&[0x1, 0x1, 0xff]).is_err());
+    }
+
+    #[test]
+    fn lenient_sequence_of() {
+        use crate::error::Error;
+
+        let raw = &[0x30, 13, 0x2, 0x1, 1, 0x1, 0x1, 0xff, 0x2, 0x1, 3, 0x2, 0x2, 0x1, 0x2c][..];
+
+        let (values, errors) = super::from_slice_lenient::<u8>(raw).unwrap();
+
+        assert_eq!(vec![1, 3], values);
+        assert_eq!(2, errors.len());
+
+        match errors[0] {
+            (1, Error::IncorrectType { .. }) => {}
+            ref error => panic!("Unexpected error: {:?}", error),
+        }
+
+        match errors[1] {
+            (3, Error::IntegerOverflow(_)) => {}
+            ref error => panic!("Unexpected error: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn constructed_bit_ignored_by_type_check() {
+        use core::identifier::Identifier;
+
+        let raw = &[0x24, 3, 0x4, 1, 5][..];
+        let mut de = super::Deserializer::with_rules(raw, crate::EncodingRules::Ber);
+        let value = de.parse_value(Some(Identifier::OCTET_STRING)).unwrap();
+
+        assert!(value.identifier.is_constructed);
+        assert_eq!(value.identifier, Identifier::OCTET_STRING);
+        assert!(value.identifier != Identifier::SEQUENCE);
+    }
+
+    #[test]
+    fn constructed_form_checked() {
+        use crate::{decoder::from_slice_with, error::Error, DecoderConfig, EncodingRules};
+
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+
+        // An INTEGER and a SEQUENCE with their constructed bit flipped.
+        let integer = [0x22, 3, 0x2, 1, 5];
+        let sequence = [0x10, 3, 0x2, 1, 5];
+
+        for config in &[DecoderConfig::new(), ber] {
+            match from_slice_with::<i32>(&integer, *config) {
+                Err(Error::IncorrectForm { constructed: true, .. }) => {}
+                result => panic!("Expected IncorrectForm, found {:?}", result),
+            }
+            match from_slice_with::<(i32,)>(&sequence, *config) {
+                Err(Error::IncorrectForm { constructed: false, .. }) => {}
+                result => panic!("Expected IncorrectForm, found {:?}", result),
+            }
+        }
+
+        // A constructed OCTET STRING is only allowed in BER.
+        let octet_string = [0x24, 0];
+        assert!(from_slice::<OctetString>(&octet_string).is_err());
+        assert!(from_slice_with::<OctetString>(&octet_string, ber).is_ok());
+    }
+
+    #[test]
+    fn borrowed_cow() {
+        use std::borrow::Cow;
+        use crate::{decoder::from_slice_with, DecoderConfig, EncodingRules};
+
+        #[derive(Debug, Deserialize)]
+        struct Message<'a> {
+            #[serde(borrow)]
+            bytes: Cow<'a, [u8]>,
+            #[serde(borrow)]
+            text: Cow<'a, str>,
+        }
+
+        let primitive = [0x30, 0x9, 0x4, 0x3, 1, 2, 3, 0x1C, 0x2, b'h', b'i'];
+        let message: Message = from_slice(&primitive).unwrap();
+
+        assert!(matches!(message.bytes, Cow::Borrowed(&[1, 2, 3])));
+        assert!(matches!(message.text, Cow::Borrowed("hi")));
+
+        // The same values split into segments, one of them nested.
+        let segmented = [
+            0x30, 0x80,
+            0x24, 0x80, 0x4, 0x2, 1, 2, 0x24, 0x3, 0x4, 0x1, 3, 0, 0,
+            0x3C, 0x6, 0x1C, 0x1, b'h', 0x1C, 0x1, b'i',
+            0, 0,
+        ];
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        let message: Message = from_slice_with(&segmented, ber).unwrap();
+
+        assert!(matches!(message.bytes, Cow::Owned(ref bytes) if bytes == &[1, 2, 3]));
+        assert!(matches!(message.text, Cow::Owned(ref text) if text == "hi"));
+
+        // Segments have to be of the string's own type.
+        let mixed = [0x24, 0x6, 0x4, 0x1, 1, 0x1C, 0x1, 2];
+        assert!(from_slice_with::<OctetString>(&mixed, ber).is_err());
+        assert_eq!(
+            OctetString::from(vec![1, 2]),
+            from_slice_with::<OctetString>(&[0x24, 0x6, 0x4, 0x1, 1, 0x4, 0x1, 2], ber).unwrap()
+        );
+    }
+
+    #[test]
+    fn deeply_nested_segments() {
+        use crate::{decoder::from_slice_with, DecoderConfig, EncodingRules};
+
+        const DEPTH: usize = 200_000;
+
+        // An OCTET STRING holding a single octet, inside segments nested far
+        // deeper than the stack could be recursed into.
+        let mut bytes = [0x24, 0x80].repeat(DEPTH);
+        bytes.extend_from_slice(&[0x4, 0x1, 0xAB]);
+        bytes.extend(std::iter::repeat_n(0, 2 * DEPTH));
+
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        assert_eq!(OctetString::from(vec![0xAB]), from_slice_with::<OctetString>(&bytes, ber).unwrap());
+    }
+
+    #[test]
+    fn null_parameters() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct AlgorithmIdentifier {
+            algorithm: ObjectIdentifier,
+            parameters: Option<Null>,
+        }
+
+        let algorithm = ObjectIdentifier::new(vec![1, 2, 840, 113549, 1, 1, 11]).unwrap();
+        let oid = [0x6, 0x9, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x1, 0x1, 0xB];
+
+        let mut present = vec![0x30, 0xD];
+        present.extend_from_slice(&oid);
+        present.extend_from_slice(&[0x5, 0x0]);
+
+        let mut absent = vec![0x30, 0xB];
+        absent.extend_from_slice(&oid);
+
+        assert_eq!(
+            AlgorithmIdentifier { algorithm: algorithm.clone(), parameters: Some(Null) },
+            from_slice(&present).unwrap()
+        );
+        assert_eq!(
+            AlgorithmIdentifier { algorithm, parameters: None },
+            from_slice(&absent).unwrap()
+        );
+        assert_eq!(Null, from_slice(&[0x5, 0x0]).unwrap());
+        assert!(from_slice::<Null>(&[0x5, 0x1, 0x0]).is_err());
+    }
+
+    #[test]
+    fn non_minimal_subidentifier() {
+        use crate::{decoder::from_slice_with, error::Error, DecoderConfig, EncodingRules};
+
+        // 1.2.1 with the last arc padded to `80 01`.
+        let padded = [0x6, 0x3, 0x2A, 0x80, 0x1];
+
+        match from_slice::<ObjectIdentifier>(&padded) {
+            Err(Error::NonMinimalSubidentifier) => {}
+            result => panic!("expected NonMinimalSubidentifier, got {:?}", result),
+        }
+
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        assert_eq!(
+            ObjectIdentifier::new(vec![1, 2, 1]).unwrap(),
+            from_slice_with::<ObjectIdentifier>(&padded, ber).unwrap()
+        );
+    }
+
+    #[test]
+    fn unit_requires_empty_null() {
+        use crate::{decoder::from_slice_with, error::Error, DecoderConfig, EncodingRules};
+
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+
+        for config in &[DecoderConfig::new(), ber] {
+            assert_eq!((), from_slice_with::<()>(&[0x5, 0x0], *config).unwrap());
+
+            match from_slice_with::<()>(&[0x5, 0x1, 0x0], *config) {
+                Err(Error::IncorrectLength(kind)) => assert_eq!("NULL", kind),
+                result => panic!("expected IncorrectLength, got {:?}", result),
+            }
+            assert!(from_slice_with::<Option<()>>(&[0x5, 0x1, 0x0], *config).is_err());
+        }
+    }
+
+    #[test]
+    fn negative_into_unsigned() {
+        use crate::error::Error;
+
+        // -1, which mustn't wrap around to the maximum of the type.
+        let minus_one = [0x2, 0x1, 0xFF];
+
+        match from_slice::<u8>(&minus_one) {
+            Err(Error::IntegerOverflow(ty)) => assert_eq!("u8", ty),
+            result => panic!("expected IntegerOverflow, got {:?}", result),
+        }
+        assert!(from_slice::<u16>(&minus_one).is_err());
+        assert!(from_slice::<u32>(&minus_one).is_err());
+        assert!(from_slice::<u64>(&minus_one).is_err());
+        assert!(from_slice::<u128>(&minus_one).is_err());
+
+        assert_eq!(-1, from_slice::<i8>(&minus_one).unwrap());
+        assert_eq!(255u8, from_slice(&[0x2, 0x2, 0x0, 0xFF]).unwrap());
+        // Short encodings decode into any wider type.
+        assert_eq!(5u64, from_slice(&[0x2, 0x1, 0x5]).unwrap());
+    }
+
+    #[test]
+    fn primitive_integers_match_big_integers() {
+        use super::{integer_contents, primitive_integer, ToPrimitive};
+
+        let contents: &[&[u8]] = &[
+            &[0x00],
+            &[0x7F],
+            &[0x80],
+            &[0xFF, 0x7F],
+            &[0x00, 0x00, 0x80],
+            &[0xFF, 0xFF, 0xFF, 0x80],
+            &[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        ];
+
+        for contents in contents {
+            let big = integer_contents(contents, "INTEGER").unwrap();
+
+            assert_eq!(big.to_i8(), primitive_integer(contents).unwrap());
+            assert_eq!(big.to_u8(), primitive_integer(contents).unwrap());
+            assert_eq!(big.to_i32(), primitive_integer(contents).unwrap());
+            assert_eq!(big.to_u64(), primitive_integer(contents).unwrap());
+            assert_eq!(big.to_i128(), primitive_integer(contents).unwrap());
+            assert_eq!(big.to_u128(), primitive_integer(contents).unwrap());
+        }
+
+        assert!(primitive_integer::<u8>(&[]).is_err());
+    }
+
+    #[test]
+    fn oversized_numbers_error() {
+        // A tag number needing more than 32 bits.
+        assert!(from_slice::<bool>(&[0x1F, 0x90, 0x80, 0x80, 0x80, 0x00, 0x1, 0x0]).is_err());
+        // An OBJECT IDENTIFIER whose root or later component overflows `u32`.
+        assert!(from_slice::<ObjectIdentifier>(&[0x6, 0x6, 0x90, 0x80, 0x80, 0x80, 0x80, 0x00]).is_err());
+        assert!(from_slice::<ObjectIdentifier>(&[0x6, 0x6, 0x2a, 0x90, 0x80, 0x80, 0x80, 0x00]).is_err());
+
+        // An ENUMERATED value that doesn't fit in an `i64`.
+        #[derive(Debug)]
+        struct Status;
+
+        impl Enumerable for Status {
+            fn to_enumerated(&self) -> i64 {
+                0
+            }
+
+            fn from_enumerated(_: i64) -> Option<Self> {
+                Some(Status)
+            }
+        }
+
+        assert!(from_slice::<Enumerated<Status>>(&[0xA, 0x9, 0x1, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn mismatched_optional_is_absent() {
+        #[derive(Debug, PartialEq, Deserialize, serde_derive::Serialize)]
+        struct Foo {
+            a: Option<bool>,
+            b: u8,
+        }
+
+        let foo = Foo { a: None, b: 5 };
+        let encoded = crate::to_vec(&foo).unwrap();
+
+        assert_eq!(&[0x30, 0x3, 0x2, 0x1, 0x5][..], &*encoded);
+        assert_eq!(foo, from_slice(&encoded).unwrap());
+        assert_eq!((None, 5), from_slice::<(Option<bool>, u8)>(&encoded).unwrap());
+    }
+
+    #[test]
+    fn optionals_compared_with_the_same_element() {
+        #[derive(Debug, PartialEq, Deserialize, serde_derive::Serialize)]
+        struct Inner {
+            a: Option<bool>,
+            b: Option<OctetString>,
+            c: Option<()>,
+            d: u8,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize, serde_derive::Serialize)]
+        struct Outer {
+            inner: Option<Inner>,
+            e: Option<bool>,
+            f: u8,
+        }
+
+        for outer in &[
+            Outer { inner: Some(Inner { a: None, b: None, c: Some(()), d: 6 }), e: None, f: 7 },
+            Outer { inner: Some(Inner { a: None, b: None, c: None, d: 6 }), e: Some(true), f: 7 },
+            Outer { inner: None, e: None, f: 7 },
+        ] {
+            assert_eq!(*outer, from_slice(&crate::to_vec(outer).unwrap()).unwrap());
+        }
+    }
+
+    #[test]
+    fn optional_prefixed_value() {
+        #[derive(Debug, PartialEq, Deserialize, serde_derive::Serialize)]
+        struct Foo {
+            a: Option<Implicit<Context, U0, u8>>,
+            b: Option<Explicit<Context, U1, bool>>,
+            c: u8,
+        }
+
+        for foo in &[
+            Foo { a: Some(Implicit::new(1)), b: Some(Explicit::new(true)), c: 2 },
+            Foo { a: None, b: Some(Explicit::new(false)), c: 2 },
+            Foo { a: Some(Implicit::new(1)), b: None, c: 2 },
+            Foo { a: None, b: None, c: 2 },
+        ] {
+            assert_eq!(*foo, from_slice(&crate::to_vec(foo).unwrap()).unwrap());
+        }
+    }
+
+    /// Every truncation of a valid encoding, and every single octet replaced
+    /// with one of a few troublesome values, must decode or error cleanly.
+    #[test]
+    fn corrupted_input_does_not_panic() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Record {
+            id: u32,
+            name: String,
+            flags: BitString,
+            oid: ObjectIdentifier,
+            values: Vec<i64>,
+            note: Option<OctetString>,
+        }
+
+        let valid = [
+            0x30, 0x20, 0x2, 0x1, 0x7, 0x1C, 0x2, b'h', b'i', 0x3, 0x2, 0x4, 0xA0, 0x6, 0x6, 0x2a,
+            0x86, 0x48, 0x86, 0xf7, 0x0d, 0x30, 0x6, 0x2, 0x1, 0x1, 0x2, 0x1, 0xFF, 0x4, 0x3, 1, 2,
+            3,
+        ];
+
+        assert!(from_slice::<Record>(&valid).is_ok());
+
+        for end in 0..valid.len() {
+            let _ = from_slice::<Record>(&valid[..end]);
+        }
+
+        for index in 0..valid.len() {
+            for &octet in &[0x00, 0x1F, 0x7F, 0x80, 0x81, 0x84, 0x89, 0xFF] {
+                let mut corrupted = valid;
+                corrupted[index] = octet;
+
+                let _ = from_slice::<Record>(&corrupted);
+            }
+        }
+    }
+
     /*
     #[test]
     fn oid_from_bytes() {
@@ -629,4 +2001,37 @@ mod tests {
         assert_eq!(oid, from_raw);
     }
     */
+
+    #[test]
+    fn self_describing() {
+        // ENUMERATED, and a context tagged INTEGER, which can't be read
+        // without its type, so it's kept as its encoding.
+        let enumerated = [0xA, 0x1, 0x2];
+        let tagged = [0x80, 0x1, 0x5];
+
+        assert_eq!(Value::Integer(2), from_slice(&enumerated).unwrap());
+        assert_eq!(Value::Bytes(tagged.to_vec()), from_slice(&tagged).unwrap());
+
+        let sequence = [
+            0x30, 0x17,
+            0x1, 0x1, 0xFF,
+            0x5, 0x0,
+            0x13, 0x2, b'h', b'i',
+            0x1E, 0x4, 0x0, b'h', 0x0, b'i',
+            0x0C, 0x2, b'h', b'i',
+            0xA1, 0x2, 0x5, 0x0,
+        ];
+
+        assert_eq!(
+            Value::Sequence(vec![
+                Value::Bool(true),
+                Value::Null,
+                Value::String(String::from("hi")),
+                Value::String(String::from("hi")),
+                Value::String(String::from("hi")),
+                Value::Bytes(vec![0xA1, 0x2, 0x5, 0x0]),
+            ]),
+            from_slice(&sequence).unwrap()
+        );
+    }
 }
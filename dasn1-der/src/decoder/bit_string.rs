@@ -1,4 +1,7 @@
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    EncodingRules,
+};
 use serde::de::{DeserializeSeed, IntoDeserializer, SeqAccess};
 
 /// An BitString deserializer
@@ -7,11 +10,24 @@ pub(crate) struct BitString<'de> {
 }
 
 impl<'de> BitString<'de> {
-    pub fn new(data: &'de [u8]) -> Self {
-        let mut contents = data.into_iter().cloned();
-        contents.next();
+    /// Checks the initial octet of `data` against the rest of the contents,
+    /// and in DER also that the bits it marks as unused are zero.
+    pub fn new(data: &'de [u8], rules: EncodingRules) -> Result<Self> {
+        let (unused_bits, bits) = match data.split_first() {
+            Some((&unused_bits, bits)) => (unused_bits, bits),
+            None => return Err(Error::IncorrectLength(String::from("BIT STRING"))),
+        };
 
-        Self { contents }
+        if unused_bits > 7 || (bits.is_empty() && unused_bits != 0) {
+            return Err(Error::InvalidUnusedBits(unused_bits));
+        }
+
+        let padding = bits.last().map_or(0, |last| last & ((1 << unused_bits) - 1));
+        if rules == EncodingRules::Der && padding != 0 {
+            return Err(Error::NonZeroUnusedBits);
+        }
+
+        Ok(Self { contents: bits.iter().cloned() })
     }
 }
 
@@ -0,0 +1,72 @@
+use core::identifier::Identifier;
+
+use crate::{
+    encoder::encode_value,
+    error::{Error, Result},
+};
+use super::{integer_contents, walk::{walk_tlv, Event}};
+
+/// Re-encodes a single value of any type in its canonical DER form. Lengths
+/// are written in the shortest definite form, the elements of a SET (or SET
+/// OF) are sorted by their encodings, INTEGER and ENUMERATED values lose any
+/// redundant leading octets, and BOOLEAN `TRUE` becomes `0xFF`. `bytes` must
+/// contain exactly one value.
+pub fn canonicalize(bytes: &[u8]) -> Result<Vec<u8>> {
+    // The identifier of each constructed value entered, and the canonical
+    // encodings of the elements read from it so far.
+    let mut open: Vec<(Identifier, Vec<Vec<u8>>)> = Vec::new();
+    let mut canonical = None;
+
+    walk_tlv(bytes, &mut |event| {
+        if open.is_empty() && canonical.is_some() {
+            return Err(Error::Parser(String::from("Unexpected data after the value")));
+        }
+
+        let encoding = match event {
+            Event::Enter(identifier, _) => {
+                open.push((identifier, Vec::new()));
+                return Ok(());
+            }
+            Event::Leave => {
+                let (identifier, mut elements) = open.pop().expect("`Leave` without `Enter`");
+
+                // Without the schema a SET can't be told apart from a SET
+                // OF, but sorting by the encodings also orders the elements
+                // of a SET by tag.
+                if identifier == Identifier::SET {
+                    elements.sort();
+                }
+
+                encode_value(identifier, true, &elements.concat())?
+            }
+            Event::Primitive(identifier, contents) => {
+                encode_value(identifier, false, &canonical_contents(identifier, contents)?)?
+            }
+        };
+
+        match open.last_mut() {
+            Some((_, elements)) => elements.push(encoding),
+            None => canonical = Some(encoding),
+        }
+
+        Ok(())
+    })?;
+
+    canonical.ok_or(Error::UnexpectedEof)
+}
+
+/// Whether `bytes` is a single value which is already in its canonical DER
+/// form, i.e. it's unchanged by `canonicalize`. Malformed input is an error
+/// rather than `false`.
+pub fn is_canonical_der(bytes: &[u8]) -> Result<bool> {
+    Ok(canonicalize(bytes)? == bytes)
+}
+
+fn canonical_contents(identifier: Identifier, contents: &[u8]) -> Result<Vec<u8>> {
+    Ok(match identifier {
+        Identifier::BOOL if contents.len() == 1 => vec![if contents[0] == 0 { 0 } else { 0xff }],
+        Identifier::INTEGER => integer_contents(contents, "INTEGER")?.to_signed_bytes_be(),
+        Identifier::ENUMERATED => integer_contents(contents, "ENUMERATED")?.to_signed_bytes_be(),
+        _ => contents.to_vec(),
+    })
+}
@@ -0,0 +1,29 @@
+use crate::EncodingRules;
+
+/// Options controlling how values are decoded, used with `from_slice_with`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DecoderConfig {
+    pub(crate) rules: EncodingRules,
+}
+
+impl DecoderConfig {
+    /// Instantiates a new `DecoderConfig` with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the encoding rules the input is expected to follow. Default:
+    /// `EncodingRules::Der`, which rejects the indefinite length form.
+    pub fn rules(mut self, rules: EncodingRules) -> Self {
+        self.rules = rules;
+        self
+    }
+}
+
+impl Default for DecoderConfig {
+    fn default() -> Self {
+        Self {
+            rules: EncodingRules::Der,
+        }
+    }
+}
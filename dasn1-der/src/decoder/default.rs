@@ -0,0 +1,74 @@
+use serde::de::{value::BoolDeserializer, DeserializeSeed, SeqAccess};
+
+use crate::{
+    error::{Error, Result},
+    EncodingRules,
+};
+use super::{option::IdentifierDeserializer, Deserializer};
+
+/// Decodes the next value, followed by whether it's forbidden from being its
+/// DEFAULT value, which it is under DER (X.690 11.5). Yields nothing if the
+/// component was left out, because the SEQUENCE ended or the next element
+/// doesn't have the component's tag.
+pub(crate) struct DefaultValue<'a, 'de: 'a> {
+    sent_value: bool,
+    sent_strict: bool,
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'a, 'de> DefaultValue<'a, 'de> {
+    pub(crate) fn new(de: &'a mut Deserializer<'de>) -> Self {
+        Self { sent_value: false, sent_strict: false, de }
+    }
+}
+
+impl<'a, 'de> SeqAccess<'de> for DefaultValue<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if !self.sent_value {
+            self.sent_value = true;
+
+            if self.de.input.is_empty() {
+                self.sent_strict = true;
+                return Ok(None);
+            }
+
+            let identifier = self.de.peek_at_identifier()?.identifier;
+
+            // A tagged component is present if the element has its tag, the
+            // same as an OPTIONAL one.
+            if let Some(tag) = self.de.field_tag.take() {
+                if identifier != tag {
+                    self.sent_strict = true;
+                    return Ok(None);
+                }
+
+                return seed.deserialize(&mut *self.de).map(Some);
+            }
+
+            // The identifier is compared before any of the element is
+            // decoded, so that errors from within a present component
+            // aren't taken to mean it's absent.
+            let mut component = IdentifierDeserializer::new(Some(identifier), self.de);
+            let value = seed.deserialize(&mut component);
+            let value = component.finish(value);
+
+            if component.mismatched() {
+                self.sent_strict = true;
+                return Ok(None);
+            }
+
+            value.map(Some)
+        } else if !self.sent_strict {
+            self.sent_strict = true;
+            let strict = self.de.rules == EncodingRules::Der;
+            seed.deserialize(BoolDeserializer::new(strict)).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -1,16 +1,33 @@
 use num_traits::ToPrimitive;
 use serde::de::{value::SeqDeserializer, DeserializeSeed, SeqAccess};
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    EncodingRules,
+};
 
 /// An ObjectIdentifier deserializer
 pub(crate) struct ObjectIdentifier<'de> {
     contents: &'de [u8],
+    rules: EncodingRules,
 }
 
 impl<'de> ObjectIdentifier<'de> {
-    pub fn new(contents: &'de [u8]) -> Self {
-        Self { contents }
+    pub fn new(contents: &'de [u8], rules: EncodingRules) -> Self {
+        Self { contents, rules }
+    }
+
+    /// Parses the subidentifier at the start of `input`, which in DER has to
+    /// be in the fewest octets, so can't start with `0x80` (X.690 8.19.2).
+    fn parse_subidentifier(&self, input: &'de [u8]) -> Result<(&'de [u8], u32)> {
+        if self.rules == EncodingRules::Der && input.first() == Some(&0x80) {
+            return Err(Error::NonMinimalSubidentifier);
+        }
+
+        let (input, number) = super::parser::parse_encoded_number(input)?;
+        let number = number.to_u32().ok_or_else(|| Error::IntegerOverflow(String::from("u32")))?;
+
+        Ok((input, number))
     }
 }
 
@@ -21,16 +38,20 @@ impl<'de> SeqAccess<'de> for ObjectIdentifier<'de> {
     where
         T: DeserializeSeed<'de>,
     {
-        let (input, root_octets) = super::parser::parse_encoded_number(self.contents)?;
-        let second = (&root_octets % 40u8).to_u32().expect("Second root component greater than `u32`");
-        let first = ((root_octets - second) / 40u8).to_u32().expect("first root component greater than `u32`");
+        let (input, root) = self.parse_subidentifier(self.contents)?;
+        // Only under the root arc 2 can the second arc be 40 or more
+        // (X.690 8.19.4).
+        let (first, second) = match root {
+            0..=79 => (root / 40, root % 40),
+            _ => (2, root - 80),
+        };
         let mut buffer = vec![first, second];
 
         let mut input = input;
         while !input.is_empty() {
-            let (new_input, number) = super::parser::parse_encoded_number(input)?;
+            let (new_input, number) = self.parse_subidentifier(input)?;
             input = new_input;
-            buffer.push(number.to_u32().expect("sub component greater than `u32`"));
+            buffer.push(number);
         }
 
         seed.deserialize(SeqDeserializer::new(buffer.into_iter()))
@@ -1,14 +1,72 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
 use core::identifier::Identifier;
 use serde::{
-    de::{self, SeqAccess, Visitor, Deserializer},
+    de::{self, Visitor, Deserializer},
     forward_to_deserialize_any,
 };
 
-use crate::error::{Error, Result};
+use super::prefix::Prefix;
+use crate::{
+    error::{Error, Result},
+    identifier::{strip_extension_marker, tag_from_struct_name, TAGGED_SEQUENCE_ENUM},
+};
+
+/// The OPTIONAL values found to be absent, shared by the deserializers of a
+/// value and those of the values within it. Whether an `Option` is present
+/// depends on its type, which is only known once `visit_some` has committed
+/// the `Option` to holding a value, so a value whose type has another
+/// identifier fails the decode, and is known to be absent when it's retried.
+#[derive(Debug, Default)]
+pub(crate) struct Absences {
+    /// The absent values, by the address of the element they were compared
+    /// with and how many OPTIONAL values were compared with it before them.
+    absent: RefCell<HashSet<(usize, usize)>>,
+    /// How many OPTIONAL values have been compared with each element.
+    compared: RefCell<HashMap<usize, usize>>,
+    /// Whether a value not yet known to be absent was found to be.
+    found: Cell<bool>,
+}
+
+impl Absences {
+    /// Counts the next OPTIONAL value compared with `element`, returning its
+    /// position and whether it's known to be absent.
+    pub fn next(&self, element: &[u8]) -> ((usize, usize), bool) {
+        let address = element.as_ptr() as usize;
+        let mut compared = self.compared.borrow_mut();
+        let count = compared.entry(address).or_insert(0);
+        let position = (address, *count);
+        *count += 1;
+
+        (position, self.absent.borrow().contains(&position))
+    }
+
+    /// Records the value at `position` as absent.
+    pub fn insert(&self, position: (usize, usize)) {
+        self.absent.borrow_mut().insert(position);
+        self.found.set(true);
+    }
+
+    /// Readies the count for decoding from the start again, returning whether
+    /// that's worth doing, as an absent value was found since the last time.
+    pub fn restart(&self) -> bool {
+        self.compared.borrow_mut().clear();
+        self.found.replace(false)
+    }
+}
 
 pub(crate) struct IdentifierDeserializer<'a, 'de> {
     identifier: Option<Identifier>,
     de: &'a mut super::Deserializer<'de>,
+    /// Whether the value's type asked for an identifier other than the one
+    /// found, before any of the value was decoded.
+    mismatched: bool,
+    /// The error decoding the value failed with, kept here as the value's
+    /// type only sees a copy of it.
+    error: Option<Error>,
 }
 
 impl<'a, 'de: 'a> IdentifierDeserializer<'a, 'de> {
@@ -17,10 +75,43 @@ impl<'a, 'de: 'a> IdentifierDeserializer<'a, 'de> {
     {
         Self {
             identifier,
-            de
+            de,
+            mismatched: false,
+            error: None,
+        }
+    }
+
+    /// Returns the error decoding the value actually failed with, in place of
+    /// the copy `result` holds.
+    pub fn finish<T>(&mut self, result: Result<T>) -> Result<T> {
+        match self.error.take() {
+            Some(error) if result.is_err() => Err(error),
+            _ => result,
         }
     }
 
+    /// Keeps the error `result` failed with, so it can be returned in place
+    /// of the copy the value's type receives.
+    fn capture<T>(&mut self, result: Result<T>) -> Result<T> {
+        result.map_err(|error| {
+            let copy = Error::Custom(error.to_string());
+            self.error = Some(error);
+            copy
+        })
+    }
+
+    /// Marks the value as absent, without an error to report.
+    fn absent<T>(&mut self) -> Result<T> {
+        self.mismatched = true;
+        Err(Error::Custom(String::from("Absent")))
+    }
+
+    /// Whether decoding failed because the value has another type's
+    /// identifier, rather than failing somewhere within the value.
+    pub fn mismatched(&self) -> bool {
+        self.mismatched
+    }
+
     pub fn check_and_deserialize<V>(&mut self, identifier: Identifier, visitor: V)
         -> Result<V::Value>
         where V: Visitor<'de>
@@ -28,20 +119,23 @@ impl<'a, 'de: 'a> IdentifierDeserializer<'a, 'de> {
         log::trace!("Comparing {:?} == {:?}", self.identifier, identifier);
         if self.identifier.map(|i| i == identifier).unwrap_or(false) {
             log::trace!("Matched identifier");
-            self.de.deserialize_any(visitor)
+            let value = self.de.deserialize_any(visitor);
+            self.capture(value)
         } else {
-            panic!();
             log::trace!("Didn't match identifier");
-            visitor.visit_none()
+            self.absent()
         }
     }
 }
 
-impl<'a, 'de: 'a> de::Deserializer<'de> for &'a mut IdentifierDeserializer<'a, 'de> {
+impl<'a, 'de: 'a> de::Deserializer<'de> for &mut IdentifierDeserializer<'a, 'de> {
     type Error = Error;
 
-    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
-        unimplemented!()
+    /// A type that decodes whatever it's given has no identifier to check,
+    /// so it's always present.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let value = self.de.deserialize_any(visitor);
+        self.capture(value)
     }
 
     fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
@@ -113,8 +207,7 @@ impl<'a, 'de: 'a> de::Deserializer<'de> for &'a mut IdentifierDeserializer<'a, '
     }
 
     fn deserialize_option<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
-        // TODO Maybe support recursive option types.
-        unimplemented!()
+        Err(de::Error::custom("an OPTIONAL type can't itself be OPTIONAL"))
     }
 
     fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &str, visitor: V) -> Result<V::Value> {
@@ -122,17 +215,45 @@ impl<'a, 'de: 'a> de::Deserializer<'de> for &'a mut IdentifierDeserializer<'a, '
     }
 
     fn deserialize_newtype_struct<V: Visitor<'de>>(
-        mut self,
+        self,
         name: &str,
         visitor: V,
     ) -> Result<V::Value> {
         let identifier = match name {
+            // An open type can hold a value with any identifier.
+            "ASN.1#Any" => {
+                let value = self.de.deserialize_newtype_struct("ASN.1#Any", visitor);
+                return self.capture(value);
+            }
+            "ASN.1#WithRawBytes" => {
+                let value = self.de.deserialize_newtype_struct("ASN.1#WithRawBytes", visitor);
+                return self.capture(value);
+            }
             "ASN.1#OctetString" => Identifier::OCTET_STRING,
             "ASN.1#ObjectIdentifier" => Identifier::OBJECT_IDENTIFIER,
             "ASN.1#BitString" => Identifier::BIT_STRING,
+            "ASN.1#SetOf" => Identifier::SET,
+            "ASN.1#Integer" => Identifier::INTEGER,
             "ASN.1#Enumerated" => Identifier::ENUMERATED,
-            "ASN.1#Implicit" => unimplemented!(),
-            "ASN.1#Explicit" => unimplemented!(),
+            "ASN.1#NumericString" => Identifier::NUMERIC_STRING,
+            "ASN.1#PrintableString" => Identifier::PRINTABLE_STRING,
+            "ASN.1#IA5String" => Identifier::IA5_STRING,
+            "ASN.1#TeletexString" => Identifier::TELETEX_STRING,
+            "ASN.1#UTCTime" => Identifier::UTC_TIME,
+            "ASN.1#GeneralizedTime" => Identifier::GENERALIZED_TIME,
+            // The prefix's type compares the tag it's sent with its own
+            // before asking for the value, so failing without having asked
+            // means the element has another tag.
+            "ASN.1#Implicit" | "ASN.1#Explicit" => {
+                let mut prefix = Prefix::new(self.de, name == "ASN.1#Explicit")?;
+                let value = visitor.visit_seq(&mut prefix);
+
+                return if value.is_err() && !prefix.sent_value() {
+                    self.absent()
+                } else {
+                    self.capture(value)
+                };
+            }
             _ => Identifier::SEQUENCE,
         };
 
@@ -158,27 +279,54 @@ impl<'a, 'de: 'a> de::Deserializer<'de> for &'a mut IdentifierDeserializer<'a, '
 
     fn deserialize_struct<V: Visitor<'de>>(
         self,
-        name: &str,
-        fields: &[&str],
+        name: &'static str,
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
-        self.deserialize_seq(visitor)
+        match tag_from_struct_name(strip_extension_marker(name).0) {
+            Some(identifier) if self.identifier == Some(identifier) => {
+                let value = de::Deserializer::deserialize_struct(&mut *self.de, name, fields, visitor);
+                self.capture(value)
+            }
+            Some(_) => self.absent(),
+            // Decoded as a struct rather than a sequence, so that the fields
+            // are counted and absent trailing OPTIONAL fields are allowed.
+            None if self.identifier == Some(Identifier::SEQUENCE) => {
+                let value = de::Deserializer::deserialize_struct(&mut *self.de, name, fields, visitor);
+                self.capture(value)
+            }
+            None => self.deserialize_seq(visitor),
+        }
     }
 
     fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         self.check_and_deserialize(Identifier::SEQUENCE, visitor)
     }
 
+    /// A CHOICE has no identifier of its own to check, so it's dispatched on
+    /// the value's identifier the same way as when it isn't OPTIONAL, and is
+    /// absent if the identifier is none of its variants'.
     fn deserialize_enum<V>(
         self,
-        _name: &'static str,
-        _variants: &'static [&'static str],
+        name: &'static str,
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_some(self)
+        let present = match self.identifier {
+            Some(identifier) if name == TAGGED_SEQUENCE_ENUM => identifier == Identifier::SEQUENCE,
+            Some(identifier) => super::choice_variant(identifier, variants).is_some(),
+            None => false,
+        };
+
+        if present {
+            let value = de::Deserializer::deserialize_enum(&mut *self.de, name, variants, visitor);
+            self.capture(value)
+        } else {
+            self.absent()
+        }
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
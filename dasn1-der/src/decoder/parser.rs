@@ -1,5 +1,8 @@
-use core::identifier::Class;
-use nom::IResult;
+use core::{
+    identifier::Class,
+    length::{decode_length, Length, LengthError},
+};
+use nom::{error::ErrorKind, IResult, Needed};
 use num_bigint::BigInt;
 use num_traits::ToPrimitive;
 
@@ -7,7 +10,7 @@ use super::{BerIdentifier as Identifier, Value};
 
 pub(crate) fn parse_value(input: &[u8]) -> IResult<&[u8], Value> {
     let (input, identifier) = parse_identifier_octet(input)?;
-    let (input, contents) = parse_contents(input)?;
+    let (input, contents) = parse_contents(input, identifier.is_constructed)?;
 
     Ok((input, Value::new(identifier, contents)))
 }
@@ -16,9 +19,10 @@ pub(crate) fn parse_identifier_octet(input: &[u8]) -> IResult<&[u8], Identifier>
     let (input, identifier) = parse_initial_octet(input)?;
 
     let (input, tag) = if identifier.identifier.tag >= 0x1f {
-        let (input, tag) = parse_encoded_number(input)?;
+        let (rest, tag) = parse_encoded_number(input)?;
+        let tag = tag.to_u32().ok_or(nom::Err::Error((input, ErrorKind::TooLarge)))?;
 
-        (input, tag.to_u32().expect("Tag was larger than `u32`."))
+        (rest, tag)
     } else {
         (input, identifier.identifier.tag)
     };
@@ -45,9 +49,19 @@ fn parse_initial_octet(input: &[u8]) -> IResult<&[u8], Identifier> {
     Ok((input, Identifier::new(class, constructed, tag)))
 }
 
-fn parse_contents(input: &[u8]) -> IResult<&[u8], &[u8]> {
-    let (input, length) = nom::bytes::streaming::take(1usize)(input)?;
-    take_contents(input, length[0])
+pub(crate) fn parse_contents(input: &[u8], constructed: bool) -> IResult<&[u8], &[u8]> {
+    let (rest, length) = parse_length(input)?;
+
+    take_contents(rest, length, constructed)
+}
+
+pub(crate) fn parse_length(input: &[u8]) -> IResult<&[u8], Length> {
+    let (length, rest) = decode_length(input).map_err(|error| match error {
+        LengthError::Incomplete(needed) => nom::Err::Incomplete(Needed::Size(needed)),
+        _ => nom::Err::Error((input, ErrorKind::LengthValue)),
+    })?;
+
+    Ok((rest, length))
 }
 
 fn concat_number(body: &[u8], end: u8) -> BigInt {
@@ -66,33 +80,67 @@ fn concat_number(body: &[u8], end: u8) -> BigInt {
     number
 }
 
-fn concat_bits(body: &[u8], width: u8) -> usize {
-    let mut result: usize = 0;
+pub(crate) fn take_contents(input: &[u8], length: Length, constructed: bool) -> IResult<&[u8], &[u8]> {
+    const EOC_OCTET: &[u8] = &[0, 0];
+    // There's no way to know how far away the end-of-contents octets are.
+    let unknown = |error| match error {
+        nom::Err::Incomplete(_) => nom::Err::Incomplete(Needed::Unknown),
+        error => error,
+    };
 
-    for byte in body {
-        result <<= width;
-        result |= *byte as usize;
-    }
+    match length {
+        // The elements may contain zero octets, or end-of-contents octets of
+        // their own, so they're skipped one at a time (X.690 8.1.3.6). Nested
+        // indefinite lengths are counted rather than recursed into, so that
+        // deeply nested input can't overflow the stack.
+        Length::Indefinite if constructed => {
+            let mut rest = input;
+            let mut depth = 0usize;
+
+            loop {
+                if rest.starts_with(EOC_OCTET) {
+                    if depth == 0 {
+                        break;
+                    }
+
+                    depth -= 1;
+                    rest = &rest[EOC_OCTET.len()..];
+                    continue;
+                }
 
-    result
+                let (after_identifier, identifier) = parse_identifier_octet(rest).map_err(unknown)?;
+                let (after_length, length) = parse_length(after_identifier).map_err(unknown)?;
+
+                rest = match length {
+                    Length::Indefinite if identifier.is_constructed => {
+                        depth += 1;
+                        after_length
+                    }
+                    length => take_contents(after_length, length, identifier.is_constructed).map_err(unknown)?.0,
+                };
+            }
+
+            let contents = &input[..input.len() - rest.len()];
+
+            Ok((&rest[EOC_OCTET.len()..], contents))
+        }
+        Length::Indefinite => {
+            let (input, contents) = nom::bytes::streaming::take_until(EOC_OCTET)(input).map_err(unknown)?;
+            let (input, _) = nom::bytes::streaming::tag(EOC_OCTET)(input).map_err(unknown)?;
+
+            Ok((input, contents))
+        }
+        Length::Definite(length) => take_exact(input, length),
+    }
 }
 
-fn take_contents(input: &[u8], length: u8) -> IResult<&[u8], &[u8]> {
-    if length == 0x80 {
-        const EOC_OCTET: &[u8] = &[0, 0];
-        let (input, contents) = nom::bytes::streaming::take_until(EOC_OCTET)(input)?;
-        let (input, _) = nom::bytes::streaming::tag(EOC_OCTET)(input)?;
-
-        Ok((input, contents))
-    } else if length >= 0x7f {
-        let length = length ^ 0x80;
-        let (input, length_slice) = nom::bytes::streaming::take(length)(input)?;
-        let length = concat_bits(&length_slice, 8);
-        nom::bytes::streaming::take(length)(input)
-    } else if length == 0 {
-        Ok((input, &[]))
+/// Takes `length` bytes from `input`, or reports how many more bytes are
+/// needed if `input` is too short.
+fn take_exact(input: &[u8], length: usize) -> IResult<&[u8], &[u8]> {
+    if input.len() < length {
+        Err(nom::Err::Incomplete(Needed::Size(length - input.len())))
     } else {
-        nom::bytes::streaming::take(length)(input)
+        Ok((&input[length..], &input[..length]))
     }
 }
 
@@ -137,6 +185,15 @@ mod tests {
         assert_eq!(value.contents, &[0xF0, 0xF0]);
     }
 
+    #[test]
+    fn value_longest_short_length_form() {
+        let input = [[0x4, 0x7F].as_ref(), &[0xAB; 0x7F]].concat();
+        let (rest, value) = parse_value(&input).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(0x7F, value.contents.len());
+    }
+
     #[test]
     fn value_really_long_length_form() {
         let full_buffer = [0xff; 0x100];
@@ -155,4 +212,38 @@ mod tests {
 
         assert_eq!(value.contents, &[0xf0, 0xf0, 0xf0, 0xf0]);
     }
+
+    #[test]
+    fn value_nested_indefinite_length_form() {
+        let input = [0x30, 0x80, 0x30, 0x80, 0x2, 0x1, 0x0, 0, 0, 0x1, 0x1, 0x0, 0, 0, 0xAB];
+        let (rest, value) = parse_value(&input[..]).unwrap();
+
+        assert_eq!(&[0xAB], rest);
+        assert_eq!(value.contents, &input[2..12]);
+        assert_eq!(Err(nom::Err::Incomplete(Needed::Unknown)), parse_value(&input[..13]));
+    }
+
+    #[test]
+    fn tag_larger_than_u32() {
+        let input = [0x1F, 0x90, 0x80, 0x80, 0x80, 0x00, 0x1, 0x0];
+
+        assert!(parse_identifier_octet(&input[..]).is_err());
+        assert!(parse_value(&input[..]).is_err());
+    }
+
+    #[test]
+    fn value_deeply_nested_indefinite_length_form() {
+        const DEPTH: usize = 100_000;
+        let input = [&[0x30, 0x80].repeat(DEPTH)[..], &[0, 0].repeat(DEPTH)].concat();
+        let (rest, value) = parse_value(&input).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(value.contents, &input[2..input.len() - 2]);
+        assert!(parse_value(&input[..input.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn value_reserved_length_form() {
+        assert!(parse_value(&[0x4, 0xFF, 0x1][..]).is_err());
+    }
 }
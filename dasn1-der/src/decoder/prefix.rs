@@ -10,13 +10,20 @@ pub(crate) struct Prefix<'a, 'de: 'a> {
     identifier: BerIdentifier,
     sent_class: bool,
     sent_tag: bool,
+    sent_value: bool,
     de: &'a mut Deserializer<'de>,
 }
 
 impl<'a, 'de> Prefix<'a, 'de> {
     pub(crate) fn new(de: &'a mut Deserializer<'de>, explicit: bool) -> Result<Self> {
         let identifier = de.peek_at_identifier()?;
-        Ok(Self { de, identifier, sent_class: false, sent_tag: false, explicit })
+        Ok(Self { de, identifier, sent_class: false, sent_tag: false, sent_value: false, explicit })
+    }
+
+    /// Whether the prefixed value was asked for, which its type only does
+    /// once it has checked the tag.
+    pub(crate) fn sent_value(&self) -> bool {
+        self.sent_value
     }
 }
 
@@ -38,9 +45,11 @@ impl<'a, 'de> SeqAccess<'de> for Prefix<'a, 'de> {
 
         } else {
             log::trace!("Deserialising inner value, explicit: {:?}", self.explicit);
+            self.sent_value = true;
+
             if self.explicit {
                 let value = self.de.parse_value(None)?;
-                seed.deserialize(&mut Deserializer::from_slice(value.contents)).map(Some)
+                seed.deserialize(&mut self.de.nested(value.contents)).map(Some)
             } else {
                 self.de.type_check = false;
                 seed.deserialize(&mut *self.de).map(Some)
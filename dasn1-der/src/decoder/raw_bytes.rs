@@ -0,0 +1,40 @@
+use serde::de::{value::BorrowedBytesDeserializer, DeserializeSeed, SeqAccess};
+
+use crate::error::{Error, Result};
+use super::Deserializer;
+
+/// Decodes the next value, followed by the bytes it was decoded from, for
+/// `WithRawBytes`.
+pub(crate) struct RawBytes<'a, 'de: 'a> {
+    start: &'de [u8],
+    sent_value: bool,
+    sent_raw: bool,
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'a, 'de> RawBytes<'a, 'de> {
+    pub(crate) fn new(de: &'a mut Deserializer<'de>) -> Self {
+        Self { start: de.input, sent_value: false, sent_raw: false, de }
+    }
+}
+
+impl<'a, 'de> SeqAccess<'de> for RawBytes<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if !self.sent_value {
+            self.sent_value = true;
+            seed.deserialize(&mut *self.de).map(Some)
+        } else if !self.sent_raw {
+            self.sent_raw = true;
+            let raw = &self.start[..self.start.len() - self.de.input.len()];
+            log::trace!("Sending {} raw bytes.", raw.len());
+            seed.deserialize(BorrowedBytesDeserializer::new(raw)).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
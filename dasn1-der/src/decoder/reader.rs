@@ -0,0 +1,416 @@
+use std::io::{self, Read};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    error::{Error, Result},
+    EncodingRules,
+};
+use super::DecoderConfig;
+
+const EOC_OCTETS: [u8; 2] = [0, 0];
+
+/// Reads exactly one complete DER value from `reader` and decodes it as `T`.
+/// The identifier and length octets are read incrementally so the length of
+/// the value doesn't need to be known in advance, and no bytes past the end
+/// of the value are consumed. Values using the indefinite length form are
+/// rejected, use `FrameReader::scan_terminator` to accept them.
+pub fn read_one<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<T> {
+    FrameReader::new(reader).read_one()
+}
+
+/// A reader for decoding successive DER values from a byte stream, such as
+/// back-to-back PDUs sent over a TCP connection.
+pub struct FrameReader<R: Read> {
+    reader: R,
+    scan_terminator: bool,
+    max_frame_length: usize,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Instantiates a new `FrameReader` reading from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            scan_terminator: false,
+            max_frame_length: usize::MAX,
+        }
+    }
+
+    /// Whether to accept values using the indefinite length form, by scanning
+    /// the nested values until the end-of-contents octets are found.
+    /// Default: `false`.
+    pub fn scan_terminator(mut self, scan_terminator: bool) -> Self {
+        self.scan_terminator = scan_terminator;
+        self
+    }
+
+    /// The longest frame, in octets, that will be read, counting its
+    /// identifier and length octets. Longer frames fail with
+    /// `Error::FrameTooLarge` as soon as their length octets are read, before
+    /// any of their contents. Contents are only buffered as they arrive, so
+    /// even without a maximum a frame can't take up more memory than the
+    /// stream provides. Default: `usize::MAX`.
+    pub fn max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// Reads the next complete value from the stream and decodes it as `T`.
+    pub fn read_one<T: DeserializeOwned>(&mut self) -> Result<T> {
+        let frame = self.read_frame()?;
+        let rules = if self.scan_terminator { EncodingRules::Ber } else { EncodingRules::Der };
+
+        super::from_slice_with(&frame, DecoderConfig::new().rules(rules))
+    }
+
+    /// Reads the next complete value from the stream, returning its
+    /// identifier, length, and contents octets without decoding them.
+    pub fn read_frame(&mut self) -> Result<Vec<u8>> {
+        let mut frame = Vec::new();
+        self.read_value(&mut frame)?;
+        Ok(frame)
+    }
+
+    /// Consumes `self` and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads a single value into `frame`. The indefinite length values
+    /// entered are counted rather than recursed into, so deeply nested values
+    /// can't overflow the stack, and every octet read, including those inside
+    /// indefinite length values, counts towards `max_frame_length`.
+    fn read_value(&mut self, frame: &mut Vec<u8>) -> Result<()> {
+        let max = self.max_frame_length;
+        // The number of indefinite length values waiting for their
+        // end-of-contents octets.
+        let mut open = 0usize;
+
+        loop {
+            let start = frame.len();
+            self.read_identifier(frame)?;
+            let length = self.read_length(frame)?;
+
+            if frame.len() > max {
+                return Err(Error::FrameTooLarge { max });
+            }
+
+            match length {
+                Some(length) => {
+                    match frame.len().checked_add(length) {
+                        Some(frame_length) if frame_length <= max => {}
+                        _ => return Err(Error::FrameTooLarge { max }),
+                    }
+
+                    // Read through `take` rather than into a buffer of the
+                    // declared length, which could be far more than is sent.
+                    let read = (&mut self.reader).take(length as u64).read_to_end(frame)?;
+
+                    if read < length {
+                        return Err(Error::UnexpectedEof);
+                    }
+
+                    if open > 0 && frame[start..] == EOC_OCTETS {
+                        open -= 1;
+                    }
+                }
+                None if self.scan_terminator => {
+                    if open == 0 {
+                        log::trace!("Scanning for end-of-contents octets.");
+                    }
+
+                    open += 1;
+                }
+                None => {
+                    return Err(Error::Parser(String::from(
+                        "Indefinite length values can't be framed without scanning for a terminator.",
+                    )))
+                }
+            }
+
+            if open == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn read_identifier(&mut self, frame: &mut Vec<u8>) -> Result<()> {
+        let initial_octet = self.read_octet(frame)?;
+
+        // High tag numbers are encoded in subsequent octets, terminated by an
+        // octet with the most significant bit cleared (X.690 8.1.2.4).
+        if initial_octet & 0x1f == 0x1f {
+            while self.read_octet(frame)? & 0x80 != 0 {}
+        }
+
+        Ok(())
+    }
+
+    /// Returns the length of the contents, or `None` if the value uses the
+    /// indefinite length form.
+    fn read_length(&mut self, frame: &mut Vec<u8>) -> Result<Option<usize>> {
+        let initial_octet = self.read_octet(frame)?;
+
+        match initial_octet {
+            0x80 => Ok(None),
+            0xff => Err(Error::Parser(String::from("Reserved length octet 0xFF."))),
+            length if length < 0x80 => Ok(Some(length as usize)),
+            length => {
+                let number_of_octets = (length & 0x7f) as usize;
+
+                if number_of_octets > std::mem::size_of::<usize>() {
+                    return Err(Error::IncorrectLength(String::from("frame")));
+                }
+
+                let mut length = 0usize;
+                for _ in 0..number_of_octets {
+                    length = (length << 8) | self.read_octet(frame)? as usize;
+                }
+
+                Ok(Some(length))
+            }
+        }
+    }
+
+    fn read_octet(&mut self, frame: &mut Vec<u8>) -> Result<u8> {
+        let mut octet = [0];
+        self.reader.read_exact(&mut octet)?;
+        frame.push(octet[0]);
+
+        Ok(octet[0])
+    }
+}
+
+/// Streams the contents of an OCTET STRING from a reader, such as the
+/// content of a CMS `SignedData` that's too big to hold in memory. BER allows
+/// the string to be split into segments (X.690 8.7.3), which are read one
+/// after the other, so the contents come out joined without being buffered.
+pub struct ContentReader<R: Read> {
+    frames: FrameReader<R>,
+    /// The number of octets left in each constructed segment being read, from
+    /// the outermost, or `None` if it uses the indefinite length form.
+    segments: Vec<Option<usize>>,
+    /// The number of octets left in the primitive segment being read.
+    remaining: usize,
+}
+
+impl<R: Read> ContentReader<R> {
+    /// Reads the identifier and length octets of the OCTET STRING at the start
+    /// of `reader`, leaving the contents to be read from the `ContentReader`.
+    pub fn new(reader: R) -> Result<Self> {
+        let mut content = Self {
+            frames: FrameReader::new(reader).scan_terminator(true),
+            segments: Vec::new(),
+            remaining: 0,
+        };
+
+        content.read_segment_header()?;
+        Ok(content)
+    }
+
+    /// Consumes `self` and returns the underlying reader, positioned after
+    /// the octets read so far.
+    pub fn into_inner(self) -> R {
+        self.frames.into_inner()
+    }
+
+    /// Reads the identifier and length octets of the next segment, or the
+    /// end-of-contents octets closing the current constructed segment.
+    fn read_segment_header(&mut self) -> Result<()> {
+        let mut header = Vec::new();
+        self.frames.read_identifier(&mut header)?;
+        let length = self.frames.read_length(&mut header)?;
+        self.consume(header.len())?;
+
+        if header == EOC_OCTETS && self.segments.last() == Some(&None) {
+            self.segments.pop();
+            return Ok(());
+        }
+
+        // Segments are always OCTET STRINGs, whatever form they use.
+        let constructed = header[0] & 0x20 != 0;
+        if header[0] & !0x20 != 0x04 {
+            let actual = super::parser::parse_identifier_octet(&header)?.1.identifier;
+            return Err(Error::IncorrectType { expected: core::identifier::Identifier::OCTET_STRING, actual });
+        }
+
+        match (constructed, length) {
+            (true, length) => self.segments.push(length),
+            (false, Some(length)) => self.remaining = length,
+            (false, None) => return Err(Error::Parser(String::from(
+                "Primitive segments can't use the indefinite length form.",
+            ))),
+        }
+
+        Ok(())
+    }
+
+    /// Counts `octets` against each definite length segment being read.
+    fn consume(&mut self, octets: usize) -> Result<()> {
+        for remaining in self.segments.iter_mut().flatten() {
+            *remaining = remaining
+                .checked_sub(octets)
+                .ok_or_else(|| Error::IncorrectLength(String::from("OCTET STRING segment")))?;
+        }
+
+        Ok(())
+    }
+
+    fn read_contents(&mut self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            if self.remaining > 0 {
+                let length = buf.len().min(self.remaining);
+                let read = self.frames.reader.read(&mut buf[..length])?;
+
+                if read == 0 {
+                    return Err(Error::UnexpectedEof);
+                }
+
+                self.remaining -= read;
+                self.consume(read)?;
+                return Ok(read);
+            }
+
+            match self.segments.last() {
+                None => return Ok(0),
+                Some(Some(0)) => {
+                    self.segments.pop();
+                }
+                Some(_) => self.read_segment_header()?,
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for ContentReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.read_contents(buf).map_err(|error| match error {
+            Error::Io(error) => error,
+            error => io::Error::new(io::ErrorKind::InvalidData, error),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_derive::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::to_vec;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Pdu {
+        id: u32,
+        data: Vec<u8>,
+    }
+
+    #[test]
+    fn concatenated_pdus() {
+        let first = Pdu { id: 1, data: vec![1, 2, 3] };
+        let second = Pdu { id: 0xffff, data: vec![7; 0x100] };
+
+        let mut stream = to_vec(&first).unwrap();
+        stream.extend(to_vec(&second).unwrap());
+
+        let mut cursor = Cursor::new(stream);
+
+        assert_eq!(first, read_one(&mut cursor).unwrap());
+        assert_eq!(second, read_one(&mut cursor).unwrap());
+        assert!(read_one::<_, Pdu>(&mut cursor).is_err());
+    }
+
+    #[test]
+    fn indefinite_length() {
+        let raw = [0x30, 0x80, 0x1, 0x1, 0xff, 0, 0, 0x1, 0x1, 0];
+        let mut cursor = Cursor::new(&raw[..]);
+
+        assert!(read_one::<_, Vec<bool>>(&mut cursor).is_err());
+
+        let mut reader = FrameReader::new(Cursor::new(&raw[..])).scan_terminator(true);
+        assert_eq!(vec![true], reader.read_one::<Vec<bool>>().unwrap());
+        assert!(!reader.read_one::<bool>().unwrap());
+    }
+
+    #[test]
+    fn huge_declared_length() {
+        // A SEQUENCE claiming to be 2 GiB long, followed by a few octets.
+        let raw = [0x30, 0x84, 0x7f, 0xff, 0xff, 0xff, 0x02, 0x01, 0x00];
+
+        match FrameReader::new(&raw[..]).max_frame_length(1024).read_frame() {
+            Err(Error::FrameTooLarge { max: 1024 }) => {}
+            result => panic!("expected FrameTooLarge, got {:?}", result),
+        }
+
+        // Without a maximum the contents still only fill what was sent.
+        assert!(matches!(FrameReader::new(&raw[..]).read_frame(), Err(Error::UnexpectedEof)));
+    }
+
+    #[test]
+    fn overflowing_length() {
+        let raw = [0x30, 0x88, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+
+        assert!(matches!(FrameReader::new(&raw[..]).read_frame(), Err(Error::FrameTooLarge { .. })));
+        assert!(read_one::<_, Vec<u8>>(&mut &raw[..]).is_err());
+    }
+
+    #[test]
+    fn deeply_nested_indefinite_length() {
+        let raw: Vec<u8> = std::iter::repeat([0x30, 0x80]).take(200_000).flatten().collect();
+
+        let mut reader = FrameReader::new(&raw[..]).scan_terminator(true).max_frame_length(1024);
+        assert!(matches!(reader.read_frame(), Err(Error::FrameTooLarge { max: 1024 })));
+
+        // Without a maximum the headers are read until the stream runs out.
+        let mut reader = FrameReader::new(&raw[..]).scan_terminator(true);
+        assert!(reader.read_frame().is_err());
+
+        let mut raw = raw;
+        raw.extend(std::iter::repeat(0).take(400_000));
+        let frame = FrameReader::new(&raw[..]).scan_terminator(true).read_frame().unwrap();
+        assert_eq!(raw.len(), frame.len());
+    }
+
+    #[test]
+    fn segmented_content() {
+        let raw = [
+            0x24, 0x80,
+            0x04, 0x02, b'H', b'e',
+            0x24, 0x07,
+            0x04, 0x01, b'l',
+            0x04, 0x02, b'l', b'o',
+            0x04, 0x00,
+            0x04, 0x01, b'!',
+            0, 0,
+            0x01, 0x01, 0xff,
+        ];
+
+        let mut reader = ContentReader::new(Cursor::new(&raw[..])).unwrap();
+        let mut content = Vec::new();
+        io::copy(&mut reader, &mut content).unwrap();
+
+        assert_eq!(b"Hello!", &*content);
+
+        // The value after the string is left in the reader.
+        let mut rest = reader.into_inner();
+        assert!(read_one::<_, bool>(&mut rest).unwrap());
+
+        let mut content = String::new();
+        ContentReader::new(&[0x04, 0x03, b'a', b'b', b'c'][..]).unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!("abc", content);
+
+        // Segments have to be OCTET STRINGs, and fit in their parent.
+        let mut sink = Vec::new();
+        let wrong_type = [0x24, 0x80, 0x03, 0x01, 0x00, 0, 0];
+        let overrun = [0x24, 0x03, 0x04, 0x02, b'a', b'b'];
+        assert!(io::copy(&mut ContentReader::new(&wrong_type[..]).unwrap(), &mut sink).is_err());
+        assert!(io::copy(&mut ContentReader::new(&overrun[..]).unwrap(), &mut sink).is_err());
+        assert!(ContentReader::new(&[0x02, 0x01, 0x00][..]).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error::{Error, Result};
+
+/// Decodes the contents octets of a REAL value. Besides the binary form
+/// required by DER, this accepts the special values and the decimal forms
+/// (ISO 6093 NR1, NR2, and NR3) that other encoders may produce. (X.690 8.5)
+pub(crate) fn decode_real(contents: &[u8]) -> Result<f64> {
+    let (&initial_octet, rest) = match contents.split_first() {
+        Some(split) => split,
+        None => return Ok(0.0),
+    };
+
+    match initial_octet >> 6 {
+        0b10 | 0b11 => decode_binary(initial_octet, rest),
+        0b01 => match initial_octet {
+            0x40 if rest.is_empty() => Ok(f64::INFINITY),
+            0x41 if rest.is_empty() => Ok(f64::NEG_INFINITY),
+            0x42 if rest.is_empty() => Ok(f64::NAN),
+            0x43 if rest.is_empty() => Ok(-0.0),
+            _ => Err(malformed("unknown special value")),
+        },
+        _ => decode_decimal(initial_octet, rest),
+    }
+}
+
+fn decode_binary(initial_octet: u8, rest: &[u8]) -> Result<f64> {
+    let base_bits = match (initial_octet >> 4) & 0b11 {
+        0 => 1,
+        1 => 3,
+        2 => 4,
+        _ => return Err(malformed("reserved base")),
+    };
+    let scaling = i64::from((initial_octet >> 2) & 0b11);
+
+    let (exponent_len, rest) = match initial_octet & 0b11 {
+        3 => match rest.split_first() {
+            Some((&len, rest)) => (len as usize, rest),
+            None => return Err(Error::UnexpectedEof),
+        },
+        format => (format as usize + 1, rest),
+    };
+
+    if exponent_len == 0 || rest.len() < exponent_len {
+        return Err(malformed("exponent is missing"));
+    }
+
+    let (exponent_octets, mantissa_octets) = rest.split_at(exponent_len);
+
+    // Exponents this far out give an infinite or zero value either way, so
+    // clamping them keeps the arithmetic from overflowing.
+    let limit = 1 << 20;
+    let mut exponent: i64 = if exponent_octets[0] & 0x80 != 0 { -1 } else { 0 };
+
+    for &octet in exponent_octets {
+        exponent = (exponent << 8 | i64::from(octet)).clamp(-limit, limit);
+    }
+
+    let mantissa = mantissa_octets
+        .iter()
+        .fold(0.0, |mantissa, &octet| mantissa * 256.0 + f64::from(octet));
+    let value = scale(mantissa, exponent * base_bits + scaling);
+
+    Ok(if initial_octet & 0x40 != 0 { -value } else { value })
+}
+
+/// Multiplies `value` by two to the power of `exponent`, in steps small
+/// enough that the intermediate results don't overflow or underflow early.
+fn scale(mut value: f64, mut exponent: i64) -> f64 {
+    const STEP: i64 = 1000;
+
+    while exponent > STEP && value.is_finite() {
+        value *= 2f64.powi(STEP as i32);
+        exponent -= STEP;
+    }
+
+    while exponent < -STEP && value != 0.0 {
+        value *= 2f64.powi(-STEP as i32);
+        exponent += STEP;
+    }
+
+    value * 2f64.powi(exponent.clamp(-STEP, STEP) as i32)
+}
+
+fn decode_decimal(initial_octet: u8, rest: &[u8]) -> Result<f64> {
+    let number = std::str::from_utf8(rest)
+        .map_err(|_| malformed("decimal form isn't ASCII"))?
+        .trim_start_matches(' ')
+        .replace(',', ".");
+
+    if number.is_empty() || !number.bytes().all(|c| c.is_ascii_digit() || b"+-.Ee".contains(&c)) {
+        return Err(malformed("invalid decimal form"));
+    }
+
+    let has_mark = number.contains('.');
+    let has_exponent = number.contains(['E', 'e']);
+
+    let valid = match initial_octet & 0x3f {
+        1 => !has_mark && !has_exponent,
+        2 => !has_exponent,
+        3 => has_exponent,
+        _ => false,
+    };
+
+    if !valid {
+        return Err(malformed("invalid decimal form"));
+    }
+
+    number.parse().map_err(|_| malformed("invalid decimal form"))
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::Parser(format!("Malformed REAL: {}", reason))
+}
@@ -0,0 +1,109 @@
+use core::identifier::Identifier;
+
+use crate::error::Result;
+use super::parser;
+
+/// A single encoded value, split into its identifier and contents without
+/// being decoded into a type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tlv<'a> {
+    identifier: Identifier,
+    constructed: bool,
+    indefinite: bool,
+    contents: &'a [u8],
+    encoding: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// The tag of the value.
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// Whether the value uses the constructed form.
+    pub fn is_constructed(&self) -> bool {
+        self.constructed
+    }
+
+    /// Whether the value uses the indefinite length form, which DER doesn't
+    /// allow.
+    pub fn has_indefinite_length(&self) -> bool {
+        self.indefinite
+    }
+
+    /// The contents octets of the value, not including any end-of-contents
+    /// octets.
+    pub fn contents(&self) -> &'a [u8] {
+        self.contents
+    }
+
+    /// The complete encoding (identifier, length, and contents octets) of
+    /// the value.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.encoding
+    }
+}
+
+/// Splits the value at the start of `bytes` from the bytes after it.
+pub fn split_tlv(bytes: &[u8]) -> Result<(Tlv<'_>, &[u8])> {
+    let (after_identifier, _) = parser::parse_identifier_octet(bytes)?;
+    let (rest, value) = parser::parse_value(bytes)?;
+    let tlv = Tlv {
+        identifier: value.identifier.identifier,
+        constructed: value.identifier.is_constructed,
+        indefinite: after_identifier.first() == Some(&0x80),
+        contents: value.contents,
+        encoding: &bytes[..bytes.len() - rest.len()],
+    };
+
+    Ok((tlv, rest))
+}
+
+/// Iterates over the values in `contents`, such as the contents octets of a
+/// SEQUENCE or SET, without decoding them. Iteration stops after the first
+/// value that can't be split off.
+pub fn children(contents: &[u8]) -> impl Iterator<Item = Result<Tlv<'_>>> {
+    let mut rest = Some(contents);
+
+    std::iter::from_fn(move || {
+        let input = rest.take().filter(|input| !input.is_empty())?;
+
+        Some(split_tlv(input).map(|(tlv, after)| {
+            rest = Some(after);
+            tlv
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_children() {
+        // SEQUENCE { INTEGER 5, OCTET STRING 'ABCD'H, SEQUENCE { BOOLEAN TRUE } }
+        let sequence = [0x30, 0x0C, 0x02, 0x01, 0x05, 0x04, 0x02, 0xAB, 0xCD, 0x30, 0x03, 0x01, 0x01, 0xFF];
+        let (tlv, rest) = split_tlv(&sequence).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(Identifier::SEQUENCE, tlv.identifier());
+
+        let children = children(tlv.contents()).collect::<Result<Vec<_>>>().unwrap();
+        let identifiers = children.iter().map(Tlv::identifier).collect::<Vec<_>>();
+
+        assert_eq!(vec![Identifier::INTEGER, Identifier::OCTET_STRING, Identifier::SEQUENCE], identifiers);
+        assert_eq!(&[0xAB, 0xCD][..], children[1].contents());
+        assert!(children[2].is_constructed());
+        assert!(!children[2].has_indefinite_length());
+        assert_eq!(&sequence[9..], children[2].as_bytes());
+    }
+
+    #[test]
+    fn truncated_child() {
+        let mut children = children(&[0x02, 0x01, 0x05, 0x04, 0x05, 0xAB]);
+
+        assert_eq!(Identifier::INTEGER, children.next().unwrap().unwrap().identifier());
+        assert!(children.next().unwrap().is_err());
+        assert!(children.next().is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use core::{identifier::Identifier, length::Length};
+
+use crate::error::{Error, Result};
+use super::parser;
+
+const EOC_OCTETS: &[u8] = &[0, 0];
+
+/// A step through an encoding, reported by `walk_tlv`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event<'a> {
+    /// The start of a constructed value, with the length from its header.
+    Enter(Identifier, Length),
+    /// The end of the constructed value entered most recently.
+    Leave,
+    /// A primitive value and its contents octets.
+    Primitive(Identifier, &'a [u8]),
+}
+
+/// Walks through each value in `bytes`, calling `visit` as constructed
+/// values are entered and left, and for every primitive value inside them.
+/// The values entered are kept on a stack rather than recursed into, so any
+/// depth of nesting can be walked; a caller wanting to limit it can count
+/// `Enter` and `Leave` events, and stop the walk by returning an error from
+/// `visit`.
+pub fn walk_tlv<'a, F>(bytes: &'a [u8], visit: &mut F) -> Result<()>
+where
+    F: FnMut(Event<'a>) -> Result<()>,
+{
+    // For each value entered, where its contents end, `None` if they end with
+    // end-of-contents octets, and where the innermost definite length value
+    // around it ends, which nothing inside may go past.
+    let mut open: Vec<(Option<usize>, usize)> = Vec::new();
+    let mut position = 0;
+
+    loop {
+        let limit = open.last().map_or(bytes.len(), |&(_, limit)| limit);
+        let input = &bytes[position..limit];
+
+        match open.last() {
+            Some(&(Some(end), _)) if position == end => {
+                open.pop();
+                visit(Event::Leave)?;
+                continue;
+            }
+            Some(&(None, _)) if input.starts_with(EOC_OCTETS) => {
+                open.pop();
+                position += EOC_OCTETS.len();
+                visit(Event::Leave)?;
+                continue;
+            }
+            None if input.is_empty() => return Ok(()),
+            _ => {}
+        }
+
+        let (rest, identifier) = parser::parse_identifier_octet(input)?;
+        let (rest, length) = parser::parse_length(rest)?;
+        let header = input.len() - rest.len();
+
+        if identifier.is_constructed {
+            let end = match length {
+                Length::Definite(length) if length > rest.len() => return Err(Error::UnexpectedEof),
+                Length::Definite(length) => Some(position + header + length),
+                Length::Indefinite => None,
+            };
+
+            visit(Event::Enter(identifier.identifier, length))?;
+            open.push((end, end.unwrap_or(limit)));
+            position += header;
+        } else {
+            let (rest, contents) = parser::take_contents(rest, length, false)?;
+
+            visit(Event::Primitive(identifier.identifier, contents))?;
+            position += input.len() - rest.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(bytes: &[u8]) -> Result<Vec<Event<'_>>> {
+        let mut events = Vec::new();
+        walk_tlv(bytes, &mut |event| {
+            events.push(event);
+            Ok(())
+        })?;
+
+        Ok(events)
+    }
+
+    #[test]
+    fn nested_values() {
+        // SEQUENCE { INTEGER 5, SET (indefinite) { BOOLEAN TRUE } }, NULL
+        let bytes = [0x30, 0x0A, 0x02, 0x01, 0x05, 0x31, 0x80, 0x01, 0x01, 0xFF, 0x00, 0x00, 0x05, 0x00];
+
+        assert_eq!(
+            vec![
+                Event::Enter(Identifier::SEQUENCE, Length::Definite(10)),
+                Event::Primitive(Identifier::INTEGER, &[0x05]),
+                Event::Enter(Identifier::SET, Length::Indefinite),
+                Event::Primitive(Identifier::BOOL, &[0xFF]),
+                Event::Leave,
+                Event::Leave,
+                Event::Primitive(Identifier::NULL, &[]),
+            ],
+            events(&bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn overrunning_values() {
+        // An element longer than the SEQUENCE around it.
+        assert!(events(&[0x30, 0x03, 0x04, 0x02, 0x00, 0x00]).is_err());
+        // A SEQUENCE longer than the input.
+        assert!(events(&[0x30, 0x05, 0x05, 0x00]).is_err());
+        // Missing end-of-contents octets.
+        assert!(events(&[0x30, 0x80, 0x05, 0x00]).is_err());
+    }
+
+    #[test]
+    fn deeply_nested_sequences() {
+        const DEPTH: usize = 10_000;
+
+        // Built from the innermost SEQUENCE out, each header holding the
+        // length of everything inside it.
+        let mut headers = Vec::with_capacity(DEPTH);
+        let mut length = 0;
+        for _ in 0..DEPTH {
+            let mut header = vec![0x30];
+            core::length::encode_length(length, &mut header).unwrap();
+            length += header.len();
+            headers.push(header);
+        }
+        let bytes: Vec<u8> = headers.into_iter().rev().flatten().collect();
+
+        let mut depth = 0usize;
+        let mut deepest = 0;
+        walk_tlv(&bytes, &mut |event| {
+            match event {
+                Event::Enter(..) => depth += 1,
+                Event::Leave => depth -= 1,
+                Event::Primitive(..) => unreachable!(),
+            }
+            deepest = deepest.max(depth);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(DEPTH, deepest);
+        assert_eq!(0, depth);
+    }
+}
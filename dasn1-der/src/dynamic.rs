@@ -0,0 +1,71 @@
+//! Encoding and decoding through trait objects, for handling messages of
+//! different types behind one interface, such as in a protocol dispatcher.
+
+use std::collections::HashMap;
+
+use core::identifier::Identifier;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    decoder::parser,
+    error::{Error, Result},
+};
+
+/// Encodes `self` as DER. Unlike `Serialize`, this can be used as a trait
+/// object (e.g. `Box<dyn DynEncode>`), and is implemented for every type that
+/// implements `Serialize`.
+pub trait DynEncode {
+    /// Encodes `self` as DER, the same as `to_vec`.
+    fn encode_der(&self) -> Result<Vec<u8>>;
+}
+
+impl<T: Serialize> DynEncode for T {
+    fn encode_der(&self) -> Result<Vec<u8>> {
+        crate::to_vec(self)
+    }
+}
+
+type Decode<M> = Box<dyn Fn(&[u8]) -> Result<M>>;
+
+/// Decodes messages of different types into a common type `M` (e.g. an
+/// `enum` of the messages, or `Box<dyn Any>`), picking the type to decode as
+/// by the message's tag.
+pub struct DecoderRegistry<M> {
+    decoders: HashMap<Identifier, Decode<M>>,
+}
+
+impl<M> DecoderRegistry<M> {
+    /// Instantiates a new `DecoderRegistry` without any types.
+    pub fn new() -> Self {
+        Self { decoders: HashMap::new() }
+    }
+
+    /// Decodes messages tagged with `identifier` as `T`, converting them to
+    /// `M` with `wrap`. Replaces any type already registered with the tag.
+    pub fn register<T, F>(&mut self, identifier: Identifier, wrap: F) -> &mut Self
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> M + 'static,
+    {
+        let decode = move |bytes: &[u8]| crate::from_slice(bytes).map(&wrap);
+        self.decoders.insert(identifier, Box::new(decode));
+        self
+    }
+
+    /// Decodes `bytes` as the type registered with its tag. Fails with
+    /// `Error::NoVariantFound` if no type is registered with the tag.
+    pub fn decode(&self, bytes: &[u8]) -> Result<M> {
+        let identifier = parser::parse_identifier_octet(bytes)?.1.identifier;
+
+        match self.decoders.get(&identifier) {
+            Some(decode) => decode(bytes),
+            None => Err(Error::NoVariantFound(identifier.tag)),
+        }
+    }
+}
+
+impl<M> Default for DecoderRegistry<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,7 +1,10 @@
 mod object_identifier;
 mod bit_string;
+mod builder;
 mod bytes;
+mod config;
 mod prefix;
+mod real;
 
 use std::io::Write;
 
@@ -10,7 +13,10 @@ use num_bigint::ToBigInt;
 use serde::{ser, Serialize};
 
 use crate::error::{Error, Result};
-use core::identifier::Identifier;
+use core::{
+    identifier::{Class, Identifier},
+    length::encode_length,
+};
 
 use self::{
     bit_string::BitStringSerializer,
@@ -19,25 +25,70 @@ use self::{
     prefix::PrefixSerializer
 };
 
+pub use self::{
+    builder::DerBuilder,
+    config::{EncoderConfig, EncodingRules},
+};
+
+const EOC_OCTETS: [u8; 2] = [0, 0];
+
+/// Serialize an instance of `T` as ASN.1 DER into `writer`.
 pub fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
 where
     W: Write,
     T: Serialize,
 {
-    let mut serializer = Serializer::new(writer);
+    to_writer_with(writer, value, EncoderConfig::default())
+}
+
+fn to_writer_with<W, T>(writer: W, value: &T, config: EncoderConfig) -> Result<()>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::new(writer, config);
     value.serialize(&mut serializer)?;
     Ok(())
 }
 
 /// Serialize an instance of `T` as a ASN.1 DER byte vector.
 pub fn to_vec<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    to_vec_with(value, EncoderConfig::default())
+}
+
+/// Serialize an instance of `T` as a byte vector, using the encoding options
+/// in `config`.
+pub fn to_vec_with<T: Serialize>(value: &T, config: EncoderConfig) -> Result<Vec<u8>> {
     let mut vec = Vec::new();
+    encode_into_with(&mut vec, value, config)?;
+    Ok(vec)
+}
+
+/// Serialize an instance of `T` as ASN.1 DER into `buf`, replacing its
+/// contents. The capacity of `buf` is kept, so reusing the same buffer across
+/// many values avoids allocating a new one for each. If encoding fails `buf`
+/// is left with whatever was encoded before the error.
+pub fn encode_into<T: Serialize>(buf: &mut Vec<u8>, value: &T) -> Result<()> {
+    encode_into_with(buf, value, EncoderConfig::default())
+}
 
-    to_writer(&mut vec, value)?;
+fn encode_into_with<T: Serialize>(buf: &mut Vec<u8>, value: &T, config: EncoderConfig) -> Result<()> {
+    buf.clear();
+    to_writer_with(&mut *buf, value, config)?;
 
-    debug!("HEX Debug representation: {:?}", hex::encode(&vec));
+    debug!("HEX Debug representation: {:?}", hex::encode(&buf));
 
-    Ok(vec)
+    Ok(())
+}
+
+/// Encodes the identifier and length octets for `contents`, followed by the
+/// contents themselves.
+pub(crate) fn encode_value(tag: Identifier, constructed: bool, contents: &[u8]) -> Result<Vec<u8>> {
+    let mut ser = Serializer::new(Vec::new(), EncoderConfig::default());
+    ser.set_tag(tag);
+    ser.constructed = constructed;
+    ser.encode(contents)?;
+    Ok(ser.output)
 }
 
 pub struct Serializer<W: Write> {
@@ -47,11 +98,17 @@ pub struct Serializer<W: Write> {
     constructed: bool,
     /// If present bool matches `implicit` state.
     prefixed: Option<bool>,
+    /// Whether the next bytes are an already encoded value to be written
+    /// as is.
+    raw: bool,
+    /// Whether the next sequence is a SET OF, whose elements are sorted.
+    set_of: bool,
+    config: EncoderConfig,
 }
 
 impl Serializer<Vec<u8>> {
-    fn serialize_to_vec<T: ?Sized + Serialize>(value: &T, implicit: bool) -> Result<Self> {
-        let mut ser = Self::new(Vec::new());
+    fn serialize_to_vec<T: ?Sized + Serialize>(value: &T, implicit: bool, config: EncoderConfig) -> Result<Self> {
+        let mut ser = Self::new(Vec::new(), config);
         ser.implicit = implicit;
         value.serialize(&mut ser)?;
         Ok(ser)
@@ -59,13 +116,16 @@ impl Serializer<Vec<u8>> {
 }
 
 impl<W: Write> Serializer<W> {
-    fn new(output: W) -> Self {
+    fn new(output: W, config: EncoderConfig) -> Self {
         Self {
             output,
             tag: None,
             implicit: false,
             constructed: false,
             prefixed: None,
+            raw: false,
+            set_of: false,
+            config,
         }
     }
 
@@ -85,16 +145,29 @@ impl<W: Write> Serializer<W> {
     }
 
     fn encode(&mut self, contents: &[u8]) -> Result<()> {
-        // TODO: Switch bool to operate on the true version of this expression.
-        if !self.implicit {
-            self.encode_preamble(contents.len())?;
+        if self.implicit {
+            self.output.write_all(contents)?;
+            return Ok(());
+        }
+
+        let indefinite = self.config.rules == EncodingRules::Ber
+            && self.tag.is_some_and(|tag| self.is_constructed(tag));
+
+        if indefinite {
+            self.encode_preamble(None)?;
+            self.output.write_all(contents)?;
+            self.output.write_all(&EOC_OCTETS)?;
+        } else {
+            self.encode_preamble(Some(contents.len()))?;
+            self.output.write_all(contents)?;
         }
 
-        self.output.write(contents)?;
         Ok(())
     }
 
-    fn encode_preamble(&mut self, original_length: usize) -> Result<()> {
+    /// Encodes the identifier and length octets, using the indefinite length
+    /// form if `length` is `None`.
+    fn encode_preamble(&mut self, length: Option<usize>) -> Result<()> {
         let tag = self
             .tag
             .take()
@@ -102,40 +175,66 @@ impl<W: Write> Serializer<W> {
 
         self.encode_tag(tag)?;
 
-        if original_length <= 127 {
-            self.output.write(&[original_length as u8])?;
-        } else {
-            let mut length = original_length;
-            let mut length_buffer = std::collections::VecDeque::new();
+        match length {
+            Some(length) => self.encode_definite_length(length)?,
+            None => self.output.write_all(&[0x80])?,
+        }
 
-            while length != 0 {
-                length_buffer.push_front((length & 0xff) as u8);
-                length >>= 8;
-            }
+        self.clear_state();
 
-            let length_buffer: Vec<u8> = length_buffer.into();
-            self.output.write(&[length_buffer.len() as u8 | 0x80])?;
-            self.output.write(&length_buffer)?;
+        Ok(())
+    }
+
+    /// Encodes `length` in the fewest octets, unless the config asks for
+    /// more.
+    fn encode_definite_length(&mut self, length: usize) -> Result<()> {
+        let bytes = length.to_be_bytes();
+        let significant = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count()..];
+        // The short form has no octets following the initial one.
+        let needed = if length <= 127 { 0 } else { significant.len() };
+
+        if self.config.min_length_octets <= needed {
+            encode_length(length, &mut self.output)?;
+            return Ok(());
         }
 
-        self.clear_state();
+        let count = self.config.min_length_octets;
+        self.output.write_all(&[count as u8 | 0x80])?;
+        self.output.write_all(&vec![0; count - significant.len()])?;
+        self.output.write_all(significant)?;
 
         Ok(())
     }
 
+    /// Encodes the contents of a BIT STRING (its unused bits octet, then its
+    /// bits) in the constructed form, as primitive BIT STRINGs of at most
+    /// `size` octets of bits each. Only the last segment can have unused
+    /// bits.
+    fn encode_segmented_bit_string(&mut self, contents: &[u8], size: usize) -> Result<()> {
+        let (unused_bits, bits) = contents.split_first().unwrap_or((&0, &[]));
+        let chunks: Vec<&[u8]> = if bits.is_empty() { vec![&[]] } else { bits.chunks(size).collect() };
+
+        let mut segments = Serializer::new(Vec::new(), self.config);
+        for (index, chunk) in chunks.iter().enumerate() {
+            let unused_bits = if index + 1 == chunks.len() { *unused_bits } else { 0 };
+            let mut segment = vec![unused_bits];
+            segment.extend_from_slice(chunk);
+
+            segments.set_tag(Identifier::BIT_STRING);
+            segments.encode(&segment)?;
+        }
+
+        self.set_constructed();
+        self.encode(&segments.output)
+    }
+
     fn encode_tag(&mut self, tag: Identifier) -> Result<()> {
         let mut tag_byte = tag.class as u8;
         let mut tag_number = tag.tag;
 
         // Constructed is a single bit.
         tag_byte <<= 1;
-        tag_byte |= match tag {
-            Identifier::EXTERNAL |
-            Identifier::SEQUENCE |
-            Identifier::SET => 1,
-            _ if self.constructed => 1,
-            _ => 0,
-        };
+        tag_byte |= self.is_constructed(tag) as u8;
 
         // Identifier number is five bits
         tag_byte <<= 5;
@@ -163,6 +262,45 @@ impl<W: Write> Serializer<W> {
         Ok(())
     }
 
+    fn is_constructed(&self, tag: Identifier) -> bool {
+        match tag {
+            Identifier::EXTERNAL |
+            Identifier::SEQUENCE |
+            Identifier::SET => true,
+            _ => self.constructed,
+        }
+    }
+
+    /// Encodes a variant of an enum named `ASN.1#TaggedSequence`, as a
+    /// SEQUENCE of `variant_index` as an ENUMERATED, followed by the
+    /// variant's value if it has one.
+    fn encode_tagged_sequence<T>(&mut self, variant_index: u32, value: Option<&T>) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let index = variant_index.to_bigint().unwrap().to_signed_bytes_be();
+        let mut contents = encode_value(Identifier::ENUMERATED, false, &index)?;
+
+        if let Some(value) = value {
+            let value = Serializer::serialize_to_vec(value, true, self.config)?;
+
+            match value.tag {
+                Some(tag) => {
+                    let mut inner = Serializer::new(&mut contents, self.config);
+                    inner.constructed = value.is_constructed(tag);
+                    inner.set_tag(tag);
+                    inner.encode(&value.output)?;
+                }
+                // Open types are already encoded.
+                None => contents.extend_from_slice(&value.output),
+            }
+        }
+
+        self.tag = self.tag.or(Some(Identifier::SEQUENCE));
+        self.set_constructed();
+        self.encode(&contents)
+    }
+
     fn encode_bool(&mut self, v: bool) -> Result<()> {
         let v = if v { 0xff } else { 0 };
 
@@ -171,7 +309,11 @@ impl<W: Write> Serializer<W> {
     }
 
     fn encode_integer<N: ToBigInt>(&mut self, value: N) -> Result<()> {
-        self.set_tag(Identifier::INTEGER);
+        // ENUMERATED values are encoded the same as INTEGERs.
+        if self.tag != Some(Identifier::ENUMERATED) {
+            self.set_tag(Identifier::INTEGER);
+        }
+
         self.encode(&value.to_bigint().unwrap().to_signed_bytes_be())
     }
 }
@@ -249,9 +391,10 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
         self.serialize_f64(f64::from(v))
     }
 
-    fn serialize_f64(self, _v: f64) -> Result<()> {
+    fn serialize_f64(self, v: f64) -> Result<()> {
         log::trace!("Serializing f64.");
-        unimplemented!()
+        self.set_tag(Identifier::REAL);
+        self.encode(&real::encode_real(v))
     }
 
     fn serialize_char(self, v: char) -> Result<()> {
@@ -261,13 +404,26 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
 
     fn serialize_str(self, v: &str) -> Result<()> {
         log::trace!("Serializing str.");
-        self.set_tag(Identifier::UNIVERSAL_STRING);
+        // Restricted string and time types set their own tag before
+        // serializing.
+        if self.tag.is_none() {
+            self.set_tag(Identifier::UNIVERSAL_STRING);
+        }
         self.encode(v.as_bytes())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        if self.raw {
+            log::trace!("Writing encoded value.");
+            self.raw = false;
+            self.output.write_all(v)?;
+            return Ok(());
+        }
+
         log::trace!("Serializing bytes.");
-        self.set_tag(Identifier::OCTET_STRING);
+        if self.tag.is_none() {
+            self.set_tag(Identifier::OCTET_STRING);
+        }
         self.encode(v)
     }
 
@@ -290,24 +446,35 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
         self.encode(&[])
     }
 
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
-        log::trace!("Serializing unit struct.");
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        log::trace!("Serializing unit struct {}.", name);
+
+        if name == crate::identifier::PHANTOM_DATA {
+            return Ok(());
+        }
+
         self.serialize_unit()
     }
 
     fn serialize_unit_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> Result<()> {
         log::trace!("Serializing unit variant.");
-        if self.tag.map(|i| i == Identifier::ENUMERATED).unwrap_or(false) {
+        if name == crate::identifier::TAGGED_SEQUENCE_ENUM {
+            self.encode_tagged_sequence::<()>(variant_index, None)
+        } else if self.tag.map(|i| i == Identifier::ENUMERATED).unwrap_or(false) {
             self.encode(&variant_index.to_bigint().unwrap().to_signed_bytes_be())
         } else {
-           unreachable!("Shouldn't be possible.")
+            // Without a tag of its own, a unit variant of a CHOICE has
+            // nothing to encode.
+            Err(Error::Custom(format!(
+                "{}::{} has no encoding, wrap the enum in `Enumerated` to encode it as an ENUMERATED",
+                name, variant
+            )))
         }
-
     }
 
     fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
@@ -335,6 +502,39 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
                 log::trace!("Serializing ENUMERATED.");
                 self.set_tag(Identifier::ENUMERATED);
             }
+            "ASN.1#NumericString" => {
+                log::trace!("Serializing NumericString.");
+                self.set_tag(Identifier::NUMERIC_STRING);
+            }
+            "ASN.1#PrintableString" => {
+                log::trace!("Serializing PrintableString.");
+                self.set_tag(Identifier::PRINTABLE_STRING);
+            }
+            "ASN.1#IA5String" => {
+                log::trace!("Serializing IA5String.");
+                self.set_tag(Identifier::IA5_STRING);
+            }
+            "ASN.1#TeletexString" => {
+                log::trace!("Serializing TeletexString.");
+                self.set_tag(Identifier::TELETEX_STRING);
+            }
+            "ASN.1#UTCTime" => {
+                log::trace!("Serializing UTCTime.");
+                self.set_tag(Identifier::UTC_TIME);
+            }
+            "ASN.1#GeneralizedTime" => {
+                log::trace!("Serializing GeneralizedTime.");
+                self.set_tag(Identifier::GENERALIZED_TIME);
+            }
+            "ASN.1#SetOf" => {
+                log::trace!("Serializing SET OF.");
+                self.set_tag(Identifier::SET);
+                self.set_of = true;
+            }
+            "ASN.1#Any" => {
+                log::trace!("Serializing ANY.");
+                self.raw = true;
+            }
             "ASN.1#Implicit" => {
                 log::trace!("Serializing implicit prefix.");
                 self.prefixed = Some(true);
@@ -355,18 +555,32 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
     fn serialize_newtype_variant<T>(
         self,
         name: &'static str,
-        _variant_index: u32,
-        _variant: &'static str,
+        variant_index: u32,
+        variant: &'static str,
         value: &T,
     ) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
         log::trace!("Serializing {}.", name);
-        let ser = Serializer::serialize_to_vec(value, true)?;
+        if name == crate::identifier::TAGGED_SEQUENCE_ENUM {
+            return self.encode_tagged_sequence(variant_index, Some(value));
+        }
+
+        let ser = Serializer::serialize_to_vec(value, true, self.config)?;
         self.constructed = ser.constructed;
-        self.set_tag(ser.tag.unwrap());
-        self.encode(&ser.output)
+        // A variant named after a tag is implicitly tagged with it.
+        match crate::identifier::tag_from_struct_name(variant).or(ser.tag) {
+            Some(tag) => {
+                self.set_tag(tag);
+                self.encode(&ser.output)
+            }
+            // Open types, such as an unknown variant's, are already encoded.
+            None => {
+                self.output.write_all(&ser.output)?;
+                Ok(())
+            }
+        }
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
@@ -396,6 +610,12 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
         len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
         log::trace!("Serializing {}", name);
+        if name == crate::identifier::TAGGED_SEQUENCE_ENUM {
+            return Err(Error::Custom(String::from(
+                "Only unit and newtype variants can be encoded as a tagged SEQUENCE.",
+            )));
+        }
+
         self.set_constructed();
         self.serialize_seq(Some(len))
     }
@@ -408,7 +628,23 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
 
     fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
         log::trace!("Serializing {}", name);
-        self.serialize_map(Some(len))
+        let (name, _) = crate::identifier::strip_extension_marker(name);
+
+        if let Some(identifier) = crate::identifier::tag_from_struct_name(name) {
+            self.set_tag(identifier);
+        } else if name == crate::identifier::SET_STRUCT {
+            self.set_tag(Identifier::SET);
+        }
+
+        let mut sequence = self.serialize_map(Some(len))?;
+
+        if name == crate::identifier::AUTOMATIC_STRUCT {
+            sequence.next_field_tag = Some(0);
+        }
+
+        sequence.sort_by_tag = name == crate::identifier::SET_STRUCT;
+
+        Ok(sequence)
     }
 
     fn serialize_struct_variant(
@@ -427,10 +663,23 @@ impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
 pub struct Sequence<'a, W: Write> {
     ser: &'a mut Serializer<W>,
     sink: SerializerKind,
+    /// The encoded key of the map entry currently being serialized.
+    key: Option<Vec<u8>>,
+    /// The number of the context-specific tag given to the next field, if
+    /// the fields are automatically tagged.
+    next_field_tag: Option<u32>,
+    /// Whether the fields are those of a SET, and so are reordered by their
+    /// tags once they're all encoded.
+    sort_by_tag: bool,
 }
 
 impl<'a, W: Write> Sequence<'a, W> {
     fn new(ser: &'a mut Serializer<W>) -> Self {
+        if std::mem::take(&mut ser.set_of) {
+            let sink = SerializerKind::SetOf(Vec::new(), ser.config);
+            return Self { ser, sink, key: None, next_field_tag: None, sort_by_tag: false };
+        }
+
         let sink = match ser.tag {
             Some(Identifier::OCTET_STRING) => {
                 SerializerKind::OctetString(ByteSerializer::new())
@@ -446,13 +695,13 @@ impl<'a, W: Write> Sequence<'a, W> {
             }
             _ => {
                 match ser.prefixed {
-                    Some(implicit) => SerializerKind::Prefix(PrefixSerializer::new(implicit)),
-                    _ => SerializerKind::Normal(Serializer::new(Vec::new())),
+                    Some(implicit) => SerializerKind::Prefix(PrefixSerializer::new(implicit, ser.config)),
+                    _ => SerializerKind::Normal(Serializer::new(Vec::new(), ser.config)),
                 }
             },
         };
 
-        Self { ser, sink }
+        Self { ser, sink, key: None, next_field_tag: None, sort_by_tag: false }
     }
 }
 
@@ -479,8 +728,16 @@ impl<'a, W: Write> ser::SerializeSeq for Sequence<'a, W> {
             _ => self.ser.tag.or(Some(Identifier::SEQUENCE)),
         };
 
+        let segment_size = match self.sink {
+            SerializerKind::BitString(_) => self.ser.config.bit_string_segment_size,
+            _ => None,
+        };
+
         let contents = self.sink.output();
-        self.ser.encode(&contents)
+        match segment_size {
+            Some(size) => self.ser.encode_segmented_bit_string(&contents, size),
+            None => self.ser.encode(&contents),
+        }
     }
 }
 
@@ -488,18 +745,33 @@ impl<'a, W: Write> ser::SerializeMap for Sequence<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_value<T>(&mut self, value: &T) -> Result<Self::Ok>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        ser::SerializeSeq::serialize_element(self, value)
+        self.key = Some(Serializer::serialize_to_vec(key, false, self.ser.config)?.output);
+        Ok(())
     }
 
-    fn serialize_key<T>(&mut self, _key: &T) -> Result<()>
+    /// Encodes the value along with the previously serialized key as a
+    /// `SEQUENCE { key, value }` entry.
+    fn serialize_value<T>(&mut self, value: &T) -> Result<Self::Ok>
     where
         T: ?Sized + Serialize,
     {
-        Ok(())
+        let mut entry = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Custom(String::from("Map value serialized without a key.")))?;
+        entry.extend(Serializer::serialize_to_vec(value, false, self.ser.config)?.output);
+
+        match self.sink {
+            SerializerKind::Normal(ref mut ser) => {
+                ser.set_tag(Identifier::SEQUENCE);
+                ser.encode(&entry)
+            }
+            _ => Err(Error::Custom(String::from("Maps can only be encoded as a SEQUENCE."))),
+        }
     }
 
     fn end(self) -> Result<()> {
@@ -511,18 +783,70 @@ impl<'a, W: Write> ser::SerializeStruct for Sequence<'a, W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        ser::SerializeSeq::serialize_element(self, value)
+        let (field_tag, explicit) = match self.next_field_tag {
+            Some(number) => {
+                self.next_field_tag = Some(number + 1);
+                (Identifier::new(Class::Context, number), false)
+            }
+            None => match crate::identifier::tag_from_field_name(key) {
+                Some(field_tag) => field_tag,
+                None => return ser::SerializeSeq::serialize_element(self, value),
+            },
+        };
+
+        let field = Serializer::serialize_to_vec(value, true, self.ser.config)?;
+
+        match (field.tag, &mut self.sink) {
+            // Absent OPTIONAL fields don't produce a tag, or anything else.
+            (None, _) => Ok(()),
+            // An explicit tag is constructed, and holds the field's complete
+            // encoding rather than replacing its tag.
+            (Some(tag), SerializerKind::Normal(ref mut ser)) if explicit => {
+                let mut inner = Serializer::new(Vec::new(), ser.config);
+                inner.constructed = field.is_constructed(tag);
+                inner.set_tag(tag);
+                inner.encode(&field.output)?;
+
+                ser.set_constructed();
+                ser.set_tag(field_tag);
+                ser.encode(&inner.output)
+            }
+            (Some(tag), SerializerKind::Normal(ref mut ser)) => {
+                if field.is_constructed(tag) {
+                    ser.set_constructed();
+                }
+
+                ser.set_tag(field_tag);
+                ser.encode(&field.output)
+            }
+            _ => Err(Error::Custom(String::from("Tagged fields can only be encoded in a SEQUENCE."))),
+        }
     }
 
-    fn end(self) -> Result<()> {
+    fn end(mut self) -> Result<()> {
+        if self.sort_by_tag {
+            if let SerializerKind::Normal(ref mut ser) = self.sink {
+                ser.output = sort_by_tag(&ser.output)?;
+            }
+        }
+
         ser::SerializeSeq::end(self)
     }
 }
 
+/// Orders the encoded elements of a SET by their tags, which DER requires
+/// (X.690 10.3) whatever order the struct's fields are declared in.
+fn sort_by_tag(contents: &[u8]) -> Result<Vec<u8>> {
+    let mut elements = crate::children(contents).collect::<Result<Vec<_>>>()?;
+    elements.sort_by_key(|element| (element.identifier().class, element.identifier().tag));
+
+    Ok(elements.iter().flat_map(|element| element.as_bytes()).copied().collect())
+}
+
 impl<'a, W: Write> ser::SerializeTuple for Sequence<'a, W> {
     type Ok = ();
     type Error = Error;
@@ -589,6 +913,8 @@ impl<'a, W: Write> ser::SerializeStructVariant for Sequence<'a, W> {
 }
 
 enum SerializerKind {
+    /// The encodings of a SET OF's elements, sorted once they're all known.
+    SetOf(Vec<Vec<u8>>, EncoderConfig),
     BitString(BitStringSerializer),
     Integer(ByteSerializer),
     Normal(Serializer<Vec<u8>>),
@@ -600,6 +926,10 @@ enum SerializerKind {
 impl SerializerKind {
     fn serialize<T: ser::Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
         match self {
+            SerializerKind::SetOf(elements, config) => {
+                elements.push(Serializer::serialize_to_vec(value, false, *config)?.output);
+                Ok(())
+            }
             SerializerKind::BitString(ser) => value.serialize(ser),
             SerializerKind::Integer(ser) => value.serialize(ser),
             SerializerKind::Normal(ser) => value.serialize(ser),
@@ -612,9 +942,20 @@ impl SerializerKind {
     fn output(self) -> Vec<u8> {
         match self {
             SerializerKind::BitString(mut ser) => {
-                ser.output.insert(0, ser.last.trailing_zeros() as u8);
+                // A zero octet, or none at all, has no unused bits to mark.
+                let unused_bits = match ser.last {
+                    0 => 0,
+                    last => last.trailing_zeros() as u8,
+                };
+                ser.output.insert(0, unused_bits);
                 ser.output
             },
+            SerializerKind::SetOf(mut elements, _) => {
+                // DER orders the elements by their encodings (X.690 11.6),
+                // and BER allows any order, so they're always sorted.
+                elements.sort();
+                elements.concat()
+            }
             SerializerKind::Normal(ser) => ser.output,
             SerializerKind::Integer(ser) => ser.output,
             SerializerKind::OctetString(ser) => ser.output,
@@ -646,6 +987,35 @@ mod tests {
         assert_eq!(&[2, 3, 0, 0xff, 0xff][..], &*to_vec(&multi_byte_integer).unwrap());
     }
 
+    #[test]
+    fn reused_buffer() {
+        let mut buffer = Vec::new();
+
+        for i in 0..10_000u32 {
+            let value = (i, i % 2 == 0, format!("{}", i));
+            encode_into(&mut buffer, &value).unwrap();
+
+            assert_eq!(to_vec(&value).unwrap(), buffer);
+        }
+    }
+
+    #[test]
+    fn real() {
+        assert_eq!(&[9, 0][..], &*to_vec(&0.0).unwrap());
+        assert_eq!(&[9, 3, 0x80, 0, 1][..], &*to_vec(&1.0).unwrap());
+        assert_eq!(&[9, 3, 0x80, 0xff, 1][..], &*to_vec(&0.5).unwrap());
+        // The mantissa is always odd, with the factors of two moved into
+        // the exponent.
+        assert_eq!(&[9, 3, 0x80, 1, 5][..], &*to_vec(&10.0).unwrap());
+        assert_eq!(&[9, 3, 0xC0, 0, 3][..], &*to_vec(&-3.0f32).unwrap());
+        assert_eq!(&[9, 1, 0x40][..], &*to_vec(&f64::INFINITY).unwrap());
+        assert_eq!(&[9, 1, 0x43][..], &*to_vec(&-0.0).unwrap());
+
+        for &value in &[2.75, -1.0e300, 5.0e-324, f64::MAX, f64::MIN_POSITIVE] {
+            assert_eq!(value, crate::from_slice::<f64>(&to_vec(&value).unwrap()).unwrap());
+        }
+    }
+
     #[test]
     fn universal_string() {
         assert_eq!(
@@ -673,14 +1043,22 @@ mod tests {
     #[test]
     fn enumerated() {
         use core::types::{Enumerable, Enumerated};
-        #[derive(Clone, Debug, Serialize, PartialEq)]
+        #[derive(Clone, Debug, PartialEq)]
         enum Foo {
             Ein,
             Zwei,
             Drei,
         }
 
-        impl Enumerable for Foo {}
+        impl Enumerable for Foo {
+            fn to_enumerated(&self) -> i64 {
+                self.clone() as i64
+            }
+
+            fn from_enumerated(value: i64) -> Option<Self> {
+                [Foo::Ein, Foo::Zwei, Foo::Drei].get(value as usize).cloned()
+            }
+        }
 
         let ein = Enumerated::new(Foo::Ein);
         let zwei = Enumerated::new(Foo::Zwei);
@@ -691,6 +1069,21 @@ mod tests {
         assert_eq!(&[0xA, 1, 2][..], &*to_vec(&drei).unwrap());
     }
 
+    #[test]
+    fn bare_unit_variant() {
+        #[derive(Serialize)]
+        enum Color {
+            Green,
+        }
+
+        #[derive(Serialize)]
+        struct Palette {
+            favourite_color: Color,
+        }
+
+        assert!(to_vec(&Palette { favourite_color: Color::Green }).is_err());
+    }
+
     #[test]
     fn choice() {
         #[derive(Clone, Debug, Serialize, PartialEq)]
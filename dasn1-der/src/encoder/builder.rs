@@ -0,0 +1,101 @@
+use core::{identifier::Identifier, types::ObjectIdentifier};
+use num_bigint::BigInt;
+
+use super::object_identifier::encode_component;
+
+/// Assembles DER values by hand, for one-off structures that don't warrant
+/// defining a type. Each method appends a complete value, and constructed
+/// values are built with a nested builder.
+///
+/// ```
+/// use dasn1_der::DerBuilder;
+///
+/// let mut builder = DerBuilder::new();
+/// builder.sequence(|sequence| {
+///     sequence.integer(1).octet_string(&[1, 2]);
+/// });
+///
+/// assert_eq!(&[0x30, 7, 0x2, 1, 1, 0x4, 2, 1, 2][..], &*builder.finish());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DerBuilder {
+    output: Vec<u8>,
+}
+
+impl DerBuilder {
+    /// Instantiates a new empty `DerBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a BOOLEAN.
+    pub fn bool(&mut self, value: bool) -> &mut Self {
+        self.value(Identifier::BOOL, false, &[if value { 0xff } else { 0 }])
+    }
+
+    /// Appends an INTEGER.
+    pub fn integer(&mut self, value: i64) -> &mut Self {
+        self.value(Identifier::INTEGER, false, &BigInt::from(value).to_signed_bytes_be())
+    }
+
+    /// Appends an OCTET STRING.
+    pub fn octet_string(&mut self, bytes: &[u8]) -> &mut Self {
+        self.value(Identifier::OCTET_STRING, false, bytes)
+    }
+
+    /// Appends an OBJECT IDENTIFIER.
+    pub fn oid(&mut self, oid: &ObjectIdentifier) -> &mut Self {
+        let mut contents = Vec::new();
+        let components = oid.as_ref();
+
+        // The first two components share the first subidentifier (X.690 8.19.4).
+        // An OID shortened through `DerefMut` is encoded as if its missing
+        // second component were zero.
+        if let Some(&first) = components.first() {
+            let second = components.get(1).copied().unwrap_or(0);
+            encode_component(u64::from(first) * 40 + u64::from(second), &mut contents);
+        }
+
+        for &component in components.iter().skip(2) {
+            encode_component(u64::from(component), &mut contents);
+        }
+
+        self.value(Identifier::OBJECT_IDENTIFIER, false, &contents)
+    }
+
+    /// Appends a SEQUENCE of the values appended by `build`.
+    pub fn sequence<F: FnOnce(&mut DerBuilder)>(&mut self, build: F) -> &mut Self {
+        self.constructed(Identifier::SEQUENCE, build)
+    }
+
+    /// Appends the value appended by `build`, explicitly tagged with `tag`.
+    pub fn tagged<F: FnOnce(&mut DerBuilder)>(&mut self, tag: Identifier, build: F) -> &mut Self {
+        self.constructed(tag, build)
+    }
+
+    /// Appends a value already encoded elsewhere, as is.
+    pub fn raw(&mut self, encoded: &[u8]) -> &mut Self {
+        self.output.extend_from_slice(encoded);
+        self
+    }
+
+    /// Consumes `self` and returns the values appended so far.
+    pub fn finish(self) -> Vec<u8> {
+        self.output
+    }
+
+    fn constructed<F: FnOnce(&mut DerBuilder)>(&mut self, tag: Identifier, build: F) -> &mut Self {
+        let mut contents = DerBuilder::new();
+        build(&mut contents);
+
+        self.value(tag, true, &contents.output)
+    }
+
+    fn value(&mut self, tag: Identifier, constructed: bool, contents: &[u8]) -> &mut Self {
+        // Encoding into a `Vec` can't fail, as the tag is always given.
+        let encoded = super::encode_value(tag, constructed, contents)
+            .expect("Encoding into a Vec failed.");
+
+        self.raw(&encoded)
+    }
+}
@@ -0,0 +1,63 @@
+/// The set of encoding rules to encode or decode with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EncodingRules {
+    /// Basic Encoding Rules. Constructed values are encoded with the
+    /// indefinite length form, terminated by end-of-contents octets. When
+    /// decoding, either length form is accepted.
+    Ber,
+    /// Distinguished Encoding Rules. All values are encoded with the
+    /// definite length form.
+    Der,
+}
+
+/// Options controlling how values are encoded, used with `to_vec_with`.
+///
+/// Besides the encoding rules, there are options producing valid BER that
+/// isn't canonical, for testing how other decoders handle it. The defaults
+/// produce canonical DER.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EncoderConfig {
+    pub(crate) rules: EncodingRules,
+    pub(crate) min_length_octets: usize,
+    pub(crate) bit_string_segment_size: Option<usize>,
+}
+
+impl EncoderConfig {
+    /// Instantiates a new `EncoderConfig` with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the encoding rules to encode with. Default: `EncodingRules::Der`.
+    pub fn rules(mut self, rules: EncodingRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Sets the fewest octets following the initial length octet, padded
+    /// with leading zeros, so that definite lengths use the long form even
+    /// where the short form would do. At most 126 octets are used, as
+    /// X.690 8.1.3.5 reserves more. Default: `0`, the shortest form.
+    pub fn min_length_octets(mut self, octets: usize) -> Self {
+        self.min_length_octets = octets.min(126);
+        self
+    }
+
+    /// Sets the most octets of bits in each segment of a BIT STRING, which
+    /// is then encoded in the constructed form (X.690 8.6.3), or `None` to
+    /// always use the primitive form. Default: `None`.
+    pub fn bit_string_segment_size(mut self, size: Option<usize>) -> Self {
+        self.bit_string_segment_size = size.map(|size| size.max(1));
+        self
+    }
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            rules: EncodingRules::Der,
+            min_length_octets: 0,
+            bit_string_segment_size: None,
+        }
+    }
+}
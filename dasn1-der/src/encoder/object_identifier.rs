@@ -18,8 +18,11 @@ impl ObjectIdentifierSerializer {
     }
 }
 
-fn encode_component(mut v: u32, writer: &mut Vec<u8>) -> Result<()> {
-    let mut bytes: Vec<u8> = Vec::new();
+/// Writes `v` in base 128, with the high bit set on all but the last octet.
+pub(super) fn encode_component(mut v: u64, writer: &mut Vec<u8>) {
+    // A zero component still takes an octet.
+    let mut bytes: Vec<u8> = vec![(v & 0x7f) as u8];
+    v >>= 7;
 
     while v != 0 {
         bytes.push((v & 0x7f) as u8);
@@ -33,8 +36,6 @@ fn encode_component(mut v: u32, writer: &mut Vec<u8>) -> Result<()> {
 
     let final_octet = bytes[0] as u8;
     writer.push(final_octet);
-
-    Ok(())
 }
 
 impl<'a> ser::Serializer for &'a mut ObjectIdentifierSerializer {
@@ -55,9 +56,11 @@ impl<'a> ser::Serializer for &'a mut ObjectIdentifierSerializer {
             0 => self.first_component = Some(v),
             1 => {
                 let first = self.first_component.unwrap();
-                encode_component((first * 40) + v, &mut self.output)?;
+                // The first two components share the first subidentifier
+                // (X.690 8.19.4).
+                encode_component(u64::from(first) * 40 + u64::from(v), &mut self.output);
             },
-            _ => encode_component(v, &mut self.output)?,
+            _ => encode_component(u64::from(v), &mut self.output),
         }
 
         self.len += 1;
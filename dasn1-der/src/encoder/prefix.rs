@@ -5,7 +5,7 @@ use serde::{
 };
 
 use crate::error::{Error, Result};
-use super::Serializer;
+use super::{EncoderConfig, Serializer};
 
 /// Serializer used solely to encode octet strings properly.
 pub(crate) struct PrefixSerializer {
@@ -15,8 +15,8 @@ pub(crate) struct PrefixSerializer {
 }
 
 impl PrefixSerializer {
-    pub fn new(implicit: bool) -> Self {
-        let mut ser = Serializer::new(Vec::new());
+    pub fn new(implicit: bool, config: EncoderConfig) -> Self {
+        let mut ser = Serializer::new(Vec::new(), config);
         ser.implicit = implicit;
         Self {
             output: ser,
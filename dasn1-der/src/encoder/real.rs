@@ -0,0 +1,68 @@
+/// The contents octets of a REAL value in the canonical DER form, which is
+/// base 2 with no scaling factor and an odd mantissa. (X.690 8.5 & 11.3.1)
+pub(crate) fn encode_real(value: f64) -> Vec<u8> {
+    if value == 0.0 {
+        return if value.is_sign_negative() { vec![0x43] } else { Vec::new() };
+    } else if value.is_nan() {
+        return vec![0x42];
+    } else if value.is_infinite() {
+        return vec![if value.is_sign_negative() { 0x41 } else { 0x40 }];
+    }
+
+    let bits = value.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+
+    // Subnormal numbers have no implicit leading bit.
+    let (mut mantissa, mut exponent) = if biased_exponent == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), biased_exponent - 1075)
+    };
+
+    let trailing_zeros = mantissa.trailing_zeros();
+    mantissa >>= trailing_zeros;
+    exponent += trailing_zeros as i32;
+
+    let exponent_octets = minimal_signed(exponent);
+    let mantissa_octets = mantissa.to_be_bytes();
+    let mantissa_octets = &mantissa_octets[mantissa.leading_zeros() as usize / 8..];
+
+    let mut initial_octet = 0x80;
+
+    if value.is_sign_negative() {
+        initial_octet |= 0x40;
+    }
+
+    // The exponent is never longer than two octets, so it never needs the
+    // separate exponent length octet.
+    initial_octet |= exponent_octets.len() as u8 - 1;
+
+    let mut contents = vec![initial_octet];
+    contents.extend_from_slice(&exponent_octets);
+    contents.extend_from_slice(mantissa_octets);
+    contents
+}
+
+/// The shortest two's complement encoding of `n`.
+fn minimal_signed(n: i32) -> Vec<u8> {
+    let bytes = n.to_be_bytes();
+    let mut start = 0;
+
+    // An octet can be dropped if it only repeats the sign of the next one.
+    while start < bytes.len() - 1 {
+        let redundant = match bytes[start] {
+            0 => bytes[start + 1] & 0x80 == 0,
+            0xff => bytes[start + 1] & 0x80 != 0,
+            _ => false,
+        };
+
+        if !redundant {
+            break;
+        }
+
+        start += 1;
+    }
+
+    bytes[start..].to_vec()
+}
@@ -4,7 +4,7 @@ use std::{error, fmt, io};
 use nom::Err;
 use serde::{de, ser};
 
-use core::identifier::Identifier;
+use core::{identifier::Identifier, types::ConstraintError};
 
 /// Alias for a `Result` with the error type `asn1_der::Error`.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -25,6 +25,50 @@ pub enum Error {
     IntegerOverflow(String),
     /// Malformed ASN.1 DER.
     Parser(String),
+    /// The input ended before the identifier, length, or contents octets of
+    /// a value did.
+    UnexpectedEof,
+    /// A value read by a `FrameReader` was longer than its maximum frame
+    /// length.
+    FrameTooLarge {
+        /// The maximum length of a frame, in octets.
+        max: usize,
+    },
+    /// A value used the indefinite length form, which DER doesn't allow
+    /// (X.690 10.1).
+    IndefiniteLengthInDer,
+    /// A BIT STRING's initial octet was above 7, or wasn't 0 in an empty BIT
+    /// STRING (X.690 8.6.2).
+    InvalidUnusedBits(u8),
+    /// A BIT STRING had unused bits that weren't zero, which DER doesn't
+    /// allow (X.690 11.2.1).
+    NonZeroUnusedBits,
+    /// An arc of an OBJECT IDENTIFIER was encoded with a leading `0x80`
+    /// octet, rather than in the fewest octets (X.690 8.19.2).
+    NonMinimalSubidentifier,
+    /// The elements of a SET OF weren't sorted by their encodings, which DER
+    /// requires (X.690 11.6).
+    UnsortedSetOf,
+    /// The elements of a SET weren't in the canonical order of their tags,
+    /// which DER requires (X.690 10.3).
+    UnsortedSet,
+    /// A SEQUENCE ended before one of its required fields.
+    MissingField {
+        /// Position of the first missing field.
+        index: usize,
+    },
+    /// A SEQUENCE that isn't extensible had more elements than fields.
+    UnexpectedField,
+    /// A decoded value didn't satisfy its type's constraints.
+    Constraint(ConstraintError),
+    /// A value used the constructed form where its type requires the
+    /// primitive form, or the other way around (X.690 8.1.2.5).
+    IncorrectForm {
+        /// Tag of the value.
+        identifier: Identifier,
+        /// Whether the value used the constructed form.
+        constructed: bool,
+    },
     /// Expected a tag other than what was provided.
     IncorrectType {
         /// Tag that was expected.
@@ -32,6 +76,14 @@ pub enum Error {
         /// Tag that was found.
         actual: Identifier
     },
+    /// Decoding a field of a struct failed.
+    Field {
+        /// Where the field is, starting from the outermost struct, such as
+        /// `Certificate.tbsCertificate.serialNumber`.
+        path: String,
+        /// Why the field failed to decode.
+        error: Box<Error>,
+    },
 }
 
 impl de::Error for Error {
@@ -46,7 +98,15 @@ impl ser::Error for Error {
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Field { error, .. } => Some(&**error),
+            Error::Constraint(error) => Some(error),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -56,12 +116,32 @@ impl fmt::Display for Error {
             Error::Io(error) => write!(f, "IO: {}", error),
             Error::NoVariantFound(index) => write!(f, "No variant found with index '{}'.", index),
             Error::Parser(msg) => write!(f, "Parsing: {}", msg),
+            Error::UnexpectedEof => write!(f, "Unexpected end of input"),
+            Error::FrameTooLarge { max } => write!(f, "Frame is longer than the maximum of {} octets", max),
+            Error::IndefiniteLengthInDer => write!(f, "Indefinite length form isn't allowed in DER"),
+            Error::InvalidUnusedBits(bits) => write!(f, "Invalid number of unused bits in BIT STRING: {}", bits),
+            Error::NonZeroUnusedBits => write!(f, "Unused bits in BIT STRING must be zero in DER"),
+            Error::NonMinimalSubidentifier => write!(f, "OBJECT IDENTIFIER arcs must be encoded in the fewest octets"),
+            Error::UnsortedSetOf => write!(f, "Elements of SET OF must be sorted in DER"),
+            Error::UnsortedSet => write!(f, "Elements of SET must be in tag order in DER"),
+            Error::MissingField { index } => write!(f, "Missing required field at position {}", index),
+            Error::UnexpectedField => write!(f, "Unexpected element after the last field"),
+            Error::Constraint(error) => write!(f, "{}", error),
             Error::IntegerOverflow(number) => write!(f, "Couldn't cast big int to {}", number),
+            Error::IncorrectForm { identifier, constructed: true } => write!(f, "{:?} must use the primitive form", identifier),
+            Error::IncorrectForm { identifier, constructed: false } => write!(f, "{:?} must use the constructed form", identifier),
             Error::IncorrectType { expected, actual } => write!(f, "Found {:?}, expected: {:?}", actual, expected),
+            Error::Field { path, error } => write!(f, "{}: {}", path, error),
         }
     }
 }
 
+impl From<ConstraintError> for Error {
+    fn from(error: ConstraintError) -> Self {
+        Error::Constraint(error)
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(error: io::Error) -> Self {
         Error::Io(error)
@@ -70,6 +150,11 @@ impl From<io::Error> for Error {
 
 impl<I: std::fmt::Debug> From<Err<I>> for Error {
     fn from(nom_error: Err<I>) -> Self {
-        Error::Parser(format!("{:?}", nom_error))
+        match nom_error {
+            // The parsers are streaming, so running out of input is only
+            // reported as needing more of it.
+            Err::Incomplete(_) => Error::UnexpectedEof,
+            error => Error::Parser(format!("{:?}", error)),
+        }
     }
 }
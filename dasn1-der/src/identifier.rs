@@ -38,6 +38,98 @@ impl std::ops::Deref for BerIdentifier {
     }
 }
 
+/// The prefix of a struct name that replaces its SEQUENCE tag with another
+/// tag, such as `ASN.1#[APPLICATION 1]`.
+pub(crate) const TAGGED_STRUCT: &str = "ASN.1#[";
+
+/// Parses the tag out of a struct name starting with `TAGGED_STRUCT`, where
+/// the class is `UNIVERSAL`, `APPLICATION`, or `PRIVATE`, and is
+/// context-specific if it's left out (e.g. `ASN.1#[0]`).
+pub(crate) fn tag_from_struct_name(name: &str) -> Option<Identifier> {
+    let tag = name.strip_prefix(TAGGED_STRUCT)?.strip_suffix(']')?;
+    let mut parts = tag.split_whitespace().rev();
+    let number = parts.next()?.parse().ok()?;
+
+    let class = match parts.next() {
+        None => Class::Context,
+        Some("UNIVERSAL") => Class::Universal,
+        Some("APPLICATION") => Class::Application,
+        Some("PRIVATE") => Class::Private,
+        Some(_) => return None,
+    };
+
+    match parts.next() {
+        None => Some(Identifier::new(class, number)),
+        Some(_) => None,
+    }
+}
+
+/// The name of a struct whose fields are implicitly tagged with context-specific
+/// tags numbered in declaration order, like a SEQUENCE in a module with
+/// `AUTOMATIC TAGS`.
+pub(crate) const AUTOMATIC_STRUCT: &str = "ASN.1#Automatic";
+
+/// The name of a struct encoded as a SET, whose fields are all renamed after
+/// distinct tags (e.g. `ASN.1#[0]`) so they can be decoded in any order.
+pub(crate) const SET_STRUCT: &str = "ASN.1#Set";
+
+/// The suffix of a struct name marking the SEQUENCE as extensible, such as
+/// `ASN.1#[APPLICATION 1] ...`, in which case elements following the last
+/// field are skipped rather than rejected when decoding.
+pub(crate) const EXTENSION_MARKER: &str = "...";
+
+/// Splits the extension marker off of a struct name, returning the rest of
+/// the name and whether it was there.
+pub(crate) fn strip_extension_marker(name: &str) -> (&str, bool) {
+    match name.strip_suffix(EXTENSION_MARKER) {
+        Some(name) => (name.trim_end(), true),
+        None => (name, false),
+    }
+}
+
+/// The name `serde` gives `PhantomData`, which has no encoding, so a marker
+/// field takes up no space in its SEQUENCE.
+pub(crate) const PHANTOM_DATA: &str = "PhantomData";
+
+/// The suffix of a field name marking its tag as explicit, such as
+/// `ASN.1#[3] EXPLICIT`, in which case the tag is wrapped around the field's
+/// value rather than replacing the value's own tag.
+pub(crate) const EXPLICIT_FIELD: &str = "EXPLICIT";
+
+/// The name of an enum encoded as a SEQUENCE of the variant's index as an
+/// ENUMERATED, followed by the variant's value, rather than as a CHOICE.
+pub(crate) const TAGGED_SEQUENCE_ENUM: &str = "ASN.1#TaggedSequence";
+
+/// Parses the tag out of a field name renamed after one, and whether the tag
+/// is explicit.
+pub(crate) fn tag_from_field_name(name: &str) -> Option<(Identifier, bool)> {
+    match name.strip_suffix(EXPLICIT_FIELD) {
+        Some(name) => Some((tag_from_struct_name(name.trim_end())?, true)),
+        None => Some((tag_from_struct_name(name)?, false)),
+    }
+}
+
+/// How a struct or field is named in the path of a decoding error. Tagged
+/// names are shortened to their tag (e.g. `[0]`), and automatically tagged
+/// structs and SETs have no name of their own.
+pub(crate) fn path_name(name: &str) -> Option<&str> {
+    if name == AUTOMATIC_STRUCT || name == SET_STRUCT {
+        None
+    } else if name.starts_with(TAGGED_STRUCT) {
+        Some(&name[TAGGED_STRUCT.len() - 1..])
+    } else {
+        Some(name)
+    }
+}
+
+/// Compares only the class and tag number, as the constructed bit is a detail
+/// of how the value was encoded rather than part of the type's identity.
+impl PartialEq<Identifier> for BerIdentifier {
+    fn eq(&self, other: &Identifier) -> bool {
+        self.identifier == *other
+    }
+}
+
 impl From<Identifier> for BerIdentifier {
     fn from(identifier: Identifier) -> Self {
         Self {
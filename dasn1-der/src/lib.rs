@@ -4,15 +4,93 @@
 //! way to encode a value) and for when you have all of the data needed to
 //! encode. DER is used in frequently in cryptography (X.509 certificates,
 //! PKCS#12).
+//!
+//! ## CHOICE
+//! Enums are decoded as a CHOICE by matching the context-specific tag number
+//! of the value against the index of each variant. A variant which contains
+//! another CHOICE has no tag of its own, and should be renamed with a name
+//! starting with `ASN.1#Untagged` (e.g. `#[serde(rename = "ASN.1#Untagged")]`).
+//! Any tag not matching one of the other variants is then decoded as the
+//! untagged variant.
+//!
+//! For forward compatibility, a CHOICE can also have a variant containing an
+//! `AnyValue` renamed with a name starting with `ASN.1#Unknown`, which any
+//! tag not matching another variant is decoded as, keeping its complete
+//! encoding. An unknown variant is encoded as the value it holds.
+//!
+//! A variant can instead be given any class and number by renaming it after
+//! its tag, in the same form as for tagged SEQUENCEs below (e.g.
+//! `#[serde(rename = "ASN.1#[APPLICATION 0]")]`). Its value is then
+//! implicitly tagged with that tag, and only a value with the same class and
+//! number decodes as that variant.
+//!
+//! A CHOICE is only ever told apart by the identifier of its value, never by
+//! its contents, whether or not it's OPTIONAL. The contents of an ENUMERATED
+//! value are only read as a discriminant when decoding an `Enumerated`, so a
+//! CHOICE is never decoded from a `[UNIVERSAL 10]` value's integer. An
+//! OPTIONAL CHOICE is absent if the next element's tag is none of its
+//! variants', leaving the element for the fields after it.
+//!
+//! An enum renamed `ASN.1#TaggedSequence` isn't a CHOICE, but a
+//! `SEQUENCE { type ENUMERATED, value CHOICE }` holding the index of the
+//! variant, followed by the variant's value in its own encoding. Only unit
+//! and newtype variants are supported, and a unit variant has no value.
+//!
+//! ## Tagged SEQUENCEs
+//! Structs are encoded as a SEQUENCE, unless they are renamed with a name of
+//! the form `ASN.1#[CLASS NUMBER]` (e.g.
+//! `#[serde(rename = "ASN.1#[APPLICATION 1]")]`), in which case the SEQUENCE
+//! is implicitly tagged with that tag instead. The class may be `UNIVERSAL`,
+//! `APPLICATION`, or `PRIVATE`, and is context-specific if left out.
+//!
+//! Alternatively a struct renamed to `ASN.1#Automatic` has each of its fields
+//! implicitly tagged with a context-specific tag numbered by the field's
+//! position (`[0]`, `[1]`, ...), as if it were in a module with
+//! `AUTOMATIC TAGS`. Absent `Option` fields keep their number, so the tags of
+//! the other fields don't change.
+//!
+//! A single field can be implicitly tagged by renaming it after its tag (e.g.
+//! `#[serde(rename = "ASN.1#[0]")]`). An `Option` field tagged this way is
+//! only decoded from the next element if the element has the field's tag, so
+//! `SEQUENCE { a INTEGER, b [0] INTEGER OPTIONAL, c [1] BOOLEAN OPTIONAL }`
+//! decodes with any combination of `b` and `c` present.
+//!
+//! Ending the name with `EXPLICIT` (e.g. `#[serde(rename = "ASN.1#[3] EXPLICIT")]`)
+//! tags the field explicitly instead, wrapping the field's own encoding in a
+//! constructed value with that tag, like `[3] EXPLICIT SEQUENCE OF Extension
+//! OPTIONAL` in an X.509 certificate. When deriving `AsnType`, the field's tag
+//! is given with `#[asn(tag(context, 3), explicit)]` too, and the derive
+//! checks that it agrees with the field's name.
+//!
+//! A SEQUENCE is decoded as a struct only if it has no elements past the
+//! struct's last field. If the SEQUENCE has an extension marker (`...`), end
+//! the struct's name with `...` (e.g. `#[serde(rename = "Ticket ...")]` or
+//! `#[serde(rename = "ASN.1#Automatic ...")]`), and any elements added by
+//! later versions of the type are skipped instead.
+//!
+//! ## SETs
+//! A struct renamed to `ASN.1#Set` is encoded as a SET, and every one of its
+//! fields must be renamed after a distinct tag. Its elements are decoded by
+//! matching their tags to the fields, so they can be in any order, though
+//! DER requires them in the order of their tags (X.690 10.3). They're always
+//! encoded in that order, whatever order the fields are declared in.
 #![deny(missing_docs)]
 
 mod decoder;
+mod dynamic;
 mod encoder;
 pub mod identifier;
 pub mod error;
 
-pub use decoder::from_slice;
-pub use encoder::to_vec;
+pub use decoder::{
+    canonicalize, children, from_slice, from_slice_expecting, from_slice_lenient,
+    from_slice_validated, from_slice_with, is_canonical_der, read_one, split_tlv, try_decode,
+    walk_tlv, ContentReader, DecodeState, DecoderConfig, ElementError, Event, FrameReader, Tlv,
+};
+pub use dynamic::{DecoderRegistry, DynEncode};
+pub use encoder::{
+    encode_into, to_vec, to_vec_with, to_writer, DerBuilder, EncoderConfig, EncodingRules,
+};
 pub use error::Result;
 
 #[cfg(test)]
@@ -101,14 +179,22 @@ mod tests {
 
     #[test]
     fn choice() {
-        #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+        #[derive(Clone, Debug, PartialEq)]
         enum Foo {
             Ein,
             Zwei,
             Drei,
         }
 
-        impl Enumerable for Foo {}
+        impl Enumerable for Foo {
+            fn to_enumerated(&self) -> i64 {
+                self.clone() as i64
+            }
+
+            fn from_enumerated(value: i64) -> Option<Self> {
+                [Foo::Ein, Foo::Zwei, Foo::Drei].get(value as usize).cloned()
+            }
+        }
 
         let ein = Enumerated::new(Foo::Ein);
         let zwei = Enumerated::new(Foo::Zwei);
@@ -217,6 +303,110 @@ mod tests {
     }
     */
 
+    #[test]
+    fn default_component() {
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        struct Extension {
+            id: u8,
+            #[serde(
+                default,
+                skip_serializing_if = "core::types::default::is_default",
+                deserialize_with = "core::types::default::deserialize"
+            )]
+            critical: bool,
+        }
+
+        let absent = [0x30, 3, 2, 1, 5];
+        let explicit = [0x30, 6, 2, 1, 5, 1, 1, 0];
+        let extension = Extension { id: 5, critical: false };
+
+        assert_eq!(&absent[..], &*to_vec(&extension).unwrap());
+        assert_eq!(extension, from_slice(&absent).unwrap());
+
+        match from_slice::<Extension>(&explicit) {
+            Err(error::Error::Field { path, .. }) => assert_eq!("Extension.critical", path),
+            result => panic!("expected the default value to be rejected, got {:?}", result),
+        }
+
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        assert_eq!(extension, from_slice_with(&explicit, ber).unwrap());
+
+        let critical = Extension { id: 5, critical: true };
+        assert_eq!(critical, from_slice(&to_vec(&critical).unwrap()).unwrap());
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Middle {
+            #[serde(default, deserialize_with = "core::types::default::deserialize")]
+            critical: bool,
+            id: u8,
+        }
+
+        assert_eq!(Middle { critical: false, id: 5 }, from_slice(&absent).unwrap());
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Config {
+            #[serde(default, deserialize_with = "core::types::default::deserialize")]
+            flags: Vec<bool>,
+            id: u8,
+        }
+
+        // A present component with an error inside it isn't mistaken for an
+        // absent one.
+        let malformed = [0x30, 8, 0x30, 3, 2, 1, 1, 2, 1, 5];
+
+        match from_slice::<Config>(&malformed) {
+            Err(error::Error::Field { path, .. }) => assert_eq!("Config.flags", path),
+            result => panic!("expected an error in the flags, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn set_fields_by_tag() {
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        #[serde(rename = "ASN.1#Set")]
+        struct Set {
+            #[serde(rename = "ASN.1#[0]")]
+            a: u8,
+            #[serde(rename = "ASN.1#[1]")]
+            b: Option<bool>,
+            #[serde(rename = "ASN.1#[2]")]
+            c: u8,
+        }
+
+        let set = Set { a: 5, b: Some(true), c: 7 };
+        let sorted = [0x31, 9, 0x80, 1, 5, 0x81, 1, 0xFF, 0x82, 1, 7];
+        let shuffled = [0x31, 9, 0x82, 1, 7, 0x80, 1, 5, 0x81, 1, 0xFF];
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+
+        assert_eq!(&sorted[..], &*to_vec(&set).unwrap());
+        assert_eq!(set, from_slice(&sorted).unwrap());
+        assert_eq!(set, from_slice_with(&shuffled, ber).unwrap());
+        assert!(matches!(from_slice::<Set>(&shuffled), Err(error::Error::UnsortedSet)));
+
+        let absent = [0x31, 6, 0x82, 1, 7, 0x80, 1, 5];
+        assert_eq!(Set { a: 5, b: None, c: 7 }, from_slice_with(&absent, ber).unwrap());
+
+        let unknown = [0x31, 6, 0x80, 1, 5, 0x83, 1, 0];
+        assert!(matches!(from_slice::<Set>(&unknown), Err(error::Error::UnexpectedField)));
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        #[serde(rename = "ASN.1#Set")]
+        struct Unordered {
+            #[serde(rename = "ASN.1#[2]")]
+            c: u8,
+            #[serde(rename = "ASN.1#[APPLICATION 0]")]
+            b: bool,
+            #[serde(rename = "ASN.1#[0]")]
+            a: u8,
+        }
+
+        // Application tags come before context-specific ones.
+        let unordered = Unordered { c: 7, b: true, a: 5 };
+        let encoded = to_vec(&unordered).unwrap();
+
+        assert_eq!(&[0x31, 9, 0x40, 1, 0xFF, 0x80, 1, 5, 0x82, 1, 7][..], &*encoded);
+        assert_eq!(unordered, from_slice(&encoded).unwrap());
+    }
 
     #[test]
     fn object_identifier() {
@@ -234,6 +424,15 @@ mod tests {
         );
         assert_eq!(rsa.clone(), from_slice(&to_vec(&rsa).unwrap()).unwrap());
         assert_eq!(pkcs.clone(), from_slice(&to_vec(&pkcs).unwrap()).unwrap());
+
+        // Zero arcs, and a second arc past 39 under the root arc 2.
+        let zeroes = ObjectIdentifier::new(vec![0, 0, 0]).unwrap();
+        let example = ObjectIdentifier::new(vec![2, 999, 3]).unwrap();
+
+        assert_eq!(vec![0x6, 0x2, 0x0, 0x0], to_vec(&zeroes).unwrap());
+        assert_eq!(zeroes.clone(), from_slice(&to_vec(&zeroes).unwrap()).unwrap());
+        assert_eq!(vec![0x6, 0x3, 0x88, 0x37, 0x3], to_vec(&example).unwrap());
+        assert_eq!(example.clone(), from_slice(&to_vec(&example).unwrap()).unwrap());
     }
 
     #[test]
@@ -251,6 +450,399 @@ mod tests {
         assert_eq!(bits, from_slice(&to_vec(&bits).unwrap()).unwrap());
     }
 
+    #[test]
+    fn restricted_strings() {
+        let numeric = NumericString::new("0123 456").unwrap();
+        let printable = PrintableString::new("Hello, World.").unwrap();
+        let ia5 = Ia5String::new("user@example.com").unwrap();
+
+        assert_eq!(&[0x12, 8][..], &to_vec(&numeric).unwrap()[..2]);
+        assert_eq!(numeric, from_slice(&to_vec(&numeric).unwrap()).unwrap());
+        assert_eq!(printable, from_slice(&to_vec(&printable).unwrap()).unwrap());
+        assert_eq!(ia5, from_slice(&to_vec(&ia5).unwrap()).unwrap());
+
+        assert!(from_slice::<NumericString>(&[0x12, 1, b'a']).is_err());
+    }
+
+    #[test]
+    fn teletex_string() {
+        // "Zürich 5°" with a floating diaeresis before the "u".
+        let raw = b"\x14\x0aZ\xc8urich 5\xb0";
+        let teletex: TeletexString = from_slice(raw).unwrap();
+
+        assert_eq!(&raw[2..], teletex.as_ref());
+        assert_eq!("Zürich 5°", teletex.to_utf8_lossy());
+        assert_eq!(&raw[..], &*to_vec(&teletex).unwrap());
+        assert_eq!(teletex, TeletexString::from_utf8("Zürich 5°"));
+    }
+
+    #[test]
+    fn teletex_transcoding() {
+        let accented = TeletexString::from_utf8("é ç Å ß");
+        assert_eq!(&[0xc2, b'e', b' ', 0xcb, b'c', b' ', 0xca, b'A', b' ', 0xfb][..], accented.as_ref());
+        assert_eq!("é ç Å ß", accented.to_utf8_lossy());
+
+        // Combining marks are moved in front of their letter, and letters
+        // without a precomposed form keep the combining mark.
+        assert_eq!(&[0xc2, b'e'][..], TeletexString::from_utf8("e\u{301}").as_ref());
+        assert_eq!("q\u{301}", TeletexString::new(vec![0xc2, b'q']).to_utf8_lossy());
+
+        assert_eq!("´\u{FFFD}", TeletexString::new(vec![0xc2, b' ', 0xc0]).to_utf8_lossy());
+        assert_eq!(&b"??"[..], TeletexString::from_utf8("Ж€").as_ref());
+    }
+
+    #[test]
+    fn times() {
+        let utc = b"\x17\x0d191215190210Z";
+        let generalized = b"\x18\x1220191215190210.25Z";
+
+        assert_eq!(UtcTime::new("191215190210Z").unwrap(), from_slice(utc).unwrap());
+        assert_eq!(
+            GeneralizedTime::new("20191215190210.25Z").unwrap(),
+            from_slice(generalized).unwrap()
+        );
+
+        match from_slice::<UtcTime>(generalized) {
+            Err(error::Error::IncorrectType { expected, actual }) => {
+                assert_eq!(core::identifier::Identifier::UTC_TIME, expected);
+                assert_eq!(core::identifier::Identifier::GENERALIZED_TIME, actual);
+            }
+            result => panic!("Expected IncorrectType, found {:?}", result),
+        }
+        assert!(from_slice::<GeneralizedTime>(utc).is_err());
+    }
+
+    #[test]
+    fn map() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(2u32, OctetString::from(vec![2u8, 2]));
+        map.insert(1u32, OctetString::from(vec![1u8]));
+
+        let encoded = to_vec(&map).unwrap();
+
+        // Each entry is a `SEQUENCE { key, value }`, ordered by key.
+        assert_eq!(&[0x30, 17, 0x30, 6, 0x2, 1, 1, 0x4, 1, 1][..], &encoded[..10]);
+        assert_eq!(map, from_slice(&encoded).unwrap());
+    }
+
+    #[test]
+    fn indefinite_length_under_ber() {
+        let ber = EncoderConfig::new().rules(EncodingRules::Ber);
+        let sequence = (true, false);
+
+        assert_eq!(
+            &[0x30, 0x80, 0x1, 0x1, 0xff, 0x1, 0x1, 0x0, 0, 0][..],
+            &*to_vec_with(&sequence, ber).unwrap()
+        );
+        assert_eq!(
+            &[0x30, 0x6, 0x1, 0x1, 0xff, 0x1, 0x1, 0x0][..],
+            &*to_vec(&sequence).unwrap()
+        );
+    }
+
+    #[test]
+    fn non_canonical_encodings() {
+        let long_lengths = EncoderConfig::new().min_length_octets(2);
+        let encoded = to_vec_with(&5u8, long_lengths).unwrap();
+
+        assert_eq!(&[0x2, 0x82, 0x0, 0x1, 0x5][..], &*encoded);
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        assert_eq!(5u8, from_slice_with(&encoded, ber).unwrap());
+
+        // Lengths needing more octets than asked for aren't shortened.
+        let contents = OctetString::from(vec![0; 0x1234]);
+        let encoded = to_vec_with(&contents, EncoderConfig::new().min_length_octets(1)).unwrap();
+        assert_eq!(&[0x4, 0x82, 0x12, 0x34][..], &encoded[..4]);
+
+        let segmented = EncoderConfig::new()
+            .rules(EncodingRules::Ber)
+            .bit_string_segment_size(Some(2));
+        let bits = BitString::from_bytes(&[0xA, 0xB, 0xC]);
+
+        assert_eq!(
+            &[
+                0x23, 0x80,
+                0x3, 0x3, 0x0, 0xA, 0xB,
+                0x3, 0x2, 0x2, 0xC,
+                0, 0,
+            ][..],
+            &*to_vec_with(&bits, segmented).unwrap()
+        );
+        assert_eq!(to_vec(&bits).unwrap(), to_vec_with(&bits, EncoderConfig::default()).unwrap());
+    }
+
+    #[test]
+    fn indefinite_length_rejected_under_der() {
+        let encoded = [0x30, 0x80, 0x1, 0x1, 0xff, 0x1, 0x1, 0x0, 0, 0];
+
+        match from_slice::<(bool, bool)>(&encoded) {
+            Err(error::Error::IndefiniteLengthInDer) => {}
+            result => panic!("Expected IndefiniteLengthInDer, found {:?}", result),
+        }
+    }
+
+    #[test]
+    fn bit_string_unused_bits() {
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+
+        for encoded in &[&[0x3, 2, 0x8, 0x80][..], &[0x3, 1, 0x1], &[0x3, 0]] {
+            assert!(from_slice::<BitString>(encoded).is_err());
+            assert!(from_slice_with::<BitString>(encoded, ber).is_err());
+        }
+
+        match from_slice::<BitString>(&[0x3, 2, 0x8, 0x80]) {
+            Err(error::Error::InvalidUnusedBits(8)) => {}
+            result => panic!("Expected InvalidUnusedBits, found {:?}", result),
+        }
+
+        // The last bit is marked as unused, but is set.
+        let padded = [0x3, 2, 0x1, 0x81];
+        match from_slice::<BitString>(&padded) {
+            Err(error::Error::NonZeroUnusedBits) => {}
+            result => panic!("Expected NonZeroUnusedBits, found {:?}", result),
+        }
+        assert!(from_slice_with::<BitString>(&padded, ber).is_ok());
+
+        for bits in &[BitString::new(), BitString::from_bytes(&[0x80, 0])] {
+            assert_eq!(*bits, from_slice(&to_vec(bits).unwrap()).unwrap());
+        }
+    }
+
+    #[test]
+    fn collections() {
+        use std::collections::{BTreeSet, HashSet, VecDeque};
+
+        let encoded = [0x31, 9, 0x2, 1, 1, 0x2, 1, 2, 0x2, 1, 3];
+
+        let btree_set = SetOf::new([3u8, 1, 2].iter().cloned().collect::<BTreeSet<_>>());
+        assert_eq!(&encoded[..], &*to_vec(&btree_set).unwrap());
+        assert_eq!(btree_set, from_slice(&encoded).unwrap());
+
+        let hash_set = SetOf::new([3u8, 1, 2].iter().cloned().collect::<HashSet<_>>());
+        assert_eq!(&encoded[..], &*to_vec(&hash_set).unwrap());
+        assert_eq!(hash_set, from_slice(&encoded).unwrap());
+
+        // The elements are sorted by their encodings, not their values.
+        let vec = SetOf::new(vec![256u16, 3]);
+        let sorted = [0x31, 7, 0x2, 1, 3, 0x2, 2, 1, 0];
+        assert_eq!(&sorted[..], &*to_vec(&vec).unwrap());
+        assert_eq!(SetOf::new(vec![3u16, 256]), from_slice(&sorted).unwrap());
+
+        let deque = [3u8, 1, 2].iter().cloned().collect::<VecDeque<_>>();
+        let sequence = [0x30, 9, 0x2, 1, 3, 0x2, 1, 1, 0x2, 1, 2];
+        assert_eq!(&sequence[..], &*to_vec(&deque).unwrap());
+        assert_eq!(deque, from_slice::<VecDeque<u8>>(&sequence).unwrap());
+    }
+
+    #[test]
+    fn unsorted_set_of() {
+        use std::collections::BTreeSet;
+
+        let encoded = [0x31, 6, 0x2, 1, 2, 0x2, 1, 1];
+
+        match from_slice::<SetOf<BTreeSet<u8>>>(&encoded) {
+            Err(error::Error::UnsortedSetOf) => {}
+            result => panic!("Expected UnsortedSetOf, found {:?}", result),
+        }
+
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        let set: SetOf<BTreeSet<u8>> = from_slice_with(&encoded, ber).unwrap();
+        assert_eq!(vec![1, 2], set.iter().cloned().collect::<Vec<_>>());
+
+        // Only values of a SET OF type are matched by a SET OF.
+        assert!(from_slice::<BTreeSet<u8>>(&[0x31, 3, 0x2, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn asn_string() {
+        let cases = [
+            ("12345", 0x12),
+            ("Hello", 0x13),
+            ("café", 0xC),
+        ];
+
+        for (string, tag) in &cases {
+            let string = AsnString::new(*string);
+            let encoded = to_vec(&string).unwrap();
+
+            assert_eq!(*tag, encoded[0]);
+            assert_eq!(string.as_bytes(), &encoded[2..]);
+            assert_eq!(string, from_slice(&encoded).unwrap());
+        }
+
+        // Content not permitted by the type it's tagged as is rejected.
+        assert!(from_slice::<AsnString>(&[0x12, 2, b'a', b'b']).is_err());
+    }
+
+    #[test]
+    fn system_time() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        struct Signed {
+            #[serde(with = "core::types::time::system_time")]
+            at: SystemTime,
+        }
+
+        let signed = Signed {
+            at: UNIX_EPOCH + Duration::from_secs(1_234_567_890),
+        };
+        let encoded = to_vec(&signed).unwrap();
+
+        assert_eq!(&b"20090213233130Z"[..], &encoded[4..]);
+        assert_eq!(signed, from_slice(&encoded).unwrap());
+
+        let mut before_epoch = encoded;
+        before_epoch[4..].copy_from_slice(b"19691231235959Z");
+        assert!(from_slice::<Signed>(&before_epoch).is_err());
+    }
+
+    #[test]
+    fn ip_addresses() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        struct Peer {
+            #[serde(with = "core::types::net::ipv4")]
+            v4: Ipv4Addr,
+            #[serde(with = "core::types::net::ipv6")]
+            v6: Ipv6Addr,
+            #[serde(with = "core::types::net::ip")]
+            either: IpAddr,
+        }
+
+        let peer = Peer {
+            v4: Ipv4Addr::new(192, 0, 2, 1),
+            v6: "2001:db8::1".parse().unwrap(),
+            either: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        };
+        let encoded = to_vec(&peer).unwrap();
+
+        assert_eq!(&[0x4, 4, 192, 0, 2, 1][..], &encoded[2..8]);
+        assert_eq!(&[0x4, 16, 0x20, 0x01, 0x0d, 0xb8][..], &encoded[8..14]);
+        assert_eq!(peer, from_slice(&encoded).unwrap());
+
+        let peer = Peer { either: IpAddr::V6(Ipv6Addr::LOCALHOST), ..peer };
+        assert_eq!(peer, from_slice(&to_vec(&peer).unwrap()).unwrap());
+
+        // Five octets are neither an IPv4 nor an IPv6 address.
+        let mut mismatched = encoded[..2].to_vec();
+        mismatched.extend_from_slice(&[0x4, 5, 192, 0, 2, 1, 0]);
+        mismatched.extend_from_slice(&encoded[8..]);
+        mismatched[1] += 1;
+        assert!(from_slice::<Peer>(&mismatched).is_err());
+    }
+
+    #[test]
+    fn expected_top_level_tag() {
+        use core::identifier::{Class, Identifier};
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        #[serde(rename = "ASN.1#[0]")]
+        struct BindRequest {
+            version: u8,
+        }
+
+        let request = BindRequest { version: 3 };
+        let encoded = to_vec(&request).unwrap();
+
+        let bind_request = Identifier::new(Class::Context, 0);
+        let bind_response = Identifier::new(Class::Context, 1);
+
+        assert_eq!(request, from_slice_expecting(&encoded, bind_request).unwrap());
+
+        match from_slice_expecting::<BindRequest>(&encoded, bind_response) {
+            Err(error::Error::IncorrectType { expected, actual }) => {
+                assert_eq!(bind_response, expected);
+                assert_eq!(bind_request, actual);
+            }
+            result => panic!("Expected IncorrectType, found {:?}", result),
+        }
+
+        assert!(from_slice_expecting::<BindRequest>(&[], bind_request).is_err());
+    }
+
+    #[test]
+    fn nested_indefinite_length_under_ber() {
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        let sequence = ((0u8, false), true);
+        let encoded = to_vec_with(&sequence, EncoderConfig::new().rules(EncodingRules::Ber)).unwrap();
+
+        assert_eq!(
+            &[0x30, 0x80, 0x30, 0x80, 0x2, 0x1, 0x0, 0x1, 0x1, 0x0, 0, 0, 0x1, 0x1, 0xff, 0, 0][..],
+            &*encoded
+        );
+        assert_eq!(sequence, from_slice_with(&encoded, ber).unwrap());
+    }
+
+    #[test]
+    fn indefinite_length_in_definite_sequence_under_ber() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Inner {
+            a: u8,
+            b: bool,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Outer {
+            inner: Inner,
+            c: bool,
+        }
+
+        let ber = DecoderConfig::new().rules(EncodingRules::Ber);
+        let expected = Outer { inner: Inner { a: 7, b: true }, c: false };
+        let encoded = [0x30, 13, 0x30, 0x80, 0x2, 0x1, 0x7, 0x1, 0x1, 0xff, 0, 0, 0x1, 0x1, 0x0];
+
+        assert_eq!(expected, from_slice_with(&encoded, ber).unwrap());
+
+        match from_slice::<Outer>(&encoded) {
+            Err(error::Error::Field { path, error }) => {
+                assert_eq!("Outer.inner", path);
+                assert!(matches!(*error, error::Error::IndefiniteLengthInDer));
+            }
+            result => panic!("Expected IndefiniteLengthInDer, found {:?}", result),
+        }
+    }
+
+    #[test]
+    fn dynamic_messages() {
+        use core::identifier::{Class, Identifier};
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        #[serde(rename = "ASN.1#[APPLICATION 0]")]
+        struct Ping {
+            id: u8,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        #[serde(rename = "ASN.1#[APPLICATION 1]")]
+        struct Pong {
+            id: u8,
+            ok: bool,
+        }
+
+        #[derive(Debug, PartialEq)]
+        enum Message {
+            Ping(Ping),
+            Pong(Pong),
+        }
+
+        let messages: Vec<Box<dyn DynEncode>> = vec![Box::new(Ping { id: 1 }), Box::new(Pong { id: 1, ok: true })];
+        let encoded: Vec<_> = messages.iter().map(|message| message.encode_der().unwrap()).collect();
+
+        assert_eq!(&[0x60, 3, 0x2, 1, 1][..], &*encoded[0]);
+        assert_eq!(&[0x61, 6, 0x2, 1, 1, 0x1, 1, 0xff][..], &*encoded[1]);
+
+        let mut registry = DecoderRegistry::new();
+        registry
+            .register(Identifier::new(Class::Application, 0), Message::Ping)
+            .register(Identifier::new(Class::Application, 1), Message::Pong);
+
+        assert_eq!(Message::Ping(Ping { id: 1 }), registry.decode(&encoded[0]).unwrap());
+        assert_eq!(Message::Pong(Pong { id: 1, ok: true }), registry.decode(&encoded[1]).unwrap());
+        assert!(matches!(registry.decode(&[0x62, 0]), Err(error::Error::NoVariantFound(2))));
+    }
+
     #[test]
     fn implicit_prefix() {
         type MyInteger = core::types::Implicit<Context, U0, u64>;
@@ -269,6 +861,245 @@ mod tests {
         assert_eq!(new_int, from_slice(&to_vec(&new_int).unwrap()).unwrap());
     }
 
+    #[test]
+    fn double_explicit_prefix() {
+        use core::types::Explicit;
+
+        // [0] EXPLICIT [1] EXPLICIT INTEGER
+        type Doubled = Explicit<Context, U0, Explicit<Context, U1, u32>>;
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        struct Message {
+            doubled: Doubled,
+            trailing: bool,
+        }
+
+        let doubled = Doubled::new(Explicit::new(5));
+        let encoded = to_vec(&doubled).unwrap();
+
+        assert_eq!(vec![0xA0, 0x5, 0xA1, 0x3, 0x2, 0x1, 0x5], encoded);
+        assert_eq!(doubled, from_slice(&encoded).unwrap());
+
+        let message = Message { doubled, trailing: true };
+        let encoded = to_vec(&message).unwrap();
+
+        assert_eq!(&[0xA0, 0x5, 0xA1, 0x3, 0x2, 0x1, 0x5][..], &encoded[2..9]);
+        assert_eq!(message, from_slice(&encoded).unwrap());
+    }
+
+    #[test]
+    fn bit_string_helpers() {
+        let bits = BitString::from_bytes(&[0b1010_0001]);
+
+        assert_eq!(
+            vec![true, false, true, false, false, false, false, true],
+            bits.iter_bits().collect::<Vec<_>>()
+        );
+        assert_eq!(3, bits.count_ones());
+        assert_eq!(0, BitString::new().count_ones());
+    }
+
+    #[test]
+    fn octet_string_helpers() {
+        let string = OctetString::from(vec![1, 2, 3, 4]);
+
+        assert_eq!(OctetString::from(vec![2, 3]), string.slice(1..3));
+        assert_eq!(string, string.slice(..));
+        assert!(string.slice(2..2).is_empty());
+
+        let (head, tail) = (string.slice(..1), string.slice(1..));
+        assert_eq!(string, OctetString::concat(&[head, OctetString::new(), tail]));
+        assert!(OctetString::concat(&[]).is_empty());
+    }
+
+    #[test]
+    fn sequence_field_count() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Pair {
+            a: u8,
+            b: u8,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        #[serde(rename = "Pair ...")]
+        struct ExtensiblePair {
+            a: u8,
+            b: u8,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct OptionalPair {
+            a: u8,
+            b: Option<u8>,
+        }
+
+        let truncated = [0x30, 3, 2, 1, 1];
+        let extended = [0x30, 9, 2, 1, 1, 2, 1, 2, 2, 1, 3];
+
+        assert!(matches!(
+            from_slice::<Pair>(&truncated),
+            Err(crate::error::Error::MissingField { index: 1 })
+        ));
+        assert_eq!(OptionalPair { a: 1, b: None }, from_slice(&truncated).unwrap());
+
+        assert!(matches!(
+            from_slice::<Pair>(&extended),
+            Err(crate::error::Error::UnexpectedField)
+        ));
+        assert_eq!(ExtensiblePair { a: 1, b: 2 }, from_slice(&extended).unwrap());
+    }
+
+    #[test]
+    fn field_path_in_errors() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        #[serde(rename_all = "camelCase")]
+        struct TbsCertificate {
+            serial_number: u8,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        #[serde(rename_all = "camelCase")]
+        struct Certificate {
+            tbs_certificate: TbsCertificate,
+        }
+
+        let certificate = Certificate { tbs_certificate: TbsCertificate { serial_number: 5 } };
+        assert_eq!(certificate, from_slice(&[0x30, 5, 0x30, 3, 2, 1, 5]).unwrap());
+
+        // The serial number is a BOOLEAN rather than an INTEGER.
+        let encoded = [0x30, 5, 0x30, 3, 1, 1, 0xff];
+        let error = from_slice::<Certificate>(&encoded).unwrap_err();
+
+        match &error {
+            crate::error::Error::Field { path, error } => {
+                assert_eq!("Certificate.tbsCertificate.serialNumber", path);
+                assert!(matches!(**error, crate::error::Error::IncorrectType { .. }));
+            }
+            error => panic!("Expected Field, found {:?}", error),
+        }
+
+        assert!(error.to_string().starts_with("Certificate.tbsCertificate.serialNumber: "));
+    }
+
+    #[test]
+    fn canonical_der() {
+        let canonical = [0x30, 0x03, 0x02, 0x01, 0x05];
+        assert!(is_canonical_der(&canonical).unwrap());
+        assert_eq!(&canonical[..], &*canonicalize(&canonical).unwrap());
+
+        let long_length = [0x30, 0x81, 0x03, 0x02, 0x01, 0x05];
+        assert!(!is_canonical_der(&long_length).unwrap());
+        assert_eq!(&canonical[..], &*canonicalize(&long_length).unwrap());
+
+        let padded_integer = [0x30, 0x05, 0x02, 0x03, 0x00, 0x00, 0x05];
+        assert_eq!(&canonical[..], &*canonicalize(&padded_integer).unwrap());
+
+        let indefinite = [0x30, 0x80, 0x02, 0x01, 0x05, 0x00, 0x00];
+        assert_eq!(&canonical[..], &*canonicalize(&indefinite).unwrap());
+
+        let unsorted_set = [0x31, 0x06, 0x02, 0x01, 0x07, 0x01, 0x01, 0x01];
+        assert_eq!(
+            &[0x31, 0x06, 0x01, 0x01, 0xff, 0x02, 0x01, 0x07][..],
+            &*canonicalize(&unsorted_set).unwrap()
+        );
+
+        assert!(canonicalize(&[0x02, 0x01, 0x05, 0x00]).is_err());
+        assert!(is_canonical_der(&[0x30, 0x03, 0x02]).is_err());
+    }
+
+    #[test]
+    fn with_raw_bytes() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Validity {
+            not_after: u8,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Tbs {
+            serial: u8,
+            validity: Validity,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Certificate {
+            tbs: WithRawBytes<Tbs>,
+            signature: bool,
+        }
+
+        // The TBS uses a longer length form than DER allows, which
+        // re-encoding it would change.
+        let tbs = [0x30, 0x81, 0x08, 0x02, 0x01, 0x07, 0x30, 0x03, 0x02, 0x01, 0x09];
+        let encoded = [&[0x30, 0x0e][..], &tbs, &[0x01, 0x01, 0xff]].concat();
+
+        let certificate: Certificate = from_slice(&encoded).unwrap();
+        let expected = Tbs { serial: 7, validity: Validity { not_after: 9 } };
+
+        assert_eq!(&expected, certificate.tbs.value());
+        assert_eq!(&tbs[..], certificate.tbs.raw());
+        assert!(certificate.signature);
+        assert_eq!(encoded, to_vec(&certificate).unwrap());
+    }
+
+    #[test]
+    fn runtime_tagged() {
+        use core::{identifier::{Class, Identifier}, types::Tagged};
+
+        let tag = Identifier::new(Class::Context, 7);
+        let implicit = Tagged::implicit(tag, 5u32);
+        let explicit = Tagged::explicit(tag, 5u32);
+
+        assert_eq!(&[0x87, 1, 5][..], &*to_vec(&implicit).unwrap());
+        assert_eq!(&[0xa7, 3, 0x2, 1, 5][..], &*to_vec(&explicit).unwrap());
+
+        // Runtime tags decode the same as the compile time equivalents.
+        let value: Explicit<Context, U7, u32> = from_slice(&to_vec(&explicit).unwrap()).unwrap();
+        assert_eq!(5, value.into_inner());
+    }
+
+    #[test]
+    fn nested_choice() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Outer {
+            #[serde(rename = "ASN.1#Untagged")]
+            A(Inner),
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Inner {
+            X(Implicit<Context, U0, bool>),
+            Y(Implicit<Context, U1, u8>),
+        }
+
+        let y = Outer::A(Inner::Y(Implicit::new(5)));
+
+        assert_eq!(y, from_slice(&[0x81, 1, 5]).unwrap());
+        assert_eq!(&[0x81, 1, 5][..], &*to_vec(&y).unwrap());
+
+        let x = Outer::A(Inner::X(Implicit::new(true)));
+        assert_eq!(x, from_slice(&to_vec(&x).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn any_preserves_encoding() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Forwarded {
+            payload: Any,
+            id: u8,
+        }
+
+        // [5] EXPLICIT INTEGER 300
+        let tagged = [0xa5, 4, 0x2, 0x2, 0x1, 0x2c];
+        let raw = [0x30, 9, 0xa5, 4, 0x2, 0x2, 0x1, 0x2c, 0x2, 0x1, 7];
+
+        let forwarded: Forwarded = from_slice(&raw).unwrap();
+        assert_eq!(&tagged[..], forwarded.payload.as_ref());
+        assert_eq!(7, forwarded.id);
+        assert_eq!(&raw[..], &*to_vec(&forwarded).unwrap());
+
+        let value: Explicit<Context, U5, u16> = from_slice(forwarded.payload.as_ref()).unwrap();
+        assert_eq!(300, value.into_inner());
+    }
+
     #[test]
     fn nested_enum() {
         #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -278,13 +1109,21 @@ mod tests {
         }
 
 
-        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        #[derive(Clone, Debug, PartialEq)]
         enum Bravo {
             A,
             B,
         }
 
-        impl Enumerable for Bravo {}
+        impl Enumerable for Bravo {
+            fn to_enumerated(&self) -> i64 {
+                self.clone() as i64
+            }
+
+            fn from_enumerated(value: i64) -> Option<Self> {
+                [Bravo::A, Bravo::B].get(value as usize).cloned()
+            }
+        }
 
         type Charlie = Enumerated<Bravo>;
 
@@ -292,4 +1131,120 @@ mod tests {
 
         assert_eq!(input, from_slice(&to_vec(&input).unwrap()).unwrap())
     }
+
+    #[test]
+    fn choice_and_enumerated_discriminants() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Choice {
+            A(Implicit<Context, U0, bool>),
+            B(Implicit<Context, U1, u8>),
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        enum Status {
+            A,
+            B,
+        }
+
+        impl Enumerable for Status {
+            fn to_enumerated(&self) -> i64 {
+                *self as i64
+            }
+
+            fn from_enumerated(value: i64) -> Option<Self> {
+                [Status::A, Status::B].get(value as usize).cloned()
+            }
+        }
+
+        // The same discriminant, as a tag number and as ENUMERATED contents.
+        let choice = [0x81, 1, 5];
+        let enumerated = [0xA, 1, 1];
+
+        let b = Choice::B(Implicit::new(5));
+        assert_eq!(b, from_slice(&choice).unwrap());
+        assert_eq!(Some(b), from_slice(&choice).unwrap());
+        assert!(from_slice::<Choice>(&enumerated).is_err());
+        // Its tag is neither variant's, so an OPTIONAL CHOICE is absent.
+        assert_eq!(None, from_slice::<Option<Choice>>(&enumerated).unwrap());
+
+        let status = Enumerated::new(Status::B);
+        assert_eq!(status, from_slice(&enumerated).unwrap());
+        assert_eq!(Some(status), from_slice(&enumerated).unwrap());
+        assert!(from_slice::<Enumerated<Status>>(&choice).is_err());
+        assert_eq!(None, from_slice::<Option<Enumerated<Status>>>(&choice).unwrap());
+    }
+
+    #[test]
+    fn absent_optional_choice() {
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        enum C {
+            A(Implicit<Context, U0, bool>),
+            B(Implicit<Context, U1, u8>),
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        #[serde(rename = "S")]
+        struct S {
+            a: u8,
+            c: Option<C>,
+            d: Implicit<Context, U2, u8>,
+        }
+
+        let present = S { a: 1, c: Some(C::B(Implicit::new(5))), d: Implicit::new(7) };
+        let absent = S { a: 1, c: None, d: Implicit::new(7) };
+
+        assert_eq!(present, from_slice(&to_vec(&present).unwrap()).unwrap());
+        assert_eq!(&[0x30, 6, 0x02, 1, 1, 0x82, 1, 7][..], &*to_vec(&absent).unwrap());
+        assert_eq!(absent, from_slice(&to_vec(&absent).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn builder_matches_serde() {
+        use core::identifier::{Class, Identifier};
+
+        #[derive(Serialize)]
+        struct Foo {
+            a: i64,
+            b: OctetString,
+        }
+
+        let foo = Foo { a: 1, b: OctetString::from(vec![1, 2]) };
+
+        let mut builder = DerBuilder::new();
+        builder.sequence(|sequence| {
+            sequence.integer(1).octet_string(&[1, 2]);
+        });
+
+        assert_eq!(to_vec(&foo).unwrap(), builder.finish());
+
+        let oid = ObjectIdentifier::new(vec![1, 2, 840, 113549]).unwrap();
+        let mut builder = DerBuilder::new();
+        builder
+            .bool(true)
+            .oid(&oid)
+            .tagged(Identifier::new(Class::Context, 0), |tagged| {
+                tagged.integer(-129);
+            });
+
+        let mut expected = vec![0x1, 1, 0xff];
+        expected.extend(to_vec(&oid).unwrap());
+        expected.extend(&[0xA0, 4, 0x2, 2, 0xff, 0x7f]);
+        assert_eq!(expected, builder.finish());
+    }
+
+    #[test]
+    fn builder_shortened_oid() {
+        let mut oid = ObjectIdentifier::new(vec![1, 2, 840]).unwrap();
+        oid.truncate(1);
+
+        let mut builder = DerBuilder::new();
+        builder.oid(&oid);
+        assert_eq!(vec![0x6, 1, 40], builder.finish());
+
+        oid.clear();
+
+        let mut builder = DerBuilder::new();
+        builder.oid(&oid);
+        assert_eq!(vec![0x6, 0], builder.finish());
+    }
 }
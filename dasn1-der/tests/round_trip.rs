@@ -0,0 +1,128 @@
+//! Property tests checking that everything the encoder writes, the decoder
+//! reads back as the same value.
+//!
+//! `dasn1-core` is renamed to `core` here, which shadows the `core` crate the
+//! `proptest!` and `prop_assert!` macros expand to, so the tests drive a
+//! `TestRunner` themselves.
+
+use std::fmt::Debug;
+
+use core::types::{BitString, Integer, ObjectIdentifier, OctetString};
+use dasn1_der::{from_slice, to_vec};
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    collection::vec,
+    strategy::Strategy,
+    test_runner::{TestCaseError, TestRunner},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_derive::Deserialize;
+
+/// A SEQUENCE nesting SEQUENCE OFs of itself.
+#[derive(Clone, Debug, Deserialize, PartialEq, serde_derive::Serialize)]
+struct Node {
+    value: Integer,
+    label: OctetString,
+    children: Vec<Node>,
+}
+
+/// Trees of up to four levels. Shrinking drops children and levels before it
+/// touches the values, so failures reduce to the smallest nesting first.
+fn node() -> impl Strategy<Value = Node> {
+    let leaf = (any::<Integer>(), any::<OctetString>()).prop_map(|(value, label)| Node {
+        value,
+        label,
+        children: Vec::new(),
+    });
+
+    leaf.prop_recursive(4, 32, 4, |inner| {
+        (any::<Integer>(), any::<OctetString>(), vec(inner, 0..4)).prop_map(
+            |(value, label, children)| Node {
+                value,
+                label,
+                children,
+            },
+        )
+    })
+}
+
+/// Runs `test` against values from `strategy`, panicking with the smallest
+/// value it fails for.
+fn check<S: Strategy>(strategy: S, test: impl Fn(S::Value) -> Result<(), TestCaseError>) {
+    if let Err(error) = TestRunner::default().run(&strategy, test) {
+        panic!("{}", error);
+    }
+}
+
+fn round_trip<T>(value: T) -> Result<(), TestCaseError>
+where
+    T: Debug + DeserializeOwned + PartialEq + Serialize,
+{
+    let encoded = to_vec(&value).map_err(|error| TestCaseError::fail(error.to_string()))?;
+    let decoded: T = from_slice(&encoded).map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+    if value == decoded {
+        Ok(())
+    } else {
+        Err(TestCaseError::fail(format!("{:?} decoded as {:?}", value, decoded)))
+    }
+}
+
+fn check_round_trip<T>()
+where
+    T: Arbitrary + DeserializeOwned + PartialEq + Serialize,
+{
+    check(any::<T>(), round_trip);
+}
+
+#[test]
+fn primitives() {
+    check_round_trip::<bool>();
+    check_round_trip::<i8>();
+    check_round_trip::<i64>();
+    check_round_trip::<i128>();
+    check_round_trip::<u8>();
+    check_round_trip::<u64>();
+    check_round_trip::<u128>();
+}
+
+#[test]
+fn integers() {
+    check_round_trip::<Integer>();
+}
+
+#[test]
+fn octet_strings() {
+    check_round_trip::<OctetString>();
+}
+
+#[test]
+fn object_identifiers() {
+    check_round_trip::<ObjectIdentifier>();
+}
+
+/// The serde representation of a `BitString` is its octets, so the bits come
+/// back padded with zeros to a whole octet.
+#[test]
+fn bit_strings() {
+    check(any::<BitString>(), |value| {
+        let encoded = to_vec(&value).map_err(|error| TestCaseError::fail(error.to_string()))?;
+        let decoded: BitString =
+            from_slice(&encoded).map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+        let padded = decoded.len() == (value.len() + 7) / 8 * 8;
+        let same_bits = value.iter_bits().eq(decoded.iter_bits().take(value.len()));
+        let zero_padding = decoded.iter_bits().skip(value.len()).all(|bit| !bit);
+
+        if padded && same_bits && zero_padding {
+            Ok(())
+        } else {
+            Err(TestCaseError::fail(format!("{} decoded as {}", value, decoded)))
+        }
+    });
+}
+
+#[test]
+fn nested_sequences() {
+    check(node(), round_trip);
+}
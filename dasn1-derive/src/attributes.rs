@@ -1,4 +1,6 @@
-use syn::{Attribute, Lit, Meta, MetaList, NestedMeta};
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use syn::{Attribute, Data, DeriveInput, Ident, Lit, LitInt, LitStr, Meta, MetaList, NestedMeta};
 
 /// Generic attributes common to all container types.
 #[derive(Default)]
@@ -28,12 +30,30 @@ impl ContainerAttributes {
 #[derive(Default)]
 pub struct EnumAttributes {
     pub container: ContainerAttributes,
+    /// If true the `ENUMERATED` type has an extension marker, from
+    /// `#[asn(extensible)]`.
+    pub extensible: bool,
+    /// If true the enum is a `SEQUENCE { type ENUMERATED, value CHOICE }`
+    /// of the variant's index and its value, rather than a CHOICE, from
+    /// `#[asn(tagged_sequence)]`.
+    pub tagged_sequence: bool,
 }
 
 impl EnumAttributes {
     pub fn from_syn(attrs: &[Attribute]) -> Self {
+        let has_flag = |flag: &str| {
+            find_asn_attribute(attrs).is_some_and(|list| {
+                list.nested.iter().any(|nm| match nm {
+                    NestedMeta::Meta(meta) => meta.path().is_ident(flag),
+                    _ => false,
+                })
+            })
+        };
+
         Self {
             container: ContainerAttributes::from_syn(attrs),
+            extensible: has_flag("extensible"),
+            tagged_sequence: has_flag("tagged_sequence"),
         }
     }
 }
@@ -42,47 +62,133 @@ impl EnumAttributes {
 #[derive(Default)]
 pub struct StructAttributes {
     pub container: ContainerAttributes,
+    /// The class and number replacing the SEQUENCE tag, from
+    /// `#[asn(tag(application, 1))]`.
+    pub tag: Option<(Ident, LitInt)>,
+    /// If true each field is implicitly tagged with a context-specific tag
+    /// numbered by its position, from `#[asn(auto_context)]`.
+    pub auto_context: bool,
 }
 
 impl StructAttributes {
     pub fn from_syn(attrs: &[Attribute]) -> Self {
-        Self {
+        let mut attributes = Self {
             container: ContainerAttributes::from_syn(attrs),
+            ..Self::default()
+        };
+
+        if let Some(list) = find_asn_attribute(attrs) {
+            for item in list.nested.into_iter().filter_map(|nm| match nm { NestedMeta::Meta(meta) => Some(meta), _ => None }) {
+                match item {
+                    Meta::List(list) if list.path.is_ident("tag") => {
+                        attributes.tag = parse_tag(&list).ok();
+                    }
+                    ref item if item.path().is_ident("auto_context") => {
+                        attributes.auto_context = true;
+                    }
+                    _ => {}
+                }
+            }
         }
+
+        attributes
     }
 }
 
+/// Enum variant specific attributes.
 #[derive(Default)]
-pub struct FieldAttributes {
-    pub size: Option<Size>
+pub struct VariantAttributes {
+    /// If true the variant has no tag of its own, and uses the tag of its
+    /// value (e.g. a nested CHOICE).
+    pub untagged: bool,
+    /// If true the variant holds any value whose tag doesn't match one of
+    /// the other variants.
+    pub unknown: bool,
+    /// The class and number of the variant's tag, from
+    /// `#[asn(tag(application, 1))]`, rather than the context-specific tag
+    /// of its index.
+    pub tag: Option<(Ident, LitInt)>,
+    /// If true the variant is an addition after the extension marker of an
+    /// `ENUMERATED` type, from `#[asn(extension)]`.
+    pub extension: bool,
 }
 
-impl FieldAttributes {
+impl VariantAttributes {
     pub fn from_syn(syn_attrs: &[Attribute]) -> Self {
         let mut attributes = Self::default();
-        let attribute_list = syn_attrs.iter()
-            .filter_map(|a| a.parse_meta().ok())
-            .filter(|m| m.path().is_ident("asn"))
-            .next()
-            .and_then(|m| {
-                match m {
-                    Meta::List(list) => {
-                        Some(list.nested.into_iter().filter_map(|nm| match nm {
-                            NestedMeta::Meta(m) => match m {
-                                Meta::List(list) => Some(list),
-                                _ => None,
-                            }
-                            _ => None,
-                        }))
+
+        if let Some(list) = find_asn_attribute(syn_attrs) {
+            for item in list.nested.into_iter().filter_map(|nm| match nm { NestedMeta::Meta(meta) => Some(meta), _ => None }) {
+                match item {
+                    Meta::List(list) if list.path.is_ident("tag") => {
+                        attributes.tag = parse_tag(&list).ok();
+                    }
+                    ref item if item.path().is_ident("untagged") => {
+                        attributes.untagged = true;
+                    }
+                    ref item if item.path().is_ident("unknown") => {
+                        attributes.unknown = true;
+                    }
+                    ref item if item.path().is_ident("extension") => {
+                        attributes.extension = true;
                     }
-                    _ => None
+                    _ => {}
                 }
-            });
+            }
+        }
 
-        if let Some(list) = attribute_list {
-            for item in list {
-                if item.path.is_ident("size") {
-                    attributes.size = Some(Size::from_syn(item));
+        attributes
+    }
+}
+
+#[derive(Default)]
+pub struct FieldAttributes {
+    pub size: Option<Size>,
+    /// The values the field is constrained to, from
+    /// `#[asn(range = "0..=255")]`, checked with `parse_range`.
+    pub range: Option<Lit>,
+    /// The lengths the field is constrained to, from `#[asn(size = "1..4")]`.
+    pub size_range: Option<Lit>,
+    /// The name of the sibling field whose value (usually an OBJECT
+    /// IDENTIFIER) determines the type of this `ANY DEFINED BY` field.
+    pub any_defined_by: Option<String>,
+    /// The class and number the field is tagged with, from
+    /// `#[asn(tag(context, 3))]`.
+    pub tag: Option<(Ident, LitInt)>,
+    /// If true the field's tag is explicit rather than implicit, from
+    /// `#[asn(tag(context, 3), explicit)]`.
+    pub explicit: bool,
+}
+
+impl FieldAttributes {
+    pub fn from_syn(syn_attrs: &[Attribute]) -> Self {
+        let mut attributes = Self::default();
+
+        if let Some(list) = find_asn_attribute(syn_attrs) {
+            for item in list.nested.into_iter().filter_map(|nm| match nm { NestedMeta::Meta(meta) => Some(meta), _ => None }) {
+                match item {
+                    Meta::List(list) if list.path.is_ident("size") => {
+                        attributes.size = Some(Size::from_syn(list));
+                    }
+                    Meta::List(list) if list.path.is_ident("tag") => {
+                        attributes.tag = parse_tag(&list).ok();
+                    }
+                    Meta::Path(ref path) if path.is_ident("explicit") => {
+                        attributes.explicit = true;
+                    }
+                    Meta::NameValue(ref pair) if pair.path.is_ident("range") => {
+                        attributes.range = Some(pair.lit.clone());
+                    }
+                    Meta::NameValue(ref pair) if pair.path.is_ident("size") => {
+                        attributes.size_range = Some(pair.lit.clone());
+                    }
+                    Meta::NameValue(ref pair) if pair.path.is_ident("any_defined_by") => {
+                        match pair.lit {
+                            Lit::Str(ref field) => attributes.any_defined_by = Some(field.value()),
+                            _ => panic!("`any_defined_by` requires the name of a field as a string"),
+                        }
+                    }
+                    _ => {}
                 }
             }
         }
@@ -131,6 +237,163 @@ impl Size {
     }
 }
 
+/// Parses a `range` or `size` constraint, which must be a Rust range as a
+/// string such as `"0..=255"`, into its text and its tokens. Errors point at
+/// the attribute, rather than panicking in the middle of the derive.
+pub fn parse_range(lit: &Lit, name: &str) -> syn::Result<(String, TokenStream)> {
+    let usage = || syn::Error::new_spanned(lit, format!("`{}` requires a range as a string, e.g. `{} = \"0..=255\"`", name, name));
+
+    match lit {
+        Lit::Str(range) if range.value().contains("..") => {
+            let tokens = range.parse::<TokenStream>().map_err(|_| usage())?;
+            Ok((range.value(), tokens))
+        }
+        _ => Err(usage()),
+    }
+}
+
+/// Checks that every `#[asn(tag(..))]` on `input`, its fields, and its
+/// variants is well formed, so that the attributes can then be read without
+/// any errors to report.
+pub fn check_tags(input: &DeriveInput) -> syn::Result<()> {
+    let nested: Vec<&[Attribute]> = match input.data {
+        Data::Struct(ref data) => data.fields.iter().map(|field| &*field.attrs).collect(),
+        Data::Enum(ref data) => data.variants.iter().map(|variant| &*variant.attrs).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+
+    for attrs in std::iter::once(&*input.attrs).chain(nested) {
+        for item in find_asn_attribute(attrs).into_iter().flat_map(|list| list.nested) {
+            match item {
+                NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("tag") => {
+                    parse_tag(list)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the class and number out of `tag(application, 1)`.
+fn parse_tag(list: &MetaList) -> syn::Result<(Ident, LitInt)> {
+    const USAGE: &str = "`tag` requires a class and number, e.g. `tag(application, 1)`";
+    let mut nested = list.nested.iter();
+
+    let class = match nested.next() {
+        Some(NestedMeta::Meta(Meta::Path(ref path))) => {
+            match path.get_ident().map(ToString::to_string).as_deref() {
+                Some("universal") => "Universal",
+                Some("application") => "Application",
+                Some("context") => "Context",
+                Some("private") => "Private",
+                _ => return Err(syn::Error::new_spanned(path, USAGE)),
+            }
+        }
+        Some(item) => return Err(syn::Error::new_spanned(item, USAGE)),
+        None => return Err(syn::Error::new_spanned(list, USAGE)),
+    };
+
+    match (nested.next(), nested.next()) {
+        (Some(NestedMeta::Lit(Lit::Int(number))), None) => {
+            Ok((Ident::new(class, Span::call_site()), number.clone()))
+        }
+        (Some(item), None) | (_, Some(item)) => Err(syn::Error::new_spanned(item, USAGE)),
+        (None, None) => Err(syn::Error::new_spanned(list, USAGE)),
+    }
+}
+
+/// Checks that an item with an `#[asn]` attribute is renamed for serde to
+/// the name `dasn1::der` encodes it by, as serde, not this derive, drives
+/// the encoding. `matches` is given each name in the item's `#[serde]`
+/// attributes, and `expected` describes the name for the error.
+pub fn check_serde_name(
+    attrs: &[Attribute],
+    item: &dyn ToTokens,
+    attribute: &str,
+    expected: &str,
+    matches: impl Fn(&str) -> bool,
+) -> syn::Result<()> {
+    check_serde_attribute(attrs, "rename", item, attribute, expected, matches)
+}
+
+/// Checks that each value of `#[serde(key = "...")]` on an item satisfies
+/// `matches`, and that there is one.
+pub fn check_serde_attribute(
+    attrs: &[Attribute],
+    key: &str,
+    item: &dyn ToTokens,
+    attribute: &str,
+    expected: &str,
+    matches: impl Fn(&str) -> bool,
+) -> syn::Result<()> {
+    let values = serde_values(attrs, key);
+    let message = || {
+        format!(
+            "`#[asn({})]` on `{}` also requires `#[serde({} = \"{}\")]`, as serde drives the encoding",
+            attribute,
+            item.to_token_stream(),
+            key,
+            expected
+        )
+    };
+
+    match values.iter().find(|value| !matches(&value.value())) {
+        _ if values.is_empty() => Err(syn::Error::new_spanned(item, message())),
+        Some(value) => Err(syn::Error::new_spanned(value, message())),
+        None => Ok(()),
+    }
+}
+
+/// The values given in `#[serde(key = "...")]`, or both values given in
+/// e.g. `#[serde(rename(serialize = "...", deserialize = "..."))]`.
+fn serde_values(attrs: &[Attribute], key: &str) -> Vec<LitStr> {
+    let items = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten();
+
+    let mut values = Vec::new();
+
+    for item in items {
+        match item {
+            NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident(key) => {
+                values.extend(lit_str(pair.lit));
+            }
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident(key) => {
+                values.extend(list.nested.into_iter().filter_map(|nm| match nm {
+                    NestedMeta::Meta(Meta::NameValue(pair)) => lit_str(pair.lit),
+                    _ => None,
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    values
+}
+
+fn lit_str(lit: Lit) -> Option<LitStr> {
+    match lit {
+        Lit::Str(string) => Some(string),
+        _ => None,
+    }
+}
+
+/// The name of a tag the way `dasn1::der` parses it out of a serde name,
+/// e.g. `ASN.1#[APPLICATION 1]`, or `ASN.1#[1]` for a context-specific tag.
+pub fn tag_name((class, number): &(Ident, LitInt)) -> String {
+    match &*class.to_string() {
+        "Context" => format!("ASN.1#[{}]", number.base10_digits()),
+        class => format!("ASN.1#[{} {}]", class.to_uppercase(), number.base10_digits()),
+    }
+}
+
 fn find_asn_attribute(attrs: &[Attribute]) -> Option<MetaList> {
     attrs.iter()
          .filter_map(|a| a.parse_meta().ok())
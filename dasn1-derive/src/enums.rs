@@ -2,7 +2,7 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{DataEnum, Fields, Generics, Ident, Variant};
 
-use crate::attributes::EnumAttributes;
+use crate::attributes::{check_serde_name, tag_name, EnumAttributes, VariantAttributes};
 
 pub enum EnumKind {
     Choice,
@@ -21,6 +21,7 @@ impl EnumKind {
 
 pub struct Enum {
     pub attributes: EnumAttributes,
+    pub attrs: Vec<syn::Attribute>,
     pub kind: EnumKind,
     pub ident: Ident,
     pub generics: Generics,
@@ -37,9 +38,28 @@ impl super::AsnTypeGenerator for Enum {
     }
 
     fn generate_identifier_impl(&self) -> TokenStream {
+        if self.attributes.tagged_sequence {
+            return quote!(dasn1::identifier::Identifier::SEQUENCE);
+        }
+
         match self.kind {
             EnumKind::Enumerable => quote!(dasn1::identifier::Identifier::ENUMERATED),
-            EnumKind::Choice => self.create_pattern_match(format_ident!("self"), |i, _| {
+            EnumKind::Choice => self.create_pattern_match(format_ident!("self"), |i, fields| {
+                let attributes = VariantAttributes::from_syn(&self.variants[i].attrs);
+
+                // Untagged and unknown variants expose the identifier of
+                // their value.
+                if attributes.untagged || attributes.unknown {
+                    let field = &fields[0];
+                    return quote!(dasn1::identifier::AsnType::identifier(#field));
+                }
+
+                if let Some((class, number)) = attributes.tag {
+                    return quote!(
+                        dasn1::identifier::Identifier::new(dasn1::identifier::Class::#class, #number)
+                    );
+                }
+
                 let i = i as u32;
 
                 quote!(
@@ -68,7 +88,10 @@ impl super::AsnTypeGenerator for Enum {
             quote!()
         };
 
+        // The index of a tagged SEQUENCE's variant is encoded the same as a
+        // CHOICE's, followed by the variant's value.
         let encode_enum = match self.kind {
+            _ if self.attributes.tagged_sequence => self.generate_choice_per(&buf),
             EnumKind::Enumerable => self.generate_enumerable_per(&buf),
             EnumKind::Choice => self.generate_choice_per(&buf),
         };
@@ -88,6 +111,7 @@ impl Enum {
 
         Self {
             attributes: EnumAttributes::from_syn(attrs),
+            attrs: attrs.to_vec(),
             kind: EnumKind::from_variants(variants.iter()),
             ident,
             generics,
@@ -95,6 +119,192 @@ impl Enum {
         }
     }
 
+    /// Checks that no two variants of a CHOICE have the same tag, as the tag
+    /// is what tells the variants apart when decoding. Untagged and unknown
+    /// variants are left out, as their tags come from their values, as are
+    /// the variants of a tagged SEQUENCE, which are told apart by index.
+    pub fn check_unique_tags(&self) -> syn::Result<()> {
+        if self.attributes.tagged_sequence || matches!(self.kind, EnumKind::Enumerable) {
+            return Ok(());
+        }
+
+        let mut tags: Vec<(String, u32, &Ident)> = Vec::new();
+
+        for (index, variant) in self.variants.iter().enumerate() {
+            let attributes = VariantAttributes::from_syn(&variant.attrs);
+
+            if attributes.untagged || attributes.unknown {
+                continue;
+            }
+
+            let (class, number) = match attributes.tag {
+                Some((class, number)) => (class.to_string(), number.base10_parse::<u32>()?),
+                None => (String::from("Context"), index as u32),
+            };
+
+            if let Some((_, _, first)) = tags.iter().find(|(c, n, _)| *c == class && *n == number) {
+                let class = if class == "Context" { String::new() } else { class.to_uppercase() + " " };
+
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    format!("`{}` has the same tag as `{}`: [{}{}]", variant.ident, first, class, number),
+                ));
+            }
+
+            tags.push((class, number, &variant.ident));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the enum and the variants an `#[asn]` attribute changes
+    /// the encoding of are renamed for serde to match.
+    pub fn check_serde_names(&self) -> syn::Result<()> {
+        if self.attributes.tagged_sequence {
+            check_serde_name(&self.attrs, &self.ident, "tagged_sequence", "ASN.1#TaggedSequence", |name| {
+                name == "ASN.1#TaggedSequence"
+            })?;
+        }
+
+        for variant in &self.variants {
+            let attributes = VariantAttributes::from_syn(&variant.attrs);
+
+            if attributes.untagged {
+                check_serde_name(&variant.attrs, &variant.ident, "untagged", "ASN.1#Untagged", |name| {
+                    name.starts_with("ASN.1#Untagged")
+                })?;
+            }
+
+            if attributes.unknown {
+                check_serde_name(&variant.attrs, &variant.ident, "unknown", "ASN.1#Unknown", |name| {
+                    name.starts_with("ASN.1#Unknown")
+                })?;
+            }
+
+            if let Some(ref tag) = attributes.tag {
+                let tag = tag_name(tag);
+                check_serde_name(&variant.attrs, &variant.ident, "tag(..)", &tag, |name| name == tag)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generates the `Enumerable` impl, where variants without an explicit
+    /// discriminant follow on from the previous variant like in Rust. An
+    /// extensible enum may have one `#[asn(unknown)]` variant holding an
+    /// `i64`, which any value without a variant of its own decodes as.
+    pub fn into_enumerable_impl(self) -> syn::Result<TokenStream> {
+        let name = &self.ident;
+        let generics = &self.generics;
+        let extensible = self.attributes.extensible;
+        let mut previous: Option<TokenStream> = None;
+
+        let (unknown, known): (Vec<_>, Vec<_>) = self
+            .variants
+            .iter()
+            .partition(|variant| VariantAttributes::from_syn(&variant.attrs).unknown);
+
+        if let Some(variant) = known.iter().find(|variant| variant.fields != Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`Enumerable` can only be derived for enums without data, apart from an `#[asn(unknown)]` variant",
+            ));
+        }
+
+        let unknown = match &*unknown {
+            [] => None,
+            [variant] if !extensible => {
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    "An `#[asn(unknown)]` variant requires the enum to be `#[asn(extensible)]`",
+                ));
+            }
+            [variant] if variant.fields.len() == 1 => Some(&variant.ident),
+            [variant] => {
+                return Err(syn::Error::new_spanned(
+                    &variant.fields,
+                    "The `#[asn(unknown)]` variant must only hold the value, e.g. `Unknown(i64)`",
+                ));
+            }
+            [_, variant, ..] => {
+                return Err(syn::Error::new_spanned(&variant.ident, "Only one variant can be `#[asn(unknown)]`"));
+            }
+        };
+
+        if !extensible {
+            if let Some(variant) = known.iter().find(|v| VariantAttributes::from_syn(&v.attrs).extension) {
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    "`#[asn(extension)]` variants require the enum to be `#[asn(extensible)]`",
+                ));
+            }
+        }
+
+        // Values without a variant can only come from later versions of the
+        // type, so they're extensions too.
+        let extensions = known
+            .iter()
+            .filter(|variant| VariantAttributes::from_syn(&variant.attrs).extension)
+            .map(|variant| {
+                let variant = &variant.ident;
+                quote!(#name::#variant)
+            })
+            .chain(unknown.map(|variant| quote!(#name::#variant(_))))
+            .collect::<Vec<_>>();
+
+        let is_extension = if extensions.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                fn is_extension(&self) -> bool {
+                    matches!(self, #(#extensions)|*)
+                }
+            }
+        };
+
+        let (variants, values): (Vec<_>, Vec<_>) = known
+            .iter()
+            .map(|variant| {
+                let value = match (&variant.discriminant, &previous) {
+                    (Some((_, discriminant)), _) => quote!((#discriminant) as i64),
+                    (None, Some(previous)) => quote!(#previous + 1),
+                    (None, None) => quote!(0i64),
+                };
+
+                previous = Some(value.clone());
+                (&variant.ident, value)
+            })
+            .unzip();
+
+        let (unknown_value, unknown_variant) = match unknown {
+            Some(unknown) => (quote!(#name::#unknown(value) => *value,), quote!(Some(#name::#unknown(value)))),
+            None => (quote!(), quote!(None)),
+        };
+
+        Ok(quote! {
+            impl #generics dasn1::types::Enumerable for #name #generics {
+                const EXTENSIBLE: bool = #extensible;
+
+                fn to_enumerated(&self) -> i64 {
+                    match self {
+                        #(#name::#variants => #values,)*
+                        #unknown_value
+                    }
+                }
+
+                fn from_enumerated(value: i64) -> Option<Self> {
+                    match value {
+                        #(value if value == #values => Some(#name::#variants),)*
+                        _ => #unknown_variant,
+                    }
+                }
+
+                #is_extension
+            }
+        })
+    }
+
     /// Generates a match expression for `match_ident`, and calls
     /// `variant_arm_generator` for each arm of the match expression, providing
     /// the function with the index of each variant as well as list of `Ident`s
@@ -134,11 +344,11 @@ impl Enum {
         let encode_choice = self.create_pattern_match(format_ident!("self"), |index, fields| {
             let fields = fields.iter();
             quote! {
-                #buf.push_field_list(#index.encode_with_constraint(0..#max_index));
+                #buf.push_field_list(#index.encode_with_constraint(0..#max_index)?);
 
-                #(#buf.push_field_list(#fields.encode());)*
+                #(#buf.push_field_list(#fields.encode()?);)*
 
-                #buf
+                Ok(#buf)
             }
         });
 
@@ -11,24 +11,71 @@ use syn::{parse_macro_input, Data, DeriveInput, Generics, Ident};
 use enums::Enum;
 use structs::Struct;
 
-#[proc_macro_derive(AsnType, attributes(asn))]
+// `serde` is registered as well, so that the serde attributes some `#[asn]`
+// attributes require can be written on types that don't derive serde.
+#[proc_macro_derive(AsnType, attributes(asn, serde))]
 pub fn my_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
 
+    proc_macro::TokenStream::from(derive_asn_type(input))
+}
+
+fn derive_asn_type(input: DeriveInput) -> TokenStream {
+    if let Err(error) = attributes::check_tags(&input) {
+        return error.to_compile_error();
+    }
+
     let name = input.ident;
     let generics = input.generics;
     // let metas = input.attrs.into_iter().filter_map(|a| a.parse_meta().ok());
 
-    let generator = match input.data {
+    match input.data {
         Data::Struct(struct_data) => {
-            Struct::new(name, generics, &input.attrs, struct_data.fields).into_trait_impl()
+            let generator = Struct::new(name, generics, &input.attrs, struct_data.fields);
+
+            match generator.check_serde_name().and_then(|()| generator.generate_constraint_impl()) {
+                Ok(constraints) => {
+                    let asn_type = generator.into_trait_impl();
+                    quote!(#asn_type #constraints)
+                }
+                Err(error) => error.to_compile_error(),
+            }
+        }
+        Data::Enum(enum_data) => {
+            let generator = Enum::new(name, generics, &input.attrs, enum_data);
+
+            match generator.check_unique_tags().and_then(|()| generator.check_serde_names()) {
+                Ok(()) => generator.into_trait_impl(),
+                Err(error) => error.to_compile_error(),
+            }
         }
-        Data::Enum(enum_data) => Enum::new(name, generics, &input.attrs, enum_data).into_trait_impl(),
         _ => unimplemented!(),
-    };
+    }
+}
 
-    proc_macro::TokenStream::from(generator)
+/// Implements `Enumerable` for an `enum` without data, encoding each variant
+/// as its discriminant. An enum with an extension marker is annotated with
+/// `#[asn(extensible)]`, and the variants added after the marker with
+/// `#[asn(extension)]`. An extensible enum can also have an `#[asn(unknown)]`
+/// variant holding an `i64` (e.g. `Unknown(i64)`), which values added by
+/// later versions of the type decode as, instead of failing. An unknown
+/// variant holding the value of another variant, such as `Unknown(0)`, is
+/// encoded as that value, and so decodes as the other variant.
+#[proc_macro_derive(Enumerable, attributes(asn))]
+pub fn enumerable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    proc_macro::TokenStream::from(derive_enumerable(input))
+}
+
+fn derive_enumerable(input: DeriveInput) -> TokenStream {
+    match input.data {
+        Data::Enum(enum_data) => Enum::new(input.ident, input.generics, &input.attrs, enum_data)
+            .into_enumerable_impl()
+            .unwrap_or_else(|error| error.to_compile_error()),
+        _ => syn::Error::new_spanned(input.ident, "`Enumerable` can only be derived for enums").to_compile_error(),
+    }
 }
 
 trait AsnTypeGenerator: Sized {
@@ -53,7 +100,7 @@ trait AsnTypeGenerator: Sized {
             let per_impl = self.generate_per_impl();
             quote! {
                 impl #generics dasn1::per::PerEncodable for #name #generics {
-                    fn encode(&self) -> dasn1::per::ser::Buffer {
+                    fn encode(&self) -> dasn1::per::Result<dasn1::per::ser::Buffer> {
                         use dasn1::per::ConstrainedValue;
 
                         #per_impl
@@ -88,3 +135,73 @@ trait AsnTypeGenerator: Sized {
         }
     }
 }
+
+/// Golden tests of the generated code. Each input in `tests/expand` is
+/// expanded with the derives it lists, formatted with `prettyplease`, and
+/// compared with the code in its `.expanded.rs` file. Run with
+/// `DASN1_BLESS=1` to overwrite the expected code with the current output
+/// after an intended change. Inputs the derives reject are in `tests/ui`.
+#[cfg(all(test, feature = "per"))]
+mod tests {
+    use std::{env, fs, path::Path};
+
+    use syn::{Meta, NestedMeta};
+
+    use super::*;
+
+    fn expand(input: &DeriveInput) -> TokenStream {
+        let mut output = TokenStream::new();
+        let derives = input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("derive"))
+            .filter_map(|attr| match attr.parse_meta() {
+                Ok(Meta::List(list)) => Some(list.nested),
+                _ => None,
+            })
+            .flatten();
+
+        for derive in derives {
+            match derive {
+                NestedMeta::Meta(ref meta) if meta.path().is_ident("AsnType") => {
+                    output.extend(derive_asn_type(input.clone()))
+                }
+                NestedMeta::Meta(ref meta) if meta.path().is_ident("Enumerable") => {
+                    output.extend(derive_enumerable(input.clone()))
+                }
+                _ => {}
+            }
+        }
+
+        output
+    }
+
+    #[test]
+    fn expanded_code() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/expand");
+        let mut inputs: Vec<_> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| !path.to_string_lossy().ends_with(".expanded.rs"))
+            .collect();
+        inputs.sort();
+
+        assert!(!inputs.is_empty(), "No inputs found in {}", directory.display());
+
+        for path in inputs {
+            let input: DeriveInput = syn::parse_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            let actual = prettyplease::unparse(&syn::parse2(expand(&input)).unwrap());
+            let expected_path = path.with_extension("expanded.rs");
+
+            if env::var_os("DASN1_BLESS").is_some() {
+                fs::write(&expected_path, actual).unwrap();
+                continue;
+            }
+
+            let expected = fs::read_to_string(&expected_path)
+                .unwrap_or_else(|_| panic!("Missing {}, run with DASN1_BLESS=1", expected_path.display()));
+
+            assert!(expected == actual, "{} doesn't match:\n{}", expected_path.display(), actual);
+        }
+    }
+}
@@ -1,21 +1,31 @@
 
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::{Fields, Generics, Ident, Type};
+use syn::{Fields, Generics, Ident, Member, Type};
 
-pub use crate::attributes::{FieldAttributes, StructAttributes, Size};
+pub use crate::attributes::{check_serde_attribute, check_serde_name, parse_range, tag_name, FieldAttributes, StructAttributes, Size};
 
 pub struct Struct {
     ident: Ident,
     generics: Generics,
     fields: Fields,
+    attrs: Vec<syn::Attribute>,
     attributes: StructAttributes
 }
 
 impl Struct {
     pub fn new(ident: Ident, generics: Generics, attrs: &[syn::Attribute], fields: Fields) -> Self {
+        for field in fields.iter() {
+            if let Some(defined_by) = FieldAttributes::from_syn(&field.attrs).any_defined_by {
+                if !fields.iter().any(|f| f.ident.as_ref().is_some_and(|i| i == &defined_by)) {
+                    panic!("`any_defined_by` references the unknown field `{}`", defined_by);
+                }
+            }
+        }
+
         Self {
             attributes: StructAttributes::from_syn(attrs),
+            attrs: attrs.to_vec(),
             ident,
             generics,
             fields,
@@ -23,6 +33,184 @@ impl Struct {
     }
 }
 
+impl Struct {
+    /// Checks that a struct or field with a `tag`, or a struct with
+    /// `auto_context`, is renamed for serde after it. A struct's name may
+    /// also mark the SEQUENCE as extensible, and an explicitly tagged
+    /// field's name marks its tag as explicit.
+    pub fn check_serde_name(&self) -> syn::Result<()> {
+        let without_marker = |name: &str| name.strip_suffix("...").map_or(name, str::trim_end).to_owned();
+
+        match (&self.attributes.tag, self.attributes.auto_context) {
+            // Both are applied through the struct's name in `dasn1::der`.
+            (Some(_), true) => {
+                return Err(syn::Error::new_spanned(&self.ident, "`auto_context` can't be combined with `tag`"));
+            }
+            (Some(tag), false) => {
+                let tag = tag_name(tag);
+
+                check_serde_name(&self.attrs, &self.ident, "tag(..)", &tag, |name| without_marker(name) == tag)?;
+            }
+            (None, true) => {
+                check_serde_name(&self.attrs, &self.ident, "auto_context", "ASN.1#Automatic", |name| {
+                    without_marker(name) == "ASN.1#Automatic"
+                })?;
+            }
+            (None, false) => {}
+        }
+
+        for (i, field) in self.fields.iter().enumerate() {
+            let attributes = FieldAttributes::from_syn(&field.attrs);
+            let ident = field.ident.clone().map(Member::Named).unwrap_or_else(|| Member::Unnamed(i.into()));
+
+            let (tag, attribute) = match attributes.tag {
+                Some(ref tag) if attributes.explicit => (tag_name(tag) + " EXPLICIT", "tag(..), explicit"),
+                Some(ref tag) => (tag_name(tag), "tag(..)"),
+                None if attributes.explicit => {
+                    return Err(syn::Error::new_spanned(ident, "`explicit` requires a `tag`, e.g. `tag(context, 3)`"));
+                }
+                None => continue,
+            };
+
+            check_serde_name(&field.attrs, &ident, attribute, &tag, |name| name == tag)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generates an impl of `Constrained` checking each field with a `range`
+    /// or `size` constraint, and validating each field whose type is itself
+    /// `Constrained`, such as a nested struct or a `Vec` of them. Fields
+    /// with a constraint of their own also get a function deserializing
+    /// them, which rejects values not satisfying it and which the field has
+    /// to be marked with so that decoding checks the constraint, and named
+    /// fields get a setter, which only accepts values satisfying it.
+    pub fn generate_constraint_impl(&self) -> syn::Result<TokenStream> {
+        let name = &self.ident;
+        let generics = &self.generics;
+        let mut checks = Vec::new();
+        let mut methods = Vec::new();
+
+        for (i, field) in self.fields.iter().enumerate() {
+            let attributes = FieldAttributes::from_syn(&field.attrs);
+            let ident = field.ident.clone().map(Member::Named).unwrap_or_else(|| Member::Unnamed(i.into()));
+            let field_name = match field.ident {
+                Some(ref ident) => ident.to_string(),
+                None => i.to_string(),
+            };
+
+            let ranges = attributes
+                .range
+                .iter()
+                .map(|range| (parse_range(range, "range"), quote!(value)))
+                .chain(attributes.size_range.iter().map(|size| (parse_range(size, "size"), quote!(&value.len()))));
+
+            let mut conditions = Vec::new();
+
+            for (range, checked) in ranges {
+                let (description, range) = range?;
+
+                conditions.push(quote! {
+                    if !(#range).contains(#checked) {
+                        return Err(dasn1::types::ConstraintError::new(#field_name, #description));
+                    }
+                });
+            }
+
+            checks.push(quote!((&Field(&self.#ident)).validate_field()?;));
+
+            if conditions.is_empty() {
+                continue;
+            }
+
+            // Absent OPTIONAL fields have nothing to constrain.
+            let check = if is_option(&field.ty) {
+                quote!(if let Some(value) = value { #(#conditions)* })
+            } else {
+                quote!(#(#conditions)*)
+            };
+
+            checks.push(quote! {
+                let value = &self.#ident;
+                #check
+            });
+
+            let ty = &field.ty;
+            let deserialize = format_ident!("deserialize_{}", field_name);
+            let constraint = if attributes.range.is_some() { "range = .." } else { "size = .." };
+            let path = format!("{}::{}", name, deserialize);
+            let suffix = format!("::{}", deserialize);
+
+            check_serde_attribute(&field.attrs, "deserialize_with", &ident, constraint, &path, |value| {
+                value.ends_with(&suffix)
+            })?;
+
+            let doc = format!("Deserializes `{}`, if the value satisfies its constraints.", field_name);
+
+            methods.push(quote! {
+                #[doc = #doc]
+                pub fn #deserialize<'de, D>(deserializer: D) -> std::result::Result<#ty, D::Error>
+                where
+                    D: dasn1::types::constrained::Deserializer<'de>,
+                {
+                    dasn1::types::constrained::deserialize_checked(deserializer, |value: &#ty| {
+                        #check
+                        Ok(())
+                    })
+                }
+            });
+
+            if let Some(ref field_ident) = field.ident {
+                let setter = format_ident!("set_{}", field_ident);
+                let doc = format!("Sets `{}`, if `value` satisfies its constraints.", field_name);
+
+                methods.push(quote! {
+                    #[doc = #doc]
+                    pub fn #setter(&mut self, value: #ty) -> std::result::Result<(), dasn1::types::ConstraintError> {
+                        {
+                            let value = &value;
+                            #check
+                        }
+
+                        self.#ident = value;
+                        Ok(())
+                    }
+                });
+            }
+        }
+
+        let methods = if methods.is_empty() {
+            quote!()
+        } else {
+            quote! {
+                impl #generics #name #generics {
+                    #(#methods)*
+                }
+            }
+        };
+
+        // Brings in `validate_field`, which resolves to a field's own
+        // `validate` if its type has one, and to doing nothing otherwise.
+        let prelude = if checks.is_empty() {
+            quote!()
+        } else {
+            quote!(use dasn1::types::constrained::field::*;)
+        };
+
+        Ok(quote! {
+            impl #generics dasn1::types::Constrained for #name #generics {
+                fn validate(&self) -> std::result::Result<(), dasn1::types::ConstraintError> {
+                    #prelude
+                    #(#checks)*
+                    Ok(())
+                }
+            }
+
+            #methods
+        })
+    }
+}
+
 impl super::AsnTypeGenerator for Struct {
     fn name(&self) -> &Ident {
         &self.ident
@@ -33,6 +221,12 @@ impl super::AsnTypeGenerator for Struct {
     }
 
     fn generate_identifier_impl(&self) -> TokenStream {
+        if let Some((ref class, ref number)) = self.attributes.tag {
+            return quote! {
+                dasn1::identifier::Identifier::new(dasn1::identifier::Class::#class, #number)
+            };
+        }
+
         match self.fields {
             Fields::Unit => quote!(dasn1::identifier::Identifier::NULL),
             _ => quote!(dasn1::identifier::Identifier::SEQUENCE),
@@ -46,36 +240,36 @@ impl super::AsnTypeGenerator for Struct {
             // Enumerate first to get field order to be able to correctly access
             // tuple struct fields.
             .enumerate()
-            .filter(|(_, f)| match f.ty {
-                Type::Path(ref type_path) => {
-                    type_path
-                        .path
-                        .segments
-                        .first()
-                        .map(|s| s.ident == "Option")
-                        .unwrap_or(false)
-                },
-                _ => false,
-            })
+            .filter(|(_, f)| is_option(&f.ty))
             .map(|(i, f)| f.ident.clone().unwrap_or_else(|| format_ident!("{}", i)))
             .map(|ident| quote!(#buf.push(self.#ident.is_some());));
 
 
+        // Markers aren't components, so they're left out of the encoding.
         let fields_iter = self.fields.iter()
             .enumerate()
+            .filter(|(_, f)| !is_phantom_data(&f.ty))
             .map(|(i, f)| {
                 let ident = f.ident.clone().unwrap_or_else(|| format_ident!("{}", i));
                 let attributes = FieldAttributes::from_syn(&f.attrs);
 
-                if let Some(size) = attributes.size {
+                let encode = if let Some(size) = attributes.size {
                     match size {
                         Size::Fixed(_) => unimplemented!(),
                         Size::Range(start, end) => {
-                            quote!(self.#ident.encode_with_constraint(#start..=#end))
+                            quote!(encode_with_constraint(#start..=#end))
                         }
                     }
                 } else {
-                    quote!(self.#ident.encode())
+                    quote!(encode())
+                };
+
+                // The presence of optional fields is already encoded in the
+                // preamble, so absent fields are skipped entirely.
+                if is_option(&f.ty) {
+                    quote!(dasn1::per::encode_optional(&self.#ident, |value| value.#encode))
+                } else {
+                    quote!(self.#ident.#encode)
                 }
             });
 
@@ -91,9 +285,38 @@ impl super::AsnTypeGenerator for Struct {
 
             #(#optional_fields_iter)*
 
-            #(#buf.push_field_list(#fields_iter);)*
+            #(#buf.push_field_list(#fields_iter?);)*
 
-            #buf
+            Ok(#buf)
         }
     }
 }
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ref type_path) => {
+            type_path
+                .path
+                .segments
+                .first()
+                .map(|s| s.ident == "Option")
+                .unwrap_or(false)
+        },
+        _ => false,
+    }
+}
+
+/// Whether the type is `PhantomData`, by any path (e.g.
+/// `std::marker::PhantomData<T>`).
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ref type_path) => {
+            type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "PhantomData")
+        },
+        _ => false,
+    }
+}
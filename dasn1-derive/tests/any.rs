@@ -0,0 +1,53 @@
+use dasn1::{
+    der::{from_slice, to_vec},
+    types::{AnyValue, InvalidAnyValue, ObjectIdentifier},
+};
+use dasn1_derive::AsnType;
+use serde::{Deserialize, Serialize};
+
+#[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+struct AlgorithmIdentifier {
+    algorithm: ObjectIdentifier,
+    #[asn(any_defined_by = "algorithm")]
+    parameters: Option<AnyValue>,
+}
+
+#[test]
+fn sha256_with_null_parameters() {
+    let sha256 = AlgorithmIdentifier {
+        algorithm: ObjectIdentifier::new(vec![2, 16, 840, 1, 101, 3, 4, 2, 1]).unwrap(),
+        parameters: Some(AnyValue::new(vec![0x5, 0]).unwrap()),
+    };
+
+    let encoded = [
+        0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00,
+    ];
+
+    assert_eq!(&encoded[..], &*to_vec(&sha256).unwrap());
+    assert_eq!(sha256, from_slice(&encoded).unwrap());
+}
+
+#[test]
+fn ecdsa_with_absent_parameters() {
+    let ecdsa = AlgorithmIdentifier {
+        algorithm: ObjectIdentifier::new(vec![1, 2, 840, 10045, 4, 3, 2]).unwrap(),
+        parameters: None,
+    };
+
+    let encoded = [0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+
+    assert_eq!(&encoded[..], &*to_vec(&ecdsa).unwrap());
+    assert_eq!(ecdsa, from_slice(&encoded).unwrap());
+}
+
+#[test]
+fn any_value_is_exactly_one_value() {
+    assert!(AnyValue::new(vec![0x5, 0]).is_ok());
+    assert!(AnyValue::new(vec![0x9f, 0x81, 0x00, 0x81, 1, 0xff]).is_ok());
+
+    // Cut short, followed by another value, or with an indefinite length.
+    assert_eq!(Err(InvalidAnyValue), AnyValue::new(vec![]));
+    assert_eq!(Err(InvalidAnyValue), AnyValue::new(vec![0x4, 2, 0xca]));
+    assert_eq!(Err(InvalidAnyValue), AnyValue::new(vec![0x5, 0, 0x5, 0]));
+    assert_eq!(Err(InvalidAnyValue), AnyValue::new(vec![0x30, 0x80, 0, 0]));
+}
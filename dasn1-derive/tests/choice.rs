@@ -0,0 +1,119 @@
+use dasn1::identifier::{AsnType, Class, Identifier};
+use dasn1_derive::AsnType;
+
+#[test]
+fn untagged_variant() {
+    use serde::Serialize;
+
+    #[derive(AsnType, Serialize)]
+    enum Outer {
+        #[asn(untagged)]
+        #[serde(rename = "ASN.1#Untagged")]
+        A(Inner),
+        B(bool),
+    }
+
+    #[derive(AsnType, Serialize)]
+    enum Inner {
+        X(bool),
+        Y(u8),
+    }
+
+    let x = Outer::A(Inner::X(true));
+    let y = Outer::A(Inner::Y(1));
+
+    assert_eq!(Identifier::new(Class::Context, 0), x.identifier());
+    assert_eq!(Identifier::new(Class::Context, 1), y.identifier());
+    assert_eq!(Identifier::new(Class::Context, 1), Outer::B(true).identifier());
+}
+
+#[test]
+fn application_tagged_variant() {
+    use dasn1::der::{from_slice, to_vec};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    enum Choice {
+        #[serde(rename = "ASN.1#[0]")]
+        Context(u8),
+        #[asn(tag(application, 0))]
+        #[serde(rename = "ASN.1#[APPLICATION 0]")]
+        Application(u8),
+    }
+
+    let context = Choice::Context(5);
+    let application = Choice::Application(5);
+
+    assert_eq!(Identifier::new(Class::Context, 0), context.identifier());
+    assert_eq!(Identifier::new(Class::Application, 0), application.identifier());
+
+    assert_eq!(&[0x80, 1, 5][..], &*to_vec(&context).unwrap());
+    assert_eq!(&[0x40, 1, 5][..], &*to_vec(&application).unwrap());
+
+    assert_eq!(context, from_slice(&[0x80, 1, 5]).unwrap());
+    assert_eq!(application, from_slice(&[0x40, 1, 5]).unwrap());
+    assert!(from_slice::<Choice>(&[0xC0, 1, 5]).is_err());
+}
+
+#[test]
+fn unknown_variant() {
+    use dasn1::{
+        der::{from_slice, to_vec},
+        types::AnyValue,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    enum Choice {
+        #[serde(rename = "ASN.1#[0]")]
+        Known(u8),
+        #[asn(unknown)]
+        #[serde(rename = "ASN.1#Unknown")]
+        Unknown(AnyValue),
+    }
+
+    let unexpected = [0x89, 2, 0xca, 0xfe];
+    let unknown: Choice = from_slice(&unexpected).unwrap();
+
+    assert_eq!(Choice::Unknown(AnyValue::new(unexpected.to_vec()).unwrap()), unknown);
+    assert_eq!(Identifier::new(Class::Context, 9), unknown.identifier());
+    assert_eq!(&unexpected[..], &*to_vec(&unknown).unwrap());
+
+    assert_eq!(Choice::Known(5), from_slice(&[0x80, 1, 5]).unwrap());
+}
+
+#[test]
+fn tagged_sequence() {
+    use dasn1::der::{from_slice, to_vec};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    #[asn(tagged_sequence)]
+    #[serde(rename = "ASN.1#TaggedSequence")]
+    enum Event {
+        Started,
+        Progress(u8),
+        Failed(Vec<u8>),
+    }
+
+    let progress = Event::Progress(50);
+    let failed = Event::Failed(vec![1, 2]);
+
+    assert_eq!(Identifier::SEQUENCE, progress.identifier());
+
+    // SEQUENCE { type ENUMERATED, value CHOICE }
+    let encoded = to_vec(&progress).unwrap();
+    assert_eq!(&[0x30, 6, 0x0A, 1, 1, 0x02, 1, 50][..], &*encoded);
+    assert_eq!(progress, from_slice(&encoded).unwrap());
+
+    let encoded = to_vec(&failed).unwrap();
+    assert_eq!(&[0x30, 0x0B, 0x0A, 1, 2, 0x30, 6, 2, 1, 1, 2, 1, 2][..], &*encoded);
+    assert_eq!(failed, from_slice(&encoded).unwrap());
+
+    assert_eq!(&[0x30, 3, 0x0A, 1, 0][..], &*to_vec(&Event::Started).unwrap());
+    assert_eq!(Event::Started, from_slice(&[0x30, 3, 0x0A, 1, 0]).unwrap());
+
+    // An index without a variant, and a unit variant with a value.
+    assert!(from_slice::<Event>(&[0x30, 3, 0x0A, 1, 3]).is_err());
+    assert!(from_slice::<Event>(&[0x30, 5, 0x0A, 1, 0, 0x05, 0]).is_err());
+}
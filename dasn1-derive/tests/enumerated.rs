@@ -0,0 +1,76 @@
+use dasn1::{
+    der::{from_slice, to_vec},
+    types::{Enumerable, Enumerated},
+};
+use dasn1_derive::Enumerable;
+
+#[test]
+fn derived_enumerable() {
+    #[derive(Clone, Copy, Debug, Enumerable, PartialEq)]
+    enum Status {
+        Active,
+        Revoked = 5,
+        Suspended,
+    }
+
+    assert_eq!(0, Status::Active.to_enumerated());
+    assert_eq!(5, Status::Revoked.to_enumerated());
+    assert_eq!(6, Status::Suspended.to_enumerated());
+    assert_eq!(Some(Status::Suspended), Status::from_enumerated(6));
+    assert_eq!(None, Status::from_enumerated(1));
+    assert!(!Status::EXTENSIBLE);
+    assert!(!Status::Suspended.is_extension());
+
+    let revoked = Enumerated::new(Status::Revoked);
+    let suspended = Enumerated::new(Status::Suspended);
+
+    assert_eq!(&[0xA, 1, 5][..], &*to_vec(&revoked).unwrap());
+    assert_eq!(revoked, from_slice(&[0xA, 1, 5]).unwrap());
+    assert_eq!(suspended, from_slice(&to_vec(&suspended).unwrap()).unwrap());
+    assert!(from_slice::<Enumerated<Status>>(&[0xA, 1, 1]).is_err());
+}
+
+#[test]
+fn extensible_enumerable() {
+    #[derive(Clone, Copy, Debug, Enumerable, PartialEq)]
+    #[asn(extensible)]
+    enum Color {
+        Red,
+        Green,
+        #[asn(extension)]
+        Blue,
+    }
+
+    assert!(Color::EXTENSIBLE);
+    assert!(!Color::Green.is_extension());
+    assert!(Color::Blue.is_extension());
+    assert_eq!(Some(Color::Blue), Color::from_enumerated(2));
+}
+
+#[test]
+fn unknown_enumerable() {
+    #[derive(Clone, Copy, Debug, Enumerable, PartialEq)]
+    #[asn(extensible)]
+    enum Status {
+        Active,
+        Revoked,
+        #[asn(unknown)]
+        Unknown(i64),
+    }
+
+    assert_eq!(Some(Status::Revoked), Status::from_enumerated(1));
+    assert_eq!(Some(Status::Unknown(7)), Status::from_enumerated(7));
+    assert_eq!(7, Status::Unknown(7).to_enumerated());
+    assert!(Status::Unknown(7).is_extension());
+    assert!(!Status::Active.is_extension());
+
+    let unknown: Enumerated<Status> = from_slice(&[0xA, 1, 7]).unwrap();
+    assert_eq!(Status::Unknown(7), unknown.into_inner());
+    assert_eq!(&[0xA, 1, 7][..], &*to_vec(&unknown).unwrap());
+
+    // The value of a known variant decodes as that variant.
+    let known = Enumerated::new(Status::Unknown(0));
+    assert_eq!(&[0xA, 1, 0][..], &*to_vec(&known).unwrap());
+    let known: Enumerated<Status> = from_slice(&[0xA, 1, 0]).unwrap();
+    assert_eq!(Status::Active, known.into_inner());
+}
@@ -0,0 +1,37 @@
+impl dasn1::identifier::AsnType for Response {
+    fn identifier(&self) -> dasn1::identifier::Identifier {
+        match self {
+            Response::Accepted(__f0) => {
+                dasn1::identifier::Identifier::new(
+                    dasn1::identifier::Class::Context,
+                    0u32,
+                )
+            }
+            Response::Rejected(__f1) => {
+                dasn1::identifier::Identifier::new(
+                    dasn1::identifier::Class::Application,
+                    0,
+                )
+            }
+        }
+    }
+}
+impl dasn1::per::PerEncodable for Response {
+    fn encode(&self) -> dasn1::per::Result<dasn1::per::ser::Buffer> {
+        use dasn1::per::ConstrainedValue;
+        let mut buffer = dasn1::per::Buffer::new();
+        buffer.push(false);
+        match self {
+            Response::Accepted(__f0) => {
+                buffer.push_field_list(0usize.encode_with_constraint(0..2usize)?);
+                buffer.push_field_list(__f0.encode()?);
+                Ok(buffer)
+            }
+            Response::Rejected(__f1) => {
+                buffer.push_field_list(1usize.encode_with_constraint(0..2usize)?);
+                buffer.push_field_list(__f1.encode()?);
+                Ok(buffer)
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+#[derive(AsnType)]
+enum Response {
+    Accepted(bool),
+    #[asn(tag(application, 0))]
+    #[serde(rename = "ASN.1#[APPLICATION 0]")]
+    Rejected(u8),
+}
@@ -0,0 +1,94 @@
+impl dasn1::identifier::AsnType for Reading {
+    fn identifier(&self) -> dasn1::identifier::Identifier {
+        dasn1::identifier::Identifier::SEQUENCE
+    }
+}
+impl dasn1::per::PerEncodable for Reading {
+    fn encode(&self) -> dasn1::per::Result<dasn1::per::ser::Buffer> {
+        use dasn1::per::ConstrainedValue;
+        let mut buffer = dasn1::per::Buffer::new();
+        buffer.push_field_list(self.level.encode()?);
+        buffer.push_field_list(self.labels.encode()?);
+        Ok(buffer)
+    }
+}
+impl dasn1::types::Constrained for Reading {
+    fn validate(&self) -> std::result::Result<(), dasn1::types::ConstraintError> {
+        use dasn1::types::constrained::field::*;
+        (&Field(&self.level)).validate_field()?;
+        let value = &self.level;
+        if !(0..=100).contains(value) {
+            return Err(dasn1::types::ConstraintError::new("level", "0..=100"));
+        }
+        (&Field(&self.labels)).validate_field()?;
+        let value = &self.labels;
+        if !(1..4).contains(&value.len()) {
+            return Err(dasn1::types::ConstraintError::new("labels", "1..4"));
+        }
+        Ok(())
+    }
+}
+impl Reading {
+    ///Deserializes `level`, if the value satisfies its constraints.
+    pub fn deserialize_level<'de, D>(
+        deserializer: D,
+    ) -> std::result::Result<u8, D::Error>
+    where
+        D: dasn1::types::constrained::Deserializer<'de>,
+    {
+        dasn1::types::constrained::deserialize_checked(
+            deserializer,
+            |value: &u8| {
+                if !(0..=100).contains(value) {
+                    return Err(dasn1::types::ConstraintError::new("level", "0..=100"));
+                }
+                Ok(())
+            },
+        )
+    }
+    ///Sets `level`, if `value` satisfies its constraints.
+    pub fn set_level(
+        &mut self,
+        value: u8,
+    ) -> std::result::Result<(), dasn1::types::ConstraintError> {
+        {
+            let value = &value;
+            if !(0..=100).contains(value) {
+                return Err(dasn1::types::ConstraintError::new("level", "0..=100"));
+            }
+        }
+        self.level = value;
+        Ok(())
+    }
+    ///Deserializes `labels`, if the value satisfies its constraints.
+    pub fn deserialize_labels<'de, D>(
+        deserializer: D,
+    ) -> std::result::Result<Vec<u8>, D::Error>
+    where
+        D: dasn1::types::constrained::Deserializer<'de>,
+    {
+        dasn1::types::constrained::deserialize_checked(
+            deserializer,
+            |value: &Vec<u8>| {
+                if !(1..4).contains(&value.len()) {
+                    return Err(dasn1::types::ConstraintError::new("labels", "1..4"));
+                }
+                Ok(())
+            },
+        )
+    }
+    ///Sets `labels`, if `value` satisfies its constraints.
+    pub fn set_labels(
+        &mut self,
+        value: Vec<u8>,
+    ) -> std::result::Result<(), dasn1::types::ConstraintError> {
+        {
+            let value = &value;
+            if !(1..4).contains(&value.len()) {
+                return Err(dasn1::types::ConstraintError::new("labels", "1..4"));
+            }
+        }
+        self.labels = value;
+        Ok(())
+    }
+}
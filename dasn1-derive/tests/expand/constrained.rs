@@ -0,0 +1,10 @@
+#[derive(AsnType)]
+#[asn(fixed)]
+struct Reading {
+    #[asn(range = "0..=100")]
+    #[serde(deserialize_with = "Reading::deserialize_level")]
+    level: u8,
+    #[asn(size = "1..4")]
+    #[serde(deserialize_with = "Reading::deserialize_labels")]
+    labels: Vec<u8>,
+}
@@ -0,0 +1,21 @@
+impl dasn1::types::Enumerable for Color {
+    const EXTENSIBLE: bool = true;
+    fn to_enumerated(&self) -> i64 {
+        match self {
+            Color::Red => 0i64,
+            Color::Green => (5) as i64,
+            Color::Blue => (5) as i64 + 1,
+        }
+    }
+    fn from_enumerated(value: i64) -> Option<Self> {
+        match value {
+            value if value == 0i64 => Some(Color::Red),
+            value if value == (5) as i64 => Some(Color::Green),
+            value if value == (5) as i64 + 1 => Some(Color::Blue),
+            _ => None,
+        }
+    }
+    fn is_extension(&self) -> bool {
+        matches!(self, Color::Blue)
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Enumerable)]
+#[asn(extensible)]
+enum Color {
+    Red,
+    Green = 5,
+    #[asn(extension)]
+    Blue,
+}
@@ -0,0 +1,20 @@
+impl dasn1::types::Enumerable for Status {
+    const EXTENSIBLE: bool = true;
+    fn to_enumerated(&self) -> i64 {
+        match self {
+            Status::Active => 0i64,
+            Status::Revoked => 0i64 + 1,
+            Status::Unknown(value) => *value,
+        }
+    }
+    fn from_enumerated(value: i64) -> Option<Self> {
+        match value {
+            value if value == 0i64 => Some(Status::Active),
+            value if value == 0i64 + 1 => Some(Status::Revoked),
+            _ => Some(Status::Unknown(value)),
+        }
+    }
+    fn is_extension(&self) -> bool {
+        matches!(self, Status::Unknown(_))
+    }
+}
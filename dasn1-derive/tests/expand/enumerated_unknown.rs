@@ -0,0 +1,8 @@
+#[derive(Enumerable)]
+#[asn(extensible)]
+enum Status {
+    Active,
+    Revoked,
+    #[asn(unknown)]
+    Unknown(i64),
+}
@@ -0,0 +1,27 @@
+impl dasn1::identifier::AsnType for Ticket {
+    fn identifier(&self) -> dasn1::identifier::Identifier {
+        dasn1::identifier::Identifier::new(dasn1::identifier::Class::Application, 1)
+    }
+}
+impl dasn1::per::PerEncodable for Ticket {
+    fn encode(&self) -> dasn1::per::Result<dasn1::per::ser::Buffer> {
+        use dasn1::per::ConstrainedValue;
+        let mut buffer = dasn1::per::Buffer::new();
+        buffer.push(false);
+        buffer.push(self.note.is_some());
+        buffer.push_field_list(self.number.encode()?);
+        buffer
+            .push_field_list(
+                dasn1::per::encode_optional(&self.note, |value| value.encode())?,
+            );
+        Ok(buffer)
+    }
+}
+impl dasn1::types::Constrained for Ticket {
+    fn validate(&self) -> std::result::Result<(), dasn1::types::ConstraintError> {
+        use dasn1::types::constrained::field::*;
+        (&Field(&self.number)).validate_field()?;
+        (&Field(&self.note)).validate_field()?;
+        Ok(())
+    }
+}
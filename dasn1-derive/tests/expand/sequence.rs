@@ -0,0 +1,7 @@
+#[derive(AsnType)]
+#[asn(tag(application, 1))]
+#[serde(rename = "ASN.1#[APPLICATION 1] ...")]
+struct Ticket {
+    number: u8,
+    note: Option<bool>,
+}
@@ -0,0 +1,23 @@
+impl dasn1::identifier::AsnType for Event {
+    fn identifier(&self) -> dasn1::identifier::Identifier {
+        dasn1::identifier::Identifier::SEQUENCE
+    }
+}
+impl dasn1::per::PerEncodable for Event {
+    fn encode(&self) -> dasn1::per::Result<dasn1::per::ser::Buffer> {
+        use dasn1::per::ConstrainedValue;
+        let mut buffer = dasn1::per::Buffer::new();
+        buffer.push(false);
+        match self {
+            Event::Started => {
+                buffer.push_field_list(0usize.encode_with_constraint(0..2usize)?);
+                Ok(buffer)
+            }
+            Event::Progress(__f1) => {
+                buffer.push_field_list(1usize.encode_with_constraint(0..2usize)?);
+                buffer.push_field_list(__f1.encode()?);
+                Ok(buffer)
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+#[derive(AsnType)]
+#[asn(tagged_sequence)]
+#[serde(rename = "ASN.1#TaggedSequence")]
+enum Event {
+    Started,
+    Progress(u8),
+}
@@ -12,7 +12,7 @@ fn fixed_sequence() {
     }
 
     let seq = Sequence { a: 1, b: 2, c: 3 };
-    let encoded = seq.encode();
+    let encoded = seq.encode().unwrap();
     assert_eq!(32, encoded.len());
     assert_eq!(&[01, 00, 02, 03][..], &*encoded.to_bytes());
 }
@@ -26,5 +26,182 @@ fn extensible_sequence() {
         c: u8,
     }
 
-    assert_eq!(33, Sequence::default().encode().len());
+    assert_eq!(33, Sequence::default().encode().unwrap().len());
+}
+
+#[test]
+fn optional_field() {
+    #[derive(AsnType)]
+    #[asn(fixed)]
+    struct Sequence {
+        a: u8,
+        b: Option<u8>,
+    }
+
+    let present = Sequence { a: 1, b: Some(2) }.encode().unwrap();
+    assert_eq!(17, present.len());
+    assert_eq!(&[0b1000_0000, 0b1000_0001, 0][..], &*present.to_bytes());
+
+    let absent = Sequence { a: 1, b: None }.encode().unwrap();
+    assert_eq!(9, absent.len());
+    assert_eq!(&[0b0000_0000, 0b1000_0000][..], &*absent.to_bytes());
+}
+
+#[test]
+fn recursive_sequence() {
+    use std::{rc::Rc, sync::Arc};
+
+    use dasn1::{
+        der::{from_slice, to_vec},
+        identifier::{AsnType as _, Identifier},
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    struct Node {
+        value: u8,
+        next: Option<Box<Node>>,
+    }
+
+    let list = Node {
+        value: 1,
+        next: Some(Box::new(Node {
+            value: 2,
+            next: Some(Box::new(Node { value: 3, next: None })),
+        })),
+    };
+
+    let encoded = to_vec(&list).unwrap();
+    assert_eq!(
+        &[0x30, 13, 0x02, 1, 1, 0x30, 8, 0x02, 1, 2, 0x30, 3, 0x02, 1, 3][..],
+        &*encoded
+    );
+    assert_eq!(list, from_slice(&encoded).unwrap());
+    assert_eq!(Identifier::SEQUENCE, list.next.as_ref().unwrap().identifier());
+
+    // Shared pointers encode the same as the value they point to.
+    let shared = Rc::new(Node { value: 3, next: None });
+    assert_eq!(to_vec(&*shared).unwrap(), to_vec(&shared).unwrap());
+    assert_eq!(shared, from_slice::<Rc<Node>>(&to_vec(&shared).unwrap()).unwrap());
+    assert_eq!(*shared, *from_slice::<Arc<Node>>(&to_vec(&shared).unwrap()).unwrap());
+    assert_eq!(list.encode().unwrap().to_bytes(), Box::new(list).encode().unwrap().to_bytes());
+}
+
+#[test]
+fn required_field() {
+    use dasn1::der::{from_slice, to_vec};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    struct Sequence {
+        a: u8,
+        #[serde(with = "dasn1::types::required")]
+        b: Option<u8>,
+    }
+
+    let present = Sequence { a: 1, b: Some(2) };
+    let encoded = [0x30, 6, 0x02, 1, 1, 0x02, 1, 2];
+    assert_eq!(&encoded[..], &*to_vec(&present).unwrap());
+    assert_eq!(present, from_slice(&encoded).unwrap());
+
+    assert!(to_vec(&Sequence { a: 1, b: None }).is_err());
+    assert!(from_slice::<Sequence>(&[0x30, 3, 0x02, 1, 1]).is_err());
+}
+
+#[test]
+fn phantom_data_field() {
+    use std::marker::PhantomData;
+
+    use dasn1::der::{from_slice, to_vec};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq)]
+    struct Version2;
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    #[asn(fixed)]
+    struct Marked<V> {
+        a: u8,
+        version: PhantomData<V>,
+        b: Option<u8>,
+    }
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    #[asn(fixed)]
+    struct Unmarked {
+        a: u8,
+        b: Option<u8>,
+    }
+
+    let marked = Marked::<Version2> { a: 1, version: PhantomData, b: Some(2) };
+    let unmarked = Unmarked { a: 1, b: Some(2) };
+
+    let encoded = to_vec(&marked).unwrap();
+    assert_eq!(to_vec(&unmarked).unwrap(), encoded);
+    assert_eq!(marked, from_slice(&encoded).unwrap());
+    assert_eq!(unmarked.encode().unwrap().to_bytes(), marked.encode().unwrap().to_bytes());
+
+    let marked = Marked::<Version2> { b: None, ..marked };
+    assert_eq!(marked, from_slice(&to_vec(&marked).unwrap()).unwrap());
+}
+
+#[test]
+fn range_constraint() {
+    use dasn1::{
+        der::{from_slice, from_slice_validated, to_vec},
+        types::Constrained,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    #[asn(fixed)]
+    struct Reading {
+        #[asn(range = "0..=100")]
+        #[serde(deserialize_with = "Reading::deserialize_level")]
+        level: u8,
+        #[asn(size = "1..4")]
+        #[serde(deserialize_with = "Reading::deserialize_labels")]
+        labels: Vec<u8>,
+    }
+
+    let mut reading = Reading { level: 50, labels: vec![1] };
+    assert_eq!(Ok(()), reading.validate());
+    assert_eq!(reading, from_slice(&to_vec(&reading).unwrap()).unwrap());
+
+    // A level above 100 is still a valid INTEGER, so it's the constraint
+    // checked while decoding that rejects it.
+    let out_of_range = [0x30, 0x08, 0x02, 0x01, 0x65, 0x30, 0x03, 0x02, 0x01, 0x01];
+    match from_slice::<Reading>(&out_of_range) {
+        Err(error) => assert!(error.to_string().contains("`level`")),
+        result => panic!("expected a constraint error, got {:?}", result),
+    }
+
+    let no_labels = [0x30, 0x05, 0x02, 0x01, 0x05, 0x30, 0x00];
+    assert!(from_slice::<Reading>(&no_labels).is_err());
+
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    #[asn(fixed)]
+    struct Log {
+        readings: Vec<Reading>,
+        latest: Option<Reading>,
+    }
+
+    // Readings nested in other types are validated along with them, whether
+    // while decoding or after.
+    let log = Log { readings: vec![Reading { level: 101, labels: vec![1] }], latest: None };
+    assert!(from_slice::<Log>(&to_vec(&log).unwrap()).is_err());
+    assert_eq!("level", log.validate().unwrap_err().field());
+
+    let log = Log { readings: vec![Reading { level: 5, labels: vec![1] }], latest: None };
+    assert_eq!(log, from_slice_validated::<Log>(&to_vec(&log).unwrap()).unwrap());
+
+    let log = Log { readings: Vec::new(), latest: Some(Reading { level: 5, labels: Vec::new() }) };
+    assert_eq!("labels", log.validate().unwrap_err().field());
+
+    assert!(reading.set_level(101).is_err());
+    assert!(reading.set_labels(Vec::new()).is_err());
+    assert_eq!(50, reading.level);
+
+    reading.set_level(100).unwrap();
+    assert_eq!(100, reading.level);
 }
@@ -0,0 +1,136 @@
+use dasn1::{
+    der::{from_slice, to_vec},
+    identifier::{AsnType as _, Class, Identifier},
+};
+use dasn1_derive::AsnType;
+use serde::{Deserialize, Serialize};
+
+#[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+#[asn(tag(application, 1))]
+#[serde(rename = "ASN.1#[APPLICATION 1]")]
+struct BindRequest {
+    version: u8,
+    authenticated: bool,
+}
+
+#[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+struct Message {
+    id: u8,
+    request: Option<BindRequest>,
+}
+
+#[test]
+fn application_tagged_sequence() {
+    let request = BindRequest { version: 3, authenticated: true };
+    assert_eq!(Identifier::new(Class::Application, 1), request.identifier());
+
+    let encoded = [0x61, 0x06, 0x02, 0x01, 0x03, 0x01, 0x01, 0xff];
+
+    assert_eq!(&encoded[..], &*to_vec(&request).unwrap());
+    assert_eq!(request, from_slice(&encoded).unwrap());
+    assert!(from_slice::<BindRequest>(&[0x30, 0x06, 0x02, 0x01, 0x03, 0x01, 0x01, 0xff]).is_err());
+}
+
+#[test]
+fn optional_tagged_sequence() {
+    let message = Message {
+        id: 1,
+        request: Some(BindRequest { version: 3, authenticated: false }),
+    };
+
+    let encoded = to_vec(&message).unwrap();
+    assert_eq!(0x61, encoded[5]);
+    assert_eq!(message, from_slice(&encoded).unwrap());
+
+    let message = Message { id: 1, request: None };
+    assert_eq!(message, from_slice(&to_vec(&message).unwrap()).unwrap());
+}
+
+#[test]
+fn automatically_tagged_fields() {
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    #[asn(auto_context)]
+    #[serde(rename = "ASN.1#Automatic")]
+    struct Ticket {
+        version: u8,
+        lifetime: Option<u16>,
+        renewable: bool,
+    }
+
+    let ticket = Ticket {
+        version: 5,
+        lifetime: Some(300),
+        renewable: true,
+    };
+
+    let encoded = [0x30, 10, 0x80, 1, 5, 0x81, 2, 0x1, 0x2c, 0x82, 1, 0xff];
+    assert_eq!(&encoded[..], &*to_vec(&ticket).unwrap());
+    assert_eq!(ticket, from_slice(&encoded).unwrap());
+
+    let ticket = Ticket { lifetime: None, ..ticket };
+    let encoded = [0x30, 6, 0x80, 1, 5, 0x82, 1, 0xff];
+    assert_eq!(&encoded[..], &*to_vec(&ticket).unwrap());
+    assert_eq!(ticket, from_slice(&encoded).unwrap());
+}
+
+#[test]
+fn optional_tagged_fields() {
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        a: u8,
+        #[serde(rename = "ASN.1#[0]")]
+        b: Option<u8>,
+        #[serde(rename = "ASN.1#[1]")]
+        c: Option<bool>,
+    }
+
+    let cases: [(Record, &[u8]); 4] = [
+        (Record { a: 1, b: None, c: None }, &[0x30, 3, 0x02, 1, 1]),
+        (Record { a: 1, b: Some(2), c: None }, &[0x30, 6, 0x02, 1, 1, 0x80, 1, 2]),
+        (Record { a: 1, b: None, c: Some(true) }, &[0x30, 6, 0x02, 1, 1, 0x81, 1, 0xff]),
+        (
+            Record { a: 1, b: Some(2), c: Some(false) },
+            &[0x30, 9, 0x02, 1, 1, 0x80, 1, 2, 0x81, 1, 0],
+        ),
+    ];
+
+    for (record, encoded) in cases.iter() {
+        assert_eq!(*encoded, &*to_vec(record).unwrap());
+        assert_eq!(*record, from_slice(encoded).unwrap());
+    }
+
+    // An element with neither tag is left over once every field is decoded.
+    assert!(from_slice::<Record>(&[0x30, 6, 0x02, 1, 1, 0x82, 1, 2]).is_err());
+}
+
+#[test]
+fn explicitly_tagged_sequence_of() {
+    #[derive(AsnType, Debug, Deserialize, PartialEq, Serialize)]
+    struct Certificate {
+        serial_number: u8,
+        #[asn(tag(context, 1))]
+        #[serde(rename = "ASN.1#[1]")]
+        issuer_unique_id: Option<bool>,
+        #[asn(tag(context, 3), explicit)]
+        #[serde(rename = "ASN.1#[3] EXPLICIT")]
+        extensions: Option<Vec<u32>>,
+    }
+
+    let certificate = Certificate {
+        serial_number: 1,
+        issuer_unique_id: None,
+        extensions: Some(vec![2, 3]),
+    };
+
+    let encoded = [0x30, 13, 0x02, 1, 1, 0xa3, 8, 0x30, 6, 0x02, 1, 2, 0x02, 1, 3];
+    assert_eq!(&encoded[..], &*to_vec(&certificate).unwrap());
+    assert_eq!(certificate, from_slice(&encoded).unwrap());
+
+    let certificate = Certificate { extensions: None, ..certificate };
+    let encoded = [0x30, 3, 0x02, 1, 1];
+    assert_eq!(&encoded[..], &*to_vec(&certificate).unwrap());
+    assert_eq!(certificate, from_slice(&encoded).unwrap());
+
+    // The explicit tag can't use the primitive form.
+    assert!(from_slice::<Certificate>(&[0x30, 13, 0x02, 1, 1, 0x83, 8, 0x30, 6, 0x02, 1, 2, 0x02, 1, 3]).is_err());
+}
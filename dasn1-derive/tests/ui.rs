@@ -0,0 +1,7 @@
+/// Inputs the derives reject, each compared with the compiler's errors in
+/// its `.stderr` file. Run with `TRYBUILD=overwrite` to overwrite the
+/// expected errors with the current output after an intended change.
+#[test]
+fn compile_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,10 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+#[asn(auto_context, tag(application, 1))]
+struct Ticket {
+    version: u8,
+    lifetime: Option<u16>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+enum Request {
+    #[asn(tag(context, 1))]
+    #[serde(rename = "ASN.1#[1]")]
+    Open(bool),
+    #[asn(tag(context, 1))]
+    #[serde(rename = "ASN.1#[1]")]
+    Close(u8),
+}
+
+fn main() {}
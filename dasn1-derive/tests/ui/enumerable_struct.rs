@@ -0,0 +1,8 @@
+use dasn1_derive::Enumerable;
+
+#[derive(Enumerable)]
+struct Status {
+    active: bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use dasn1_derive::Enumerable;
+
+#[derive(Enumerable)]
+enum Status {
+    Active,
+    Suspended(u8),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+struct Certificate {
+    serial_number: u8,
+    #[asn(tag(context, 3), explicit)]
+    #[serde(rename = "ASN.1#[3]")]
+    extensions: Option<Vec<u32>>,
+}
+
+fn main() {}
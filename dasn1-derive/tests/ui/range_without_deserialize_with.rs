@@ -0,0 +1,9 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+struct Reading {
+    #[asn(range = "0..=100")]
+    level: u8,
+}
+
+fn main() {}
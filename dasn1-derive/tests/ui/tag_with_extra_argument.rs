@@ -0,0 +1,9 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+enum Foo {
+    #[asn(tag(context, 1, 2))]
+    A(bool),
+}
+
+fn main() {}
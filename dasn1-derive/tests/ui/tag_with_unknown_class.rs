@@ -0,0 +1,9 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+#[asn(tag(public, 1))]
+struct Foo {
+    a: bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+struct Foo {
+    #[asn(tag(context))]
+    a: bool,
+}
+
+fn main() {}
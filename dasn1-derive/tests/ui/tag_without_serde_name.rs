@@ -0,0 +1,10 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+#[asn(tag(application, 1))]
+#[serde(rename = "ASN.1#[APPLICATION 2]")]
+struct Ticket {
+    number: u8,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+#[asn(tagged_sequence)]
+enum Event {
+    Started,
+    Progress(u8),
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use dasn1_derive::Enumerable;
+
+#[derive(Enumerable)]
+#[asn(extensible)]
+enum Status {
+    Active,
+    #[asn(unknown)]
+    Unknown(i64),
+    #[asn(unknown)]
+    Other(i64),
+}
+
+fn main() {}
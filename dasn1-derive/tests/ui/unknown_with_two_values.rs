@@ -0,0 +1,11 @@
+use dasn1_derive::Enumerable;
+
+#[derive(Enumerable)]
+#[asn(extensible)]
+enum Status {
+    Active,
+    #[asn(unknown)]
+    Unknown(i64, i64),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dasn1_derive::Enumerable;
+
+#[derive(Enumerable)]
+enum Status {
+    Active,
+    #[asn(unknown)]
+    Unknown(i64),
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use dasn1::types::AnyValue;
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+enum Choice {
+    Known(u8),
+    #[asn(unknown)]
+    #[serde(rename = "Other")]
+    Unknown(AnyValue),
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use dasn1_derive::AsnType;
+
+struct Inner;
+
+#[derive(AsnType)]
+enum Value {
+    #[asn(untagged)]
+    Nested(Inner),
+    Flag(bool),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dasn1_derive::AsnType;
+
+#[derive(AsnType)]
+enum Response {
+    Accepted(bool),
+    #[asn(tag(private, 3))]
+    Rejected(u8),
+}
+
+fn main() {}
@@ -1,15 +1,21 @@
 mod constant;
+mod dot;
+mod enums;
 mod imports;
 mod structs;
 
 use std::{collections::HashSet, fmt, io::Write, mem};
 
-use failure::Fallible as Result;
+use failure::{bail, Fallible as Result};
 use heck::*;
+use itertools::Itertools;
 
-use self::{constant::Constant, imports::*, structs::*};
+pub use self::dot::Dot;
+
+use self::{constant::Constant, enums::*, imports::*, structs::*};
 use crate::{
     parser::*,
+    registry::GlobalSymbolTable,
     semantics::SemanticChecker,
 };
 
@@ -32,6 +38,16 @@ impl fmt::Display for TagEnvironment {
     }
 }
 
+impl From<&Tag> for TagEnvironment {
+    fn from(tag: &Tag) -> Self {
+        match tag {
+            Tag::Explicit => TagEnvironment::Explicit,
+            Tag::Implicit => TagEnvironment::Implicit,
+            Tag::Automatic => TagEnvironment::Automatic,
+        }
+    }
+}
+
 impl Default for TagEnvironment {
     fn default() -> Self {
         TagEnvironment::Automatic
@@ -40,11 +56,23 @@ impl Default for TagEnvironment {
 
 pub trait Backend: Default {
     fn tag_environment(&mut self, environment: TagEnvironment);
+    /// Whether to name the generated fields and variants after their ASN.1
+    /// identifiers for serde, and to derive serde for every generated type.
+    fn serde_names(&mut self, enabled: bool);
+    /// The module's assignments, for looking up the types referenced by
+    /// name.
+    fn symbol_table(&mut self, table: &GlobalSymbolTable);
     fn generate_type(&mut self, ty: &Type) -> Result<String>;
     fn generate_value(&mut self, value: &Value) -> Result<String>;
     fn generate_value_assignment(&mut self, name: String, ty: Type, value: Value) -> Result<()>;
     fn generate_sequence(&mut self, name: &str, fields: &ComponentTypeList) -> Result<String>;
     fn generate_sequence_of(&mut self, name: &str, ty: &Type) -> Result<String>;
+    fn generate_enumerated(
+        &mut self,
+        name: &str,
+        root: &[Enumeration],
+        additions: Option<&[Enumeration]>,
+    ) -> Result<String>;
     fn generate_builtin(&mut self, builtin: &BuiltinType) -> Result<String>;
     fn write_prelude<W: Write>(&mut self, writer: &mut W) -> Result<()>;
     fn write_footer<W: Write>(&self, writer: &mut W) -> Result<()>;
@@ -53,8 +81,9 @@ pub trait Backend: Default {
 #[derive(Default)]
 pub struct Rust {
     environment: TagEnvironment,
+    serde_names: bool,
+    table: GlobalSymbolTable,
     consts: HashSet<Constant>,
-    structs: Vec<Struct>,
     prelude: HashSet<Import>,
 }
 
@@ -62,43 +91,109 @@ impl Backend for Rust {
     fn tag_environment(&mut self, environment: TagEnvironment) {
         self.environment = environment;
     }
-    /// As Rust doesn't allow you to have anonymous structs,
-    /// `generate_sequence` returns the name of the struct and
-    /// stores the definition seperately.
+
+    fn serde_names(&mut self, enabled: bool) {
+        self.serde_names = enabled;
+    }
+
+    fn symbol_table(&mut self, table: &GlobalSymbolTable) {
+        self.table = table.clone();
+    }
+
     fn generate_sequence(&mut self, name: &str, components: &ComponentTypeList) -> Result<String> {
-        let mut generated_struct = Struct::new(name);
+        let mut generated_struct = Struct::new(name.to_camel_case());
 
         for field in components.components.as_ref().unwrap() {
             // Unwrap currently needed as i haven't created the simplified AST without
             // `ComponentsOf` yet.
             let (ty, optional, default) = field.as_type().unwrap();
-            let field = FieldBuilder::new(ty.name.as_ref().unwrap().to_snake_case(), self.generate_type(&ty)?)
+            let name = ty.name.as_ref().unwrap();
+            let rename = Some(name.clone()).filter(|name| self.serde_names && *name != name.to_snake_case());
+            let field = FieldBuilder::new(name.to_snake_case(), self.generate_type(&ty)?)
                 .optional(*optional)
-                .default_value(default.clone().and_then(|v| self.generate_value(&v).ok()))
+                .rename(rename)
+                .default_value(default.as_ref().map(|value| self.generate_default(ty, value)))
+                .any_defined_by(any_defined_by(ty))
+                .implements_default(self.implements_default(ty))
                 .build();
 
             generated_struct.add_field(field);
         }
 
-        self.structs.push(generated_struct);
-
-        Ok(name.to_camel_case())
+        Ok(generated_struct.to_string())
     }
 
     fn generate_sequence_of(&mut self, name: &str, ty: &Type) -> Result<String> {
-        let inner_type = match ty.raw_type {
-            RawType::Referenced(ref reference) => &reference.item,
-            _ => unimplemented!(),
+        let inner_type = self.generate_type(ty)?;
+
+        Ok(format!("pub type {} = Vec<{}>;", name.to_camel_case(), inner_type))
+    }
+
+    fn generate_enumerated(
+        &mut self,
+        name: &str,
+        root: &[Enumeration],
+        additions: Option<&[Enumeration]>,
+    ) -> Result<String> {
+        let mut generated_enum = Enum::new(name.to_camel_case());
+
+        if self.serde_names {
+            generated_enum.derive_serde();
+        }
+
+        let serde_names = self.serde_names;
+        let variant = |item: &Enumeration, value, extension| {
+            let variant = Variant::new(item.name().to_camel_case(), value, extension);
+
+            if serde_names {
+                variant.rename(item.name())
+            } else {
+                variant
+            }
         };
 
-        Ok(format!("pub type {} = Vec<{}>;", name, inner_type))
+        let values = enumeration_values(root, additions, &self.table)?;
+
+        if additions.is_some() {
+            generated_enum.extensible();
+        }
+
+        for (item, value, extension) in values {
+            generated_enum.add_variant(variant(item, value, extension));
+        }
+
+        Ok(generated_enum.to_string())
     }
 
     fn generate_type(&mut self, ty: &Type) -> Result<String> {
         match ty.raw_type {
             RawType::Builtin(ref builtin) => self.generate_builtin(builtin),
             RawType::Referenced(ref reference) if reference.is_internal() => {
-                Ok(reference.item.clone())
+                let resolved = self
+                    .table
+                    .resolve(&reference.item)
+                    .map(|(name, ty)| (name.to_camel_case(), ty.clone()));
+
+                match resolved {
+                    // Only some types are generated as items of their own,
+                    // so references to any other type are replaced by it.
+                    Some((_, ty)) if !is_generated(&ty) => self.generate_type(&ty),
+                    // An `enum` on its own is a CHOICE, so it's wrapped to be
+                    // encoded as an ENUMERATED.
+                    Some((name, Type { raw_type: RawType::Builtin(BuiltinType::Enumeration(..)), .. })) => {
+                        self.prelude.insert(Import::new(
+                            Visibility::Private,
+                            ["asn1", "types", "Enumerated"]
+                                .into_iter()
+                                .map(ToString::to_string)
+                                .collect(),
+                        ));
+
+                        Ok(format!("Enumerated<{}>", name))
+                    }
+                    Some((name, _)) => Ok(name),
+                    None => Ok(reference.item.to_camel_case()),
+                }
             }
             ref raw => {
                 warn!("UNKNOWN TYPE: {:?}", raw);
@@ -110,6 +205,12 @@ impl Backend for Rust {
     fn generate_builtin(&mut self, builtin: &BuiltinType) -> Result<String> {
         let output = match builtin {
             BuiltinType::Boolean => String::from("bool"),
+            // The time types are encoded as strings in their ISO 8601 form.
+            BuiltinType::Date
+            | BuiltinType::DateTime
+            | BuiltinType::Duration
+            | BuiltinType::Time
+            | BuiltinType::TimeOfDay => String::from("String"),
             BuiltinType::ObjectIdentifier => {
                 self.prelude.insert(Import::new(
                     Visibility::Private,
@@ -133,6 +234,17 @@ impl Backend for Rust {
 
                 String::from("OctetString")
             }
+            BuiltinType::OpenType => {
+                self.prelude.insert(Import::new(
+                    Visibility::Private,
+                    ["asn1", "types", "AnyValue"]
+                        .into_iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                ));
+
+                String::from("AnyValue")
+            }
             BuiltinType::Integer(_) => {
                 self.prelude.insert(Import::new(
                     Visibility::Private,
@@ -146,10 +258,30 @@ impl Backend for Rust {
             },
             BuiltinType::Prefixed(prefix, ty) => {
 
+                // Under IMPLICIT or AUTOMATIC TAGS, a CHOICE or open type is
+                // still tagged explicitly, as its own tag is needed to tell
+                // which alternative or type it holds (X.680 31.2.7). That
+                // goes for references to them too.
+                let tagged = match &ty.raw_type {
+                    RawType::Referenced(reference) if reference.is_internal() => {
+                        self.table.resolve(&reference.item).map(|(_, ty)| &ty.raw_type)
+                    }
+                    raw_type => Some(raw_type),
+                };
+
+                let tag_needed = matches!(
+                    tagged,
+                    Some(RawType::Builtin(BuiltinType::Choice(_))) | Some(RawType::Builtin(BuiltinType::OpenType))
+                );
+
                 let kind = match prefix.kind {
                     TagKind::Implicit => TagEnvironment::Implicit,
                     TagKind::Explicit => TagEnvironment::Explicit,
-                    TagKind::Environment => self.environment
+                    TagKind::Environment => match self.environment {
+                        TagEnvironment::Explicit => TagEnvironment::Explicit,
+                        _ if tag_needed => TagEnvironment::Explicit,
+                        _ => TagEnvironment::Implicit,
+                    },
                 };
 
                 self.prelude.insert(Import::new(
@@ -181,7 +313,8 @@ impl Backend for Rust {
 
     fn write_prelude<W: Write>(&mut self, writer: &mut W) -> Result<()> {
         let prelude = mem::replace(&mut self.prelude, HashSet::new());
-        writer.write(itertools::join(prelude.iter().map(ToString::to_string), "\n").as_bytes())?;
+        let prelude = prelude.iter().map(ToString::to_string).sorted();
+        writer.write_all(itertools::join(prelude, "\n").as_bytes())?;
 
         /*
         let consts = mem::replace(&mut self.consts, HashSet::new());
@@ -196,17 +329,15 @@ impl Backend for Rust {
         Ok(())
     }
 
-    fn write_footer<W: Write>(&self, writer: &mut W) -> Result<()> {
-        writer.write(
-            itertools::join(self.structs.iter().map(ToString::to_string), "\n").as_bytes(),
-        )?;
-
+    fn write_footer<W: Write>(&self, _: &mut W) -> Result<()> {
         Ok(())
     }
 
     fn generate_value(&mut self, value: &Value) -> Result<String> {
         match value {
-            _ => Ok(String::from("UNIMPLEMENTED")),
+            Value::Boolean(boolean) => Ok(boolean.to_string()),
+            Value::Integer(IntegerValue::Literal(integer)) => Ok(integer.to_string()),
+            _ => bail!("Can't generate the value {:?} yet.", value),
         }
     }
 
@@ -217,55 +348,532 @@ impl Backend for Rust {
     }
 }
 
+impl Rust {
+    /// Whether the type generated for `ty` implements `Default`, so a struct
+    /// holding it can have its other fields' `DEFAULT` values as its own
+    /// `Default` impl.
+    fn implements_default(&self, ty: &Type) -> bool {
+        let ty = match &ty.raw_type {
+            RawType::Referenced(reference) if reference.is_internal() => match self.table.resolve(&reference.item) {
+                Some((_, ty)) => ty,
+                None => return false,
+            },
+            _ => ty,
+        };
+
+        matches!(
+            ty.raw_type,
+            RawType::Builtin(BuiltinType::Boolean)
+                | RawType::Builtin(BuiltinType::OctetString)
+                | RawType::Builtin(BuiltinType::SequenceOf(_))
+                | RawType::Builtin(BuiltinType::Date)
+                | RawType::Builtin(BuiltinType::DateTime)
+                | RawType::Builtin(BuiltinType::Duration)
+                | RawType::Builtin(BuiltinType::Time)
+                | RawType::Builtin(BuiltinType::TimeOfDay)
+        )
+    }
+
+    /// The `DEFAULT` value of a component of type `ty`. An identifier is
+    /// generated as the variant of the ENUMERATED type it names, as the
+    /// value on its own doesn't say which type that is.
+    fn generate_default(&mut self, ty: &Type, value: &Value) -> DefaultValue {
+        let identifier = match value {
+            Value::Enumerated(identifier) | Value::Integer(IntegerValue::Identifier(identifier)) => Some(identifier),
+            Value::Defined(DefinedValue::Simple(reference)) if reference.is_internal() => Some(&reference.item),
+            _ => None,
+        };
+
+        let enumeration = match &ty.raw_type {
+            RawType::Referenced(reference) if reference.is_internal() => self.table.resolve(&reference.item),
+            _ => None,
+        };
+
+        if let (Some(identifier), Some((name, enumeration))) = (identifier, enumeration) {
+            if let RawType::Builtin(BuiltinType::Enumeration(root, _, additions)) = &enumeration.raw_type {
+                let mut items = root.iter().chain(additions.iter().flatten());
+
+                if items.any(|item| item.name() == identifier) {
+                    return DefaultValue::Generated(format!(
+                        "{}::{}",
+                        name.to_camel_case(),
+                        identifier.to_camel_case()
+                    ));
+                }
+            }
+        }
+
+        self.generate_value(value).map_or(DefaultValue::Unsupported, DefaultValue::Generated)
+    }
+}
+
 pub struct CodeGenerator<'a, W: Write, B: Backend> {
     backend: B,
-    semantic_tree: SemanticChecker,
+    semantic_trees: Vec<SemanticChecker>,
     writer: &'a mut W,
 }
 
 impl<'a, W: Write, B: Backend> CodeGenerator<'a, W, B> {
-    pub fn new(semantic_tree: SemanticChecker, writer: &'a mut W) -> Self {
+    /// Generates the code for each of `semantic_trees` into `writer`, in
+    /// order, after a single prelude for all of them.
+    pub fn new(semantic_trees: Vec<SemanticChecker>, writer: &'a mut W) -> Self {
         Self {
             backend: B::default(),
-            semantic_tree,
+            semantic_trees,
             writer,
         }
     }
 
+    /// Sets whether the generated types are named after their ASN.1
+    /// identifiers for serde. Default: `false`.
+    pub fn serde_names(mut self, enabled: bool) -> Self {
+        self.backend.serde_names(enabled);
+        self
+    }
+
     pub fn generate(mut self) -> Result<()> {
-        let table = self.semantic_tree.table;
+        // Definitions are buffered so that they're written after the prelude.
+        let mut definitions = Vec::new();
+
+        // Types imported from another of the modules are looked up there, so
+        // every module's types are known while generating each of them.
+        let semantic_trees = mem::take(&mut self.semantic_trees);
+        let types = semantic_trees
+            .iter()
+            .flat_map(|tree| tree.table.types.iter())
+            .map(|(name, ty)| (name.clone(), ty.clone()))
+            .collect::<Vec<_>>();
+
+        for semantic_tree in semantic_trees {
+            self.generate_module(semantic_tree, &types, &mut definitions)?;
+        }
+
+        self.backend.write_prelude(self.writer)?;
+        write!(self.writer, "\n\n")?;
+
+        for definition in definitions.into_iter().filter(|d| !d.is_empty()) {
+            writeln!(self.writer, "{}", definition)?;
+        }
+
+        self.backend.write_footer(self.writer)?;
+
+        Ok(())
+    }
+
+    /// Generates the definitions of `semantic_tree`'s types into
+    /// `definitions`, and adds what they use to the prelude. `types` are
+    /// those of every module, for resolving the module's imports.
+    fn generate_module(
+        &mut self,
+        semantic_tree: SemanticChecker,
+        types: &[(String, Type)],
+        definitions: &mut Vec<String>,
+    ) -> Result<()> {
+        let table = semantic_tree.table;
+        let mut known = table.clone();
+
+        for (name, ty) in types {
+            if !known.types.contains_key(name) {
+                known.insert_type(name.clone(), ty.clone());
+            }
+        }
+
+        self.backend.tag_environment(TagEnvironment::from(&semantic_tree.module.tag));
+        self.backend.symbol_table(&known);
 
-        for (name, (ty, value)) in table.values.clone().into_iter() {
-            self.backend.generate_value_assignment(name, ty, value)?;
+        for (name, (ty, value)) in table.values.iter() {
+            self.backend.generate_value_assignment(name.clone(), ty.clone(), value.clone())?;
         }
 
-        for (name, ty) in table.types.iter() {
+        // Types are generated after the types they reference, so that aliases
+        // and the output as a whole don't depend on how the module is laid out.
+        for name in table.types.dependency_order() {
+            let ty = &table.types[name];
+
             match &ty.raw_type {
                 RawType::Builtin(BuiltinType::Sequence(components)) => {
-                    self.backend.generate_sequence(&name, components)?;
+                    definitions.push(self.backend.generate_sequence(name, components)?);
                 }
                 RawType::Builtin(BuiltinType::SequenceOf(ty)) => {
-                    write!(
-                        self.writer,
-                        "{}\n",
-                        self.backend.generate_sequence_of(&name, &ty)?
-                    )?;
+                    definitions.push(self.backend.generate_sequence_of(name, ty)?);
+                }
+                RawType::Builtin(BuiltinType::Enumeration(root, _, additions)) => {
+                    definitions.push(self.backend.generate_enumerated(name, root, additions.as_deref())?);
                 }
                 RawType::Builtin(BuiltinType::Prefixed(prefix, ty)) => {
-                    write!(
-                        self.writer,
-                        "{}\n",
-                        self.backend.generate_sequence_of(&name, &ty)?
-                    )?;
+                    definitions.push(self.backend.generate_sequence_of(name, ty)?);
                 }
                 _ => {}
             }
         }
 
-        self.backend.write_prelude(self.writer)?;
-        write!(self.writer, "\n\n")?;
-        self.backend.write_footer(self.writer)?;
-
         Ok(())
     }
 }
+
+/// The value of each item of an ENUMERATED type, and whether it's an
+/// extension addition. Values are assigned to items without a number as
+/// described in X.680 20.3 and 20.6, and items numbered with a defined value
+/// take the INTEGER assigned to it in `table`, failing if there isn't one.
+pub(crate) fn enumeration_values<'a>(
+    root: &'a [Enumeration],
+    additions: Option<&'a [Enumeration]>,
+    table: &GlobalSymbolTable,
+) -> Result<Vec<(&'a Enumeration, i64, bool)>> {
+    let number = |item: &Enumeration| match item.number() {
+        Some(Number::Literal(number)) => Ok(Some(*number)),
+        Some(Number::DefinedValue(DefinedValue::Simple(reference))) if reference.is_internal() => {
+            match table.resolve_integer(&reference.item) {
+                Some(number) => Ok(Some(number)),
+                None => bail!("`{}` numbers `{}` but isn't an INTEGER value in the module.", reference, item.name()),
+            }
+        }
+        Some(number) => bail!("Can't resolve `{}`, which numbers `{}`.", number, item.name()),
+        None => Ok(None),
+    };
+
+    let root_numbers = root.iter().map(number).collect::<Result<Vec<_>>>()?;
+    let numbered = root_numbers.iter().flatten().copied().collect::<HashSet<_>>();
+    let mut root_values = numbered.clone();
+    let mut values = Vec::new();
+    let mut next = 0;
+
+    for (item, number) in root.iter().zip(root_numbers) {
+        let value = match number {
+            Some(number) => number,
+            None => {
+                while numbered.contains(&next) {
+                    next += 1;
+                }
+
+                next += 1;
+                next - 1
+            }
+        };
+
+        root_values.insert(value);
+        values.push((item, value, false));
+    }
+
+    let mut previous: Option<i64> = None;
+
+    for item in additions.into_iter().flatten() {
+        let value = match number(item)? {
+            Some(number) => number,
+            None => {
+                let mut value = previous.map_or(0, |previous| previous + 1);
+
+                while root_values.contains(&value) {
+                    value += 1;
+                }
+
+                value
+            }
+        };
+
+        previous = Some(value);
+        values.push((item, value, true));
+    }
+
+    Ok(values)
+}
+
+/// Whether `ty` is generated as an item of its own by `CodeGenerator`, which
+/// references to it use by name.
+fn is_generated(ty: &Type) -> bool {
+    matches!(
+        ty.raw_type,
+        RawType::Builtin(BuiltinType::Sequence(_))
+            | RawType::Builtin(BuiltinType::SequenceOf(_))
+            | RawType::Builtin(BuiltinType::Enumeration(..))
+            | RawType::Builtin(BuiltinType::Prefixed(..))
+    )
+}
+
+/// The sibling component an open type's table constraint (`{Set}{@id}`)
+/// refers to, as the name of its generated field.
+fn any_defined_by(ty: &Type) -> Option<String> {
+    if ty.raw_type != RawType::Builtin(BuiltinType::OpenType) {
+        return None;
+    }
+
+    ty.constraints.iter().flatten().find_map(|constraint| match constraint {
+        Constraint::General(GeneralConstraint::Table(_, components)) => match &components[..] {
+            [component] if component.len() == 1 => Some(component[0].to_snake_case()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn generate(source: &str) -> String {
+        generate_with(source, false)
+    }
+
+    fn generate_with(source: &str, serde_names: bool) -> String {
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        checker.build().unwrap();
+
+        let mut output = Vec::new();
+        CodeGenerator::<Vec<u8>, Rust>::new(vec![checker], &mut output)
+            .serde_names(serde_names)
+            .generate()
+            .unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn sequence_default_impl() {
+        let output = generate(
+            "Test DEFINITIONS ::= BEGIN
+                Message ::= SEQUENCE {
+                    version INTEGER DEFAULT 1,
+                    flag BOOLEAN
+                }
+            END",
+        );
+
+        assert!(output.contains("impl Default for Message {"));
+        assert!(output.contains("#[serde(default = \"Message::default_version\")]\nversion: Integer,"));
+        assert!(output.contains("fn default_version() -> Integer {\n        1.into()\n    }"));
+        assert!(output.contains("version: Self::default_version(),"));
+        assert!(output.contains("flag: Default::default(),"));
+    }
+
+    #[test]
+    fn enumerated_default() {
+        let output = generate(
+            "Test DEFINITIONS ::= BEGIN
+                Message ::= SEQUENCE {
+                    version Version DEFAULT v2,
+                    flag BOOLEAN
+                }
+                Unsupported ::= SEQUENCE {
+                    version Version DEFAULT v1,
+                    algorithm OBJECT IDENTIFIER DEFAULT { 1 2 3 }
+                }
+                Version ::= ENUMERATED { v1, v2 }
+            END",
+        );
+
+        assert!(output.contains("impl Default for Message {"));
+        assert!(output.contains("Version::V2.into()"));
+        assert!(output.contains("version: Self::default_version(),"));
+        assert!(!output.contains("impl Default for Unsupported {"));
+        assert!(!output.contains("UNIMPLEMENTED"));
+    }
+
+    #[test]
+    fn referenced_types() {
+        let output = generate(
+            "Test DEFINITIONS ::= BEGIN
+                Certificate ::= SEQUENCE {
+                    tbs Tbs-Certificate,
+                    signature Signature
+                }
+                Tbs-Certificate ::= SEQUENCE {
+                    serial INTEGER,
+                    issuer Name
+                }
+                Signature ::= Name
+                Name ::= OCTET STRING
+            END",
+        );
+
+        assert!(output.contains("struct TbsCertificate {"));
+        assert!(output.contains("tbs: TbsCertificate,"));
+        assert!(output.contains("signature: OctetString,"));
+        assert!(output.contains("issuer: OctetString,"));
+    }
+
+    #[test]
+    fn stable_output() {
+        let source = "Test DEFINITIONS ::= BEGIN
+            Certificates ::= SEQUENCE OF Certificate
+            Certificate ::= SEQUENCE {
+                tbs Tbs-Certificate,
+                algorithm Algorithm
+            }
+            Tbs-Certificate ::= SEQUENCE {
+                version Version DEFAULT v1,
+                serial INTEGER,
+                flag BOOLEAN
+            }
+            Algorithm ::= SEQUENCE { id OBJECT IDENTIFIER }
+            Version ::= ENUMERATED { v1, v2, v3 }
+        END";
+
+        let output = generate(source);
+
+        for _ in 0..8 {
+            assert_eq!(output, generate(source));
+        }
+
+        let position = |item: &str| output.find(item).unwrap();
+        assert!(position("enum Version") < position("pub type Certificates"));
+        assert!(position("struct Certificate {") < position("pub type Certificates"));
+        assert!(position("struct Algorithm") < position("struct TbsCertificate"));
+        assert!(position("struct TbsCertificate") < position("struct Certificate {"));
+    }
+
+    #[test]
+    fn with_components_presence() {
+        let output = generate(
+            "Test DEFINITIONS ::= BEGIN
+                Base ::= SEQUENCE {
+                    id INTEGER,
+                    extra BOOLEAN OPTIONAL,
+                    note BOOLEAN OPTIONAL
+                }
+                Strict ::= Base (WITH COMPONENTS { ..., extra PRESENT, note ABSENT })
+            END",
+        );
+
+        let strict = &output[output.find("struct Strict").unwrap()..];
+        let strict = &strict[..strict.find('}').unwrap()];
+
+        assert!(strict.contains("extra: bool,"));
+        assert!(!strict.contains("note"));
+        assert!(output.contains("extra: Option<bool>,"));
+    }
+
+    #[test]
+    fn extensible_enumerated() {
+        let output = generate(
+            "Test DEFINITIONS ::= BEGIN
+                Color ::= ENUMERATED { red, green, ..., blue }
+                Shape ::= ENUMERATED { circle(1), square }
+            END",
+        );
+
+        let color = &output[output.find("#[derive(Clone, Copy, Debug, Enumerable, PartialEq)]\n#[asn(extensible)]\nenum Color {").unwrap()..];
+        let color = &color[..color.find('}').unwrap()];
+
+        assert!(color.contains("Red = 0,\nGreen = 1,\n#[asn(extension)]\nBlue = 2,"));
+
+        let shape = &output[output.find("enum Shape").unwrap()..];
+        let shape = &shape[..shape.find('}').unwrap()];
+
+        assert!(shape.contains("Circle = 1,\nSquare = 0,"));
+        assert!(!output[..output.find("enum Shape").unwrap()].ends_with("#[asn(extensible)]\n"));
+    }
+
+    #[test]
+    fn enumerated_field() {
+        let output = generate(
+            "Test DEFINITIONS ::= BEGIN
+                Palette ::= SEQUENCE { favouriteColor Color }
+                Color ::= ENUMERATED { red(first), green, blue(third) }
+                first INTEGER ::= 1
+                second INTEGER ::= 2
+                third INTEGER ::= second
+            END",
+        );
+
+        assert!(output.contains("use asn1::types::Enumerated;"));
+        assert!(output.contains("favourite_color: Enumerated<Color>,"));
+        assert!(output.contains("Red = 1,\nGreen = 0,\nBlue = 2,"));
+
+        let source = "Test DEFINITIONS ::= BEGIN
+                Color ::= ENUMERATED { red(missing), green }
+            END";
+
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        checker.build().unwrap();
+
+        let mut output = Vec::new();
+        assert!(CodeGenerator::<Vec<u8>, Rust>::new(vec![checker], &mut output).generate().is_err());
+    }
+
+    #[test]
+    fn tag_default() {
+        let module = |default: &str| {
+            generate(&format!(
+                "Test DEFINITIONS {} TAGS ::= BEGIN
+                    Message ::= SEQUENCE {{
+                        id [0] INTEGER,
+                        flag [1] EXPLICIT BOOLEAN,
+                        note [APPLICATION 2] IMPLICIT BOOLEAN
+                    }}
+                END",
+                default
+            ))
+        };
+
+        let implicit = module("IMPLICIT");
+        assert!(implicit.contains("id: Implicit<Context, 0, Integer>,"));
+        assert!(implicit.contains("flag: Explicit<Context, 1, bool>,"));
+        assert!(implicit.contains("note: Implicit<Application, 2, bool>,"));
+
+        let explicit = module("EXPLICIT");
+        assert!(explicit.contains("id: Explicit<Context, 0, Integer>,"));
+        assert!(explicit.contains("note: Implicit<Application, 2, bool>,"));
+
+        assert!(module("AUTOMATIC").contains("id: Implicit<Context, 0, Integer>,"));
+
+        let choice = generate(
+            "Test DEFINITIONS IMPLICIT TAGS ::= BEGIN
+                Message ::= SEQUENCE {
+                    f [0] Foo,
+                    g [1] Bar
+                }
+                Foo ::= CHOICE { a INTEGER, b BOOLEAN }
+                Bar ::= Foo
+            END",
+        );
+
+        assert!(choice.contains("f: Explicit<Context, 0, "));
+        assert!(choice.contains("g: Explicit<Context, 1, "));
+    }
+
+    #[test]
+    fn serde_names() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                Message ::= SEQUENCE {
+                    messageId INTEGER,
+                    flag BOOLEAN
+                }
+                Color ::= ENUMERATED { darkRed, green }
+            END";
+
+        let output = generate_with(source, true);
+        assert!(output.contains("#[serde(rename = \"messageId\")]\nmessage_id: Integer,"));
+        assert!(output.contains("\nflag: bool,"));
+        assert!(!output.contains("#[serde(rename = \"flag\")]"));
+        assert!(output.contains(
+            "#[derive(Clone, Copy, Debug, Enumerable, PartialEq, Serialize, Deserialize)]\nenum Color {"
+        ));
+        assert!(output.contains("#[serde(rename = \"darkRed\")]\nDarkRed = 0,"));
+
+        let output = generate(source);
+        assert!(!output.contains("rename"));
+        assert!(output.contains("#[derive(Clone, Copy, Debug, Enumerable, PartialEq)]\nenum Color {"));
+    }
+
+    #[test]
+    fn open_type_field() {
+        let output = generate(
+            "Test DEFINITIONS ::= BEGIN
+                ALGORITHM ::= CLASS { &id OBJECT IDENTIFIER UNIQUE, &Type OPTIONAL }
+                SupportedAlgorithms ALGORITHM ::= { ... }
+                AlgorithmIdentifier ::= SEQUENCE {
+                    algorithm ALGORITHM.&id ({SupportedAlgorithms}),
+                    parameters ALGORITHM.&Type ({SupportedAlgorithms}{@algorithm}) OPTIONAL
+                }
+            END",
+        );
+
+        assert!(output.contains("use asn1::types::AnyValue;"));
+        assert!(output.contains("algorithm: ObjectIdentifier,"));
+        assert!(output.contains(
+            "#[asn(any_defined_by = \"algorithm\")]\nparameters: Option<AnyValue>,"
+        ));
+        assert!(output.contains("#[derive(AsnType, Serialize, Deserialize)]\nstruct AlgorithmIdentifier {"));
+    }
+}
@@ -0,0 +1,166 @@
+use std::io::Write;
+
+use failure::Fallible as Result;
+
+use super::{Backend, TagEnvironment};
+use crate::{parser::*, registry::GlobalSymbolTable};
+
+/// A backend that emits a Graphviz graph of the module's types, with a node
+/// for each SEQUENCE listing its components, and an edge for each reference
+/// to another type.
+#[derive(Default)]
+pub struct Dot {
+    nodes: Vec<String>,
+    edges: Vec<String>,
+}
+
+impl Dot {
+    fn add_edge(&mut self, from: &str, ty: &Type, label: Option<&str>) {
+        if let RawType::Referenced(ref reference) = ty.raw_type {
+            let label = label.map(|l| format!(" [label=\"{}\"]", l)).unwrap_or_default();
+            self.edges.push(format!("    \"{}\" -> \"{}\"{};", from, reference, label));
+        }
+    }
+}
+
+/// Escapes the characters with special meaning inside a record label.
+fn escape(label: &str) -> String {
+    label.chars().fold(String::new(), |mut escaped, c| {
+        if "{}|<>\"".contains(c) {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+        escaped
+    })
+}
+
+impl Backend for Dot {
+    fn tag_environment(&mut self, _: TagEnvironment) {}
+
+    fn serde_names(&mut self, _: bool) {}
+
+    fn symbol_table(&mut self, _: &GlobalSymbolTable) {}
+
+    fn generate_sequence(&mut self, name: &str, components: &ComponentTypeList) -> Result<String> {
+        let mut fields = Vec::new();
+
+        for field in components.components.iter().flatten() {
+            let (ty, optional, default) = match field.as_type() {
+                Some(component) => component,
+                None => continue,
+            };
+
+            let mut label = format!("{}: {}", ty.name.as_ref().unwrap(), self.generate_type(ty)?);
+
+            if *optional {
+                label.push_str(" OPTIONAL");
+            } else if let Some(default) = default {
+                label.push_str(&format!(" DEFAULT {}", self.generate_value(default)?));
+            }
+
+            fields.push(escape(&label));
+            self.add_edge(name, ty, None);
+        }
+
+        self.nodes.push(format!(
+            "    \"{}\" [shape=record, label=\"{{{}|{}}}\"];",
+            name,
+            escape(name),
+            fields.join("\\l|")
+        ));
+
+        Ok(String::new())
+    }
+
+    fn generate_sequence_of(&mut self, name: &str, ty: &Type) -> Result<String> {
+        self.add_edge(name, ty, Some("SEQUENCE OF"));
+        Ok(String::new())
+    }
+
+    fn generate_enumerated(
+        &mut self,
+        name: &str,
+        root: &[Enumeration],
+        additions: Option<&[Enumeration]>,
+    ) -> Result<String> {
+        let mut items = root.iter().map(|item| escape(item.name())).collect::<Vec<_>>();
+
+        if let Some(additions) = additions {
+            items.push(String::from("..."));
+            items.extend(additions.iter().map(|item| escape(item.name())));
+        }
+
+        self.nodes.push(format!(
+            "    \"{}\" [shape=record, label=\"{{{}|{}}}\"];",
+            name,
+            escape(name),
+            items.join("\\l|")
+        ));
+
+        Ok(String::new())
+    }
+
+    fn generate_type(&mut self, ty: &Type) -> Result<String> {
+        match ty.raw_type {
+            RawType::Builtin(ref builtin) => self.generate_builtin(builtin),
+            RawType::Referenced(ref reference) => Ok(reference.to_string()),
+            ref raw => Ok(format!("{:?}", raw)),
+        }
+    }
+
+    fn generate_builtin(&mut self, builtin: &BuiltinType) -> Result<String> {
+        let output = match builtin {
+            BuiltinType::Boolean => String::from("BOOLEAN"),
+            BuiltinType::BitString(_) => String::from("BIT STRING"),
+            BuiltinType::Choice(_) => String::from("CHOICE"),
+            BuiltinType::Date => String::from("DATE"),
+            BuiltinType::DateTime => String::from("DATE-TIME"),
+            BuiltinType::Duration => String::from("DURATION"),
+            BuiltinType::Enumeration(..) => String::from("ENUMERATED"),
+            BuiltinType::Integer(_) => String::from("INTEGER"),
+            BuiltinType::Null => String::from("NULL"),
+            BuiltinType::ObjectIdentifier => String::from("OBJECT IDENTIFIER"),
+            BuiltinType::OctetString => String::from("OCTET STRING"),
+            BuiltinType::OpenType => String::from("ANY"),
+            BuiltinType::Prefixed(prefix, ty) => {
+                format!("[{}] {}", prefix.number, self.generate_type(ty)?)
+            }
+            BuiltinType::Sequence(_) => String::from("SEQUENCE"),
+            BuiltinType::SequenceOf(ty) => format!("SEQUENCE OF {}", self.generate_type(ty)?),
+            BuiltinType::Set(_) => String::from("SET"),
+            BuiltinType::SetOf(ty) => format!("SET OF {}", self.generate_type(ty)?),
+            BuiltinType::Time => String::from("TIME"),
+            BuiltinType::TimeOfDay => String::from("TIME-OF-DAY"),
+            builtin => format!("{:?}", builtin),
+        };
+
+        Ok(output)
+    }
+
+    fn write_prelude<W: Write>(&mut self, writer: &mut W) -> Result<()> {
+        writer.write_all(b"digraph asn1 {")?;
+        Ok(())
+    }
+
+    fn write_footer<W: Write>(&self, writer: &mut W) -> Result<()> {
+        for line in self.nodes.iter().chain(&self.edges) {
+            writeln!(writer, "{}", line)?;
+        }
+
+        writeln!(writer, "}}")?;
+        Ok(())
+    }
+
+    fn generate_value(&mut self, value: &Value) -> Result<String> {
+        match value {
+            Value::Boolean(boolean) => Ok(boolean.to_string().to_uppercase()),
+            Value::Integer(IntegerValue::Literal(integer)) => Ok(integer.to_string()),
+            value => Ok(format!("{:?}", value)),
+        }
+    }
+
+    fn generate_value_assignment(&mut self, _: String, _: Type, _: Value) -> Result<()> {
+        Ok(())
+    }
+}
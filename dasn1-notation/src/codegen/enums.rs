@@ -0,0 +1,103 @@
+use std::fmt;
+
+use super::structs::{Attribute, Derive, Serde};
+
+/// An `ENUMERATED` type, which is generated as an `enum` without data.
+pub struct Enum {
+    name: String,
+    variants: Vec<Variant>,
+    attributes: Vec<Attribute>,
+}
+
+impl Enum {
+    pub fn new<I: Into<String>>(name: I) -> Self {
+        Self {
+            name: name.into(),
+            variants: Vec::new(),
+            attributes: vec![Attribute::Derive(vec![
+                Derive::Clone,
+                Derive::Copy,
+                Derive::Debug,
+                Derive::Enumerable,
+                Derive::PartialEq,
+            ])],
+        }
+    }
+
+    /// Derives serde's traits as well, for formats other than the ASN.1
+    /// encoding rules.
+    pub fn derive_serde(&mut self) {
+        for attribute in &mut self.attributes {
+            if let Attribute::Derive(derives) = attribute {
+                derives.extend(vec![Derive::Serialize, Derive::Deserialize]);
+            }
+        }
+    }
+
+    /// Marks the enum as having an extension marker.
+    pub fn extensible(&mut self) {
+        self.attributes.push(Attribute::Asn(String::from("extensible")));
+    }
+
+    pub fn add_variant(&mut self, variant: Variant) {
+        self.variants.push(variant);
+    }
+}
+
+impl fmt::Display for Enum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.attributes.is_empty() {
+            itertools::join(self.attributes.iter().map(ToString::to_string), "\n").fmt(f)?;
+            writeln!(f)?;
+        }
+
+        writeln!(f, "enum {} {{", self.name)?;
+
+        if !self.variants.is_empty() {
+            itertools::join(self.variants.iter().map(ToString::to_string), "\n").fmt(f)?;
+            writeln!(f)?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+pub struct Variant {
+    name: String,
+    /// The value of the variant.
+    value: i64,
+    /// Whether the variant is after the extension marker.
+    extension: bool,
+    /// The name of the variant in serde's data model.
+    rename: Option<String>,
+}
+
+impl Variant {
+    pub fn new<I: Into<String>>(name: I, value: i64, extension: bool) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            extension,
+            rename: None,
+        }
+    }
+
+    pub fn rename<I: Into<String>>(mut self, name: I) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.extension {
+            writeln!(f, "{}", Attribute::Asn(String::from("extension")))?;
+        }
+
+        if let Some(ref name) = self.rename {
+            writeln!(f, "{}", Attribute::Serde(Serde::Rename(name.clone())))?;
+        }
+
+        write!(f, "{} = {},", self.name, self.value)
+    }
+}
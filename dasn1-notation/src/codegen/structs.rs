@@ -18,11 +18,37 @@ impl Struct {
         }
     }
 
-    pub fn add_field(&mut self, field: Field) {
+    /// Adds `field`, which serde fills in with its `DEFAULT` value when it's
+    /// left out.
+    pub fn add_field(&mut self, mut field: Field) {
+        if let Some(DefaultValue::Generated(_)) = field.default_value {
+            let function = format!("{}::{}", self.name, field.default_function());
+            field.attributes.push(Attribute::Serde(Serde::Default(function)));
+        }
+
+        // `#[asn]` is only registered as an attribute by the derive.
+        if field.attributes.iter().any(|attribute| matches!(attribute, Attribute::Asn(_))) {
+            self.derive(Derive::AsnType);
+        }
+
         self.fields.push(field);
     }
 }
 
+impl Struct {
+    fn derive(&mut self, derive: Derive) {
+        for attribute in &mut self.attributes {
+            if let Attribute::Derive(derives) = attribute {
+                if !derives.contains(&derive) {
+                    derives.insert(0, derive);
+                }
+
+                return;
+            }
+        }
+    }
+}
+
 impl fmt::Display for Struct {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if !self.attributes.is_empty() {
@@ -37,18 +63,106 @@ impl fmt::Display for Struct {
             writeln!(f)?;
         }
 
+        writeln!(f, "}}")?;
+
+        let mut defaults = self.fields.iter().filter_map(|field| field.default_value.as_ref()).peekable();
+
+        if defaults.peek().is_none() {
+            return Ok(());
+        }
+
+        self.fmt_default_functions(f)?;
+
+        // A `DEFAULT` value that can't be generated yet would make the impl
+        // wrong, so none is written, nor is one for fields without a
+        // `DEFAULT` whose types have no default of their own.
+        let defaultable = self.fields.iter().all(|field| field.default_value.is_some() || field.optional || field.implements_default);
+
+        if defaultable && defaults.all(|default| matches!(default, DefaultValue::Generated(_))) {
+            self.fmt_default_impl(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Struct {
+    /// Writes a function returning each field's `DEFAULT` value, for serde's
+    /// `default` attribute, which only takes the path of a function.
+    fn fmt_default_functions(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln!(f, "impl {} {{", self.name)?;
+
+        let functions = self.fields.iter().filter_map(|field| match &field.default_value {
+            Some(DefaultValue::Generated(value)) => Some(format!(
+                "    fn {}() -> {} {{\n        {}.into()\n    }}\n",
+                field.default_function(),
+                field.ty,
+                value
+            )),
+            _ => None,
+        });
+
+        f.write_str(&itertools::join(functions, "\n"))?;
+        writeln!(f, "}}")
+    }
+
+    /// Writes an `impl Default` using each field's `DEFAULT` value, and the
+    /// type's own default for fields without one.
+    fn fmt_default_impl(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln!(f, "impl Default for {} {{", self.name)?;
+        writeln!(f, "    fn default() -> Self {{")?;
+        writeln!(f, "        Self {{")?;
+
+        for field in &self.fields {
+            let value = match &field.default_value {
+                Some(DefaultValue::Generated(_)) => Some(format!("Self::{}()", field.default_function())),
+                _ => None,
+            };
+
+            let value = match value {
+                Some(value) => value,
+                None if field.optional => String::from("None"),
+                None => String::from("Default::default()"),
+            };
+
+            writeln!(f, "            {}: {},", field.name, value)?;
+        }
+
+        writeln!(f, "        }}")?;
+        writeln!(f, "    }}")?;
         writeln!(f, "}}")
     }
 }
 
+/// A field's `DEFAULT` value.
+#[derive(Clone)]
+pub enum DefaultValue {
+    /// The value as a Rust expression.
+    Generated(String),
+    /// A value that can't be generated yet.
+    Unsupported,
+}
+
 pub struct Field {
     attributes: Vec<Attribute>,
     name: String,
     optional: bool,
+    default_value: Option<DefaultValue>,
+    /// Whether the field's type implements `Default`.
+    implements_default: bool,
     // TODO: Replace with stricter type.
     ty: String,
 }
 
+impl Field {
+    /// The name of the function returning the field's `DEFAULT` value.
+    fn default_function(&self) -> String {
+        format!("default_{}", self.name)
+    }
+}
+
 impl fmt::Display for Field {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if !self.attributes.is_empty() {
@@ -71,7 +185,10 @@ pub struct FieldBuilder {
     name: String,
     ty: String,
     optional: bool,
-    default_value: Option<String>,
+    default_value: Option<DefaultValue>,
+    any_defined_by: Option<String>,
+    rename: Option<String>,
+    implements_default: bool,
 }
 
 impl FieldBuilder {
@@ -91,28 +208,54 @@ impl FieldBuilder {
         self
     }
 
-    pub fn default_value(mut self, default_value: Option<String>) -> Self {
+    pub fn default_value(mut self, default_value: Option<DefaultValue>) -> Self {
         self.default_value = default_value;
         self
     }
 
+    /// The field whose value selects the type of this open type field.
+    pub fn any_defined_by(mut self, any_defined_by: Option<String>) -> Self {
+        self.any_defined_by = any_defined_by;
+        self
+    }
+
+    /// Whether the field's type implements `Default`.
+    pub fn implements_default(mut self, implements_default: bool) -> Self {
+        self.implements_default = implements_default;
+        self
+    }
+
+    /// The name of the field in serde's data model, if it differs from the
+    /// field's own name.
+    pub fn rename(mut self, rename: Option<String>) -> Self {
+        self.rename = rename;
+        self
+    }
+
     pub fn build(self) -> Field {
         let mut attributes = Vec::new();
 
-        if let Some(default_value) = self.default_value {
-            attributes.push(Attribute::Serde(Serde::Default(default_value)));
+        if let Some(rename) = self.rename {
+            attributes.push(Attribute::Serde(Serde::Rename(rename)));
+        }
+
+        if let Some(any_defined_by) = self.any_defined_by {
+            attributes.push(Attribute::Asn(format!("any_defined_by = {:?}", any_defined_by)));
         }
 
         Field {
             attributes,
             name: self.name,
             optional: self.optional,
+            default_value: self.default_value,
+            implements_default: self.implements_default,
             ty: self.ty,
         }
     }
 }
 
 pub enum Attribute {
+    Asn(String),
     Serde(Serde),
     Derive(Vec<Derive>),
 }
@@ -120,8 +263,10 @@ pub enum Attribute {
 impl fmt::Display for Attribute {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let attribute = match self {
+            Attribute::Asn(asn) => format!("#[asn({})]", asn),
             Attribute::Serde(serde) => match serde {
-                Serde::Default(default) => format!("#[serde(default = {})]", default),
+                Serde::Default(function) => format!("#[serde(default = {:?})]", function),
+                Serde::Rename(name) => format!("#[serde(rename = {:?})]", name),
             },
             Attribute::Derive(defaults) => format!(
                 "#[derive({})]",
@@ -134,10 +279,19 @@ impl fmt::Display for Attribute {
 }
 
 pub enum Serde {
+    /// The path of the function returning the field's default value.
     Default(String),
+    Rename(String),
 }
 
+#[derive(PartialEq)]
 pub enum Derive {
+    AsnType,
+    Clone,
+    Copy,
+    Debug,
+    Enumerable,
+    PartialEq,
     Serialize,
     Deserialize,
 }
@@ -145,6 +299,12 @@ pub enum Derive {
 impl fmt::Display for Derive {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let derive = match self {
+            Derive::AsnType => "AsnType",
+            Derive::Clone => "Clone",
+            Derive::Copy => "Copy",
+            Derive::Debug => "Debug",
+            Derive::Enumerable => "Enumerable",
+            Derive::PartialEq => "PartialEq",
             Derive::Serialize => "Serialize",
             Derive::Deserialize => "Deserialize",
         };
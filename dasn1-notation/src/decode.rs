@@ -0,0 +1,688 @@
+//! Decoding DER against the types of a module, printing the value in ASN.1
+//! value notation with the names of its components.
+
+use std::fmt::Write;
+
+use core::{
+    identifier::Class as TagClass,
+    length::encode_length,
+    types::{Integer, ObjectIdentifier},
+};
+use failure::{bail, ensure, format_err};
+
+use crate::{
+    codegen::{enumeration_values, TagEnvironment},
+    parser::*,
+    registry::GlobalSymbolTable,
+    Result,
+};
+
+const INDENT: &str = "    ";
+
+/// How deeply types may be followed into a value before decoding gives up,
+/// so that a deeply nested value can't exhaust the stack.
+const MAX_DEPTH: usize = 128;
+
+/// A value's encoding split into its parts, by the same parser the DER
+/// decoder uses.
+struct Element<'a> {
+    class: TagClass,
+    constructed: bool,
+    tag: u32,
+    contents: &'a [u8],
+    /// The complete encoding, including the identifier and length octets.
+    encoding: &'a [u8],
+}
+
+impl<'a> Element<'a> {
+    /// Splits the first value off `input`, returning it along with the rest
+    /// of the input.
+    fn parse(input: &'a [u8]) -> Result<(Self, &'a [u8])> {
+        let (tlv, rest) = der::split_tlv(input)?;
+        ensure!(!tlv.has_indefinite_length(), "Indefinite lengths aren't allowed in DER.");
+
+        let element = Element {
+            class: tlv.identifier().class,
+            constructed: tlv.is_constructed(),
+            tag: tlv.identifier().tag,
+            contents: tlv.contents(),
+            encoding: tlv.as_bytes(),
+        };
+
+        Ok((element, rest))
+    }
+
+    /// The values in the contents of a constructed value.
+    fn children(&self) -> Result<Vec<Element<'a>>> {
+        ensure!(self.constructed, "Expected a constructed value, found {}.", self);
+
+        let mut children = Vec::new();
+        let mut input = self.contents;
+
+        while !input.is_empty() {
+            let (child, rest) = Element::parse(input)?;
+            children.push(child);
+            input = rest;
+        }
+
+        Ok(children)
+    }
+
+    /// The contents of a primitive value.
+    fn primitive(&self) -> Result<&'a [u8]> {
+        ensure!(!self.constructed, "Expected a primitive value, found {}.", self);
+
+        Ok(self.contents)
+    }
+
+    /// The contents encoded as a primitive value with the universal `tag`,
+    /// which may differ from the value's own tag if it's implicitly tagged,
+    /// for the DER decoder to decode.
+    fn retagged(&self, tag: u8) -> Result<Vec<u8>> {
+        let contents = self.primitive()?;
+
+        let mut encoding = vec![tag];
+        encode_length(contents.len(), &mut encoding)?;
+        encoding.extend_from_slice(contents);
+
+        Ok(encoding)
+    }
+}
+
+impl std::fmt::Display for Element<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a value tagged [{:?} {}]", self.class, self.tag)
+    }
+}
+
+/// Decodes values against the types assigned in a module.
+pub(crate) struct Decoder<'a> {
+    table: &'a GlobalSymbolTable,
+    environment: TagEnvironment,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(table: &'a GlobalSymbolTable, environment: TagEnvironment) -> Self {
+        Self { table, environment }
+    }
+
+    /// Decodes `der` as a value of the type assigned to `name`, and prints it
+    /// as a value assignment.
+    pub fn decode(&self, name: &str, der: &[u8]) -> Result<String> {
+        let ty = self
+            .table
+            .types
+            .get(name)
+            .ok_or_else(|| format_err!("{} isn't defined in the module.", name))?;
+
+        let (element, rest) = Element::parse(der)?;
+        ensure!(rest.is_empty(), "Found {} more octets after the value.", rest.len());
+        self.check_tag(ty, &element)?;
+
+        let mut output = format!("value {} ::= ", name);
+        self.write_value(&mut output, ty, &element, 0, 0)?;
+
+        Ok(output)
+    }
+
+    /// Follows `ty` to the built-in type it refers to.
+    fn builtin<'b>(&'b self, ty: &'b Type) -> Result<&'b BuiltinType> {
+        let ty = match &ty.raw_type {
+            RawType::Referenced(reference) if reference.is_internal() => self
+                .table
+                .resolve(&reference.item)
+                .map(|(_, ty)| ty)
+                .ok_or_else(|| format_err!("{} isn't defined in the module.", reference.item))?,
+            _ => ty,
+        };
+
+        match &ty.raw_type {
+            RawType::Builtin(builtin) => Ok(builtin),
+            RawType::Referenced(reference) => {
+                bail!("{} is imported, so it can't be decoded.", reference)
+            }
+            _ => bail!("Parameterized types and types from objects can't be decoded."),
+        }
+    }
+
+    /// Whether `element` has the tag of a value of `ty`.
+    fn matches(&self, ty: &Type, element: &Element) -> Result<bool> {
+        Ok(match self.builtin(ty)? {
+            BuiltinType::Choice(choice) => {
+                for alternative in &self.alternatives(choice) {
+                    if self.matches(alternative, element)? {
+                        return Ok(true);
+                    }
+                }
+
+                false
+            }
+            BuiltinType::OpenType | BuiltinType::ObjectClassField(..) => true,
+            BuiltinType::Prefixed(prefix, _) => {
+                element.class == tag_class(prefix) && element.tag == tag_number(prefix)?
+            }
+            builtin => {
+                element.class == TagClass::Universal
+                    && universal_tag(builtin).map(u32::from) == Some(element.tag)
+            }
+        })
+    }
+
+    fn check_tag(&self, ty: &Type, element: &Element) -> Result<()> {
+        if self.matches(ty, element)? {
+            return Ok(());
+        }
+
+        match ty.name {
+            Some(ref name) => bail!("Expected {}, found {}.", name, element),
+            None => bail!("Unexpected {}.", element),
+        }
+    }
+
+    /// Whether a tag is explicit, which is decided the same way as in the
+    /// generated code.
+    fn is_explicit(&self, prefix: &Prefix, ty: &Type) -> Result<bool> {
+        Ok(match prefix.kind {
+            TagKind::Implicit => false,
+            TagKind::Explicit => true,
+            TagKind::Environment => {
+                matches!(self.environment, TagEnvironment::Explicit)
+                    || matches!(self.builtin(ty)?, BuiltinType::Choice(_) | BuiltinType::OpenType)
+            }
+        })
+    }
+
+    fn write_value(
+        &self,
+        output: &mut String,
+        ty: &Type,
+        element: &Element,
+        indent: usize,
+        depth: usize,
+    ) -> Result<()> {
+        ensure!(depth < MAX_DEPTH, "The value is nested more than {} deep.", MAX_DEPTH);
+
+        match self.builtin(ty)? {
+            BuiltinType::Boolean => {
+                let boolean: bool = der::from_slice(&element.retagged(0x1)?)?;
+                output.push_str(if boolean { "TRUE" } else { "FALSE" });
+            }
+            BuiltinType::Integer(named) => {
+                let integer: Integer = der::from_slice(&element.retagged(0x2)?)?;
+                let name = named.iter().find(|(_, number)| match number {
+                    Number::Literal(number) => Integer::from(*number) == integer,
+                    Number::DefinedValue(_) => false,
+                });
+
+                match name {
+                    Some((name, _)) => output.push_str(name),
+                    None => write!(output, "{}", integer.into_inner())?,
+                }
+            }
+            BuiltinType::Enumeration(root, _, additions) => {
+                let value: i64 = der::from_slice(&element.retagged(0x2)?)?;
+                let item = enumeration_values(root, additions.as_deref(), self.table)?
+                    .into_iter()
+                    .find(|(_, number, _)| *number == value);
+
+                match item {
+                    Some((item, _, _)) => output.push_str(item.name()),
+                    None => write!(output, "{}", value)?,
+                }
+            }
+            BuiltinType::Null => {
+                ensure!(element.contents.is_empty(), "A NULL has no contents.");
+                output.push_str("NULL");
+            }
+            BuiltinType::OctetString => write!(output, "'{}'H", hex(element.primitive()?))?,
+            // Decoded here rather than as a `BitString`, whose serde
+            // representation doesn't keep the number of unused bits.
+            BuiltinType::BitString(_) => {
+                let (&unused_bits, octets) = element
+                    .primitive()?
+                    .split_first()
+                    .ok_or_else(|| format_err!("A BIT STRING has at least one octet."))?;
+
+                ensure!(
+                    unused_bits < 8 && (unused_bits == 0 || !octets.is_empty()),
+                    "Invalid number of unused bits: {}.",
+                    unused_bits
+                );
+
+                let length = octets.len() * 8 - usize::from(unused_bits);
+                let bits: String = (0..length)
+                    .map(|i| if octets[i / 8] & 0x80 >> (i % 8) != 0 { '1' } else { '0' })
+                    .collect();
+
+                write!(output, "'{}'B", bits)?;
+            }
+            BuiltinType::ObjectIdentifier => {
+                let oid: ObjectIdentifier = der::from_slice(&element.retagged(0x6)?)?;
+                write!(output, "{{ {} }}", itertools::join(oid.iter(), " "))?;
+            }
+            BuiltinType::CharacterString(kind) => {
+                let contents = element.primitive()?;
+
+                let string = match kind {
+                    CharacterStringType::Bmp => {
+                        let units = contents
+                            .chunks(2)
+                            .map(|unit| u16::from_be_bytes([unit[0], *unit.get(1).unwrap_or(&0)]));
+
+                        std::char::decode_utf16(units)
+                            .map(|c| c.unwrap_or(std::char::REPLACEMENT_CHARACTER))
+                            .collect()
+                    }
+                    _ => String::from_utf8_lossy(contents).into_owned(),
+                };
+
+                write!(output, "\"{}\"", string.replace('"', "\"\""))?;
+            }
+            BuiltinType::Date
+            | BuiltinType::DateTime
+            | BuiltinType::Duration
+            | BuiltinType::Time
+            | BuiltinType::TimeOfDay => {
+                write!(output, "\"{}\"", String::from_utf8_lossy(element.primitive()?))?;
+            }
+            BuiltinType::Sequence(list) => {
+                let components = self.components(list)?;
+                let children = element.children()?;
+                let values = self.sequence_values(&components, list.extension.is_some(), &children)?;
+                self.write_list(output, &values, indent, depth + 1)?;
+            }
+            BuiltinType::Set(Set::Concrete(list)) => {
+                let components = self.components(list)?;
+                let children = element.children()?;
+                let values = self.set_values(&components, list.extension.is_some(), &children)?;
+                self.write_list(output, &values, indent, depth + 1)?;
+            }
+            BuiltinType::Set(Set::Extensible(..)) => {
+                let children = element.children()?;
+                self.write_list(output, &self.set_values(&[], true, &children)?, indent, depth + 1)?;
+            }
+            BuiltinType::SequenceOf(ty) | BuiltinType::SetOf(ty) => {
+                let children = element.children()?;
+                let mut values = Vec::with_capacity(children.len());
+
+                for child in &children {
+                    self.check_tag(ty, child)?;
+                    values.push((None, &**ty, child));
+                }
+
+                self.write_list(output, &values, indent, depth + 1)?;
+            }
+            BuiltinType::Choice(choice) => {
+                let alternatives = self.alternatives(choice);
+                let mut chosen = None;
+
+                for alternative in &alternatives {
+                    if self.matches(alternative, element)? {
+                        chosen = Some(alternative);
+                        break;
+                    }
+                }
+
+                let alternative = chosen.ok_or_else(|| format_err!("No alternative for {}.", element))?;
+                write!(output, "{} : ", alternative.name.as_deref().unwrap_or_default())?;
+                self.write_value(output, alternative, element, indent, depth + 1)?;
+            }
+            BuiltinType::Prefixed(prefix, ty) => {
+                if self.is_explicit(prefix, ty)? {
+                    let children = element.children()?;
+
+                    match &children[..] {
+                        [inner] => {
+                            self.check_tag(ty, inner)?;
+                            self.write_value(output, ty, inner, indent, depth + 1)?;
+                        }
+                        _ => bail!("An explicit tag holds exactly one value."),
+                    }
+                } else {
+                    self.write_value(output, ty, element, indent, depth + 1)?;
+                }
+            }
+            BuiltinType::OpenType | BuiltinType::ObjectClassField(..) => {
+                write!(output, "'{}'H", hex(element.encoding))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether components or alternatives are tagged automatically, which
+    /// they are under AUTOMATIC TAGS unless one of them is tagged already
+    /// (X.680 25.3, 29.3).
+    fn is_automatic<'b>(&self, mut types: impl Iterator<Item = &'b Type>) -> bool {
+        matches!(self.environment, TagEnvironment::Automatic)
+            && !types.any(|ty| matches!(ty.raw_type, RawType::Builtin(BuiltinType::Prefixed(..))))
+    }
+
+    /// The alternatives of a CHOICE, tagged automatically if they're to be.
+    fn alternatives(&self, choice: &ChoiceType) -> Vec<Type> {
+        let mut alternatives = choice.alternatives.clone();
+
+        if self.is_automatic(choice.alternatives.iter()) {
+            automatically_tag(alternatives.iter_mut());
+        }
+
+        alternatives
+    }
+
+    /// The components of a SEQUENCE or SET, including those of any
+    /// `COMPONENTS OF`, and whether each is OPTIONAL. Components with a
+    /// DEFAULT and extension additions are optional too. The components are
+    /// tagged automatically if they're to be, counting the ones from
+    /// `COMPONENTS OF` but not deciding by them (X.680 25.5).
+    fn components(&self, list: &ComponentTypeList) -> Result<Vec<(Type, bool)>> {
+        let mut components = self.root_components(list)?;
+
+        for addition in list.extension.iter().flat_map(|extension| &extension.additions) {
+            let added = match addition {
+                ExtensionAddition::Component(component) => std::slice::from_ref(component),
+                ExtensionAddition::Group(_, components) => &components[..],
+            };
+
+            for component in added {
+                if let Some((ty, _, _)) = component.as_type() {
+                    components.push((ty.clone(), true));
+                }
+            }
+        }
+
+        let written = list.components.iter().flatten().filter_map(|component| match component {
+            ComponentType::Type { ty, .. } => Some(ty),
+            ComponentType::ComponentsOf(_) => None,
+        });
+
+        if self.is_automatic(written) {
+            automatically_tag(components.iter_mut().map(|(ty, _)| ty));
+        }
+
+        Ok(components)
+    }
+
+    fn root_components(&self, list: &ComponentTypeList) -> Result<Vec<(Type, bool)>> {
+        let mut components = Vec::new();
+
+        for component in list.components.iter().flatten() {
+            match component {
+                ComponentType::Type { ty, optional, default } => {
+                    components.push((ty.clone(), *optional || default.is_some()))
+                }
+                // Only the root components are included (X.680 25.5).
+                ComponentType::ComponentsOf(ty) => match self.builtin(ty)? {
+                    BuiltinType::Sequence(list) | BuiltinType::Set(Set::Concrete(list)) => {
+                        components.extend(self.root_components(list)?)
+                    }
+                    _ => bail!("COMPONENTS OF has to refer to a SEQUENCE or SET."),
+                },
+            }
+        }
+
+        Ok(components)
+    }
+
+    /// Pairs each value in a SEQUENCE with its component, in order. Values
+    /// of unknown extension additions are left out.
+    fn sequence_values<'b, 'c>(
+        &self,
+        components: &'b [(Type, bool)],
+        extensible: bool,
+        children: &'c [Element<'c>],
+    ) -> Result<Vec<(Option<&'b str>, &'b Type, &'c Element<'c>)>> {
+        let mut children = children.iter().peekable();
+        let mut values = Vec::new();
+
+        for (ty, optional) in components {
+            match children.peek() {
+                Some(child) if self.matches(ty, child)? => {
+                    values.push((ty.name.as_deref(), ty, children.next().unwrap()));
+                }
+                _ if *optional => {}
+                Some(child) => self.check_tag(ty, child)?,
+                None => bail!("{} is missing.", ty.name.as_deref().unwrap_or_default()),
+            }
+        }
+
+        if let Some(child) = children.next() {
+            ensure!(extensible, "Unexpected {}.", child);
+        }
+
+        Ok(values)
+    }
+
+    /// Pairs each value in a SET with the component of the same tag, in the
+    /// order the components are defined.
+    fn set_values<'b, 'c>(
+        &self,
+        components: &'b [(Type, bool)],
+        extensible: bool,
+        children: &'c [Element<'c>],
+    ) -> Result<Vec<(Option<&'b str>, &'b Type, &'c Element<'c>)>> {
+        let mut children: Vec<_> = children.iter().map(Some).collect();
+        let mut values = Vec::new();
+
+        for (ty, optional) in components {
+            let mut found = None;
+
+            for child in &mut children {
+                if child.map_or(Ok(false), |child| self.matches(ty, child))? {
+                    found = child.take();
+                    break;
+                }
+            }
+
+            match found {
+                Some(child) => values.push((ty.name.as_deref(), ty, child)),
+                None if *optional => {}
+                None => bail!("{} is missing.", ty.name.as_deref().unwrap_or_default()),
+            }
+        }
+
+        if let Some(child) = children.into_iter().flatten().next() {
+            ensure!(extensible, "Unexpected {}.", child);
+        }
+
+        Ok(values)
+    }
+
+    /// Writes `values` between braces, a line each.
+    fn write_list(
+        &self,
+        output: &mut String,
+        values: &[(Option<&str>, &Type, &Element)],
+        indent: usize,
+        depth: usize,
+    ) -> Result<()> {
+        if values.is_empty() {
+            output.push_str("{}");
+            return Ok(());
+        }
+
+        output.push_str("{\n");
+
+        for (index, (name, ty, element)) in values.iter().enumerate() {
+            output.push_str(&INDENT.repeat(indent + 1));
+
+            if let Some(name) = name {
+                write!(output, "{} ", name)?;
+            }
+
+            self.write_value(output, ty, element, indent + 1, depth)?;
+            output.push_str(if index + 1 < values.len() { ",\n" } else { "\n" });
+        }
+
+        output.push_str(&INDENT.repeat(indent));
+        output.push('}');
+
+        Ok(())
+    }
+}
+
+/// Gives each of `types` the next context-specific tag, in order, leaving the
+/// tags explicit or implicit to the environment as automatic tags are.
+fn automatically_tag<'a>(types: impl Iterator<Item = &'a mut Type>) {
+    for (number, ty) in types.enumerate() {
+        let prefix = Prefix::new(None, TagKind::Environment, None, Number::Literal(number as i64));
+        let tagged = Type::from(RawType::Builtin(BuiltinType::Prefixed(prefix, Box::new(ty.clone()))));
+
+        *ty = Type { name: ty.name.clone(), ..tagged };
+    }
+}
+
+/// The class of a tag, which is context-specific unless it's given.
+fn tag_class(prefix: &Prefix) -> TagClass {
+    match prefix.class {
+        Some(Class::Universal) => TagClass::Universal,
+        Some(Class::Application) => TagClass::Application,
+        Some(Class::Private) => TagClass::Private,
+        Some(Class::Context) | None => TagClass::Context,
+    }
+}
+
+fn tag_number(prefix: &Prefix) -> Result<u32> {
+    match prefix.number {
+        Number::Literal(number) if number >= 0 && number <= i64::from(u32::MAX) => Ok(number as u32),
+        ref number => bail!("Can't decode values tagged with the number {}.", number),
+    }
+}
+
+/// The universal tag number of a built-in type (X.680 8.6), or `None` for a
+/// type without a tag of its own.
+fn universal_tag(builtin: &BuiltinType) -> Option<u8> {
+    Some(match builtin {
+        BuiltinType::Boolean => 1,
+        BuiltinType::Integer(_) => 2,
+        BuiltinType::BitString(_) => 3,
+        BuiltinType::OctetString => 4,
+        BuiltinType::Null => 5,
+        BuiltinType::ObjectIdentifier => 6,
+        BuiltinType::Enumeration(..) => 10,
+        BuiltinType::Time => 14,
+        BuiltinType::Sequence(_) | BuiltinType::SequenceOf(_) => 16,
+        BuiltinType::Set(_) | BuiltinType::SetOf(_) => 17,
+        BuiltinType::Date => 31,
+        BuiltinType::TimeOfDay => 32,
+        BuiltinType::DateTime => 33,
+        BuiltinType::Duration => 34,
+        BuiltinType::CharacterString(kind) => match kind {
+            CharacterStringType::Utf8 => 12,
+            CharacterStringType::Numeric => 18,
+            CharacterStringType::Printable => 19,
+            CharacterStringType::Teletex | CharacterStringType::T61 => 20,
+            CharacterStringType::Videotex => 21,
+            CharacterStringType::Ia5 => 22,
+            CharacterStringType::Graphic => 25,
+            CharacterStringType::Visible | CharacterStringType::Iso646 => 26,
+            CharacterStringType::General => 27,
+            CharacterStringType::Universal => 28,
+            CharacterStringType::Unrestricted => 29,
+            CharacterStringType::Bmp => 30,
+        },
+        _ => return None,
+    })
+}
+
+fn hex(octets: &[u8]) -> String {
+    octets.iter().map(|octet| format!("{:02X}", octet)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::Parser, semantics::SemanticChecker};
+
+    fn decode(source: &str, name: &str, der: &[u8]) -> Result<String> {
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        checker.build().unwrap();
+
+        Decoder::new(&checker.table, TagEnvironment::from(&checker.module.tag)).decode(name, der)
+    }
+
+    #[test]
+    fn implicit_tags() {
+        let source = "Test DEFINITIONS IMPLICIT TAGS ::= BEGIN
+            Record ::= SET {
+                id [0] INTEGER,
+                flag [1] BOOLEAN OPTIONAL,
+                choice [2] Choice
+            }
+            Choice ::= CHOICE { a [0] NULL, b [1] PrintableString }
+        END";
+
+        // The components out of order, and the CHOICE explicitly tagged
+        // despite the module's default.
+        let der = [0x31, 0xA, 0xA2, 0x4, 0x81, 0x2, b'h', b'i', 0x80, 0x2, 0x1, 0x0];
+
+        assert_eq!(
+            "value Record ::= {\n    id 256,\n    choice b : \"hi\"\n}",
+            decode(source, "Record", &der).unwrap()
+        );
+
+        // A missing component, and a value of the wrong type.
+        assert!(decode(source, "Record", &[0x31, 0x4, 0x80, 0x2, 0x1, 0x0]).is_err());
+        assert!(decode(source, "Choice", &[0x2, 0x1, 0x0]).is_err());
+    }
+
+    #[test]
+    fn automatic_tags() {
+        let source = "Test DEFINITIONS AUTOMATIC TAGS ::= BEGIN
+            S ::= SEQUENCE { a INTEGER OPTIONAL, b BOOLEAN, c C }
+            T ::= SET { p INTEGER, q BOOLEAN }
+            U ::= SEQUENCE { a [5] INTEGER, b BOOLEAN }
+            C ::= CHOICE { x INTEGER, y BOOLEAN }
+        END";
+
+        // `a` left out, and the CHOICE tagged explicitly.
+        let der = [0x30, 0x8, 0x81, 0x1, 0xFF, 0xA2, 0x3, 0x80, 0x1, 0x5];
+
+        assert_eq!(
+            "value S ::= {\n    b TRUE,\n    c x : 5\n}",
+            decode(source, "S", &der).unwrap()
+        );
+
+        assert_eq!(
+            "value T ::= {\n    p 1,\n    q FALSE\n}",
+            decode(source, "T", &[0x31, 0x6, 0x81, 0x1, 0x0, 0x80, 0x1, 0x1]).unwrap()
+        );
+
+        // A component tagged already turns automatic tagging off.
+        assert_eq!(
+            "value U ::= {\n    a 1,\n    b TRUE\n}",
+            decode(source, "U", &[0x30, 0x6, 0x85, 0x1, 0x1, 0x1, 0x1, 0xFF]).unwrap()
+        );
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let source = "Test DEFINITIONS ::= BEGIN
+            Node ::= SEQUENCE { children SEQUENCE OF Node }
+        END";
+
+        let mut der = vec![0x30, 0x2, 0x30, 0x0];
+
+        for _ in 0..20_000 {
+            let mut children = vec![0x30];
+            encode_length(der.len(), &mut children).unwrap();
+            children.append(&mut der);
+
+            der.push(0x30);
+            encode_length(children.len(), &mut der).unwrap();
+            der.append(&mut children);
+        }
+
+        let error = decode(source, "Node", &der).unwrap_err();
+        assert_eq!(format!("The value is nested more than {} deep.", MAX_DEPTH), error.to_string());
+    }
+
+    #[test]
+    fn indefinite_length() {
+        let source = "Test DEFINITIONS ::= BEGIN
+            Pair ::= SEQUENCE { a INTEGER, b BOOLEAN }
+        END";
+
+        let error = decode(source, "Pair", &[0x30, 0x80, 0x2, 0x1, 0x0, 0x1, 0x1, 0x0, 0x0, 0x0]).unwrap_err();
+        assert_eq!("Indefinite lengths aren't allowed in DER.", error.to_string());
+    }
+}
@@ -2,19 +2,47 @@
 extern crate log;
 
 mod codegen;
+mod decode;
 mod parser;
 mod registry;
 mod semantics;
 
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, str::FromStr};
 
-use self::{codegen::*, parser::Parser, semantics::*};
+use self::{codegen::*, decode::Decoder, parser::Parser, semantics::*};
+
+pub use self::parser::OidLimits;
 
 pub type Result<T> = std::result::Result<T, failure::Error>;
 
+/// The language generated by the compiler.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Target {
+    /// Rust types for use with the `dasn1` crates.
+    #[default]
+    Rust,
+    /// A Graphviz graph of the types and their references.
+    Dot,
+}
+
+impl FromStr for Target {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rust" => Ok(Target::Rust),
+            "dot" => Ok(Target::Dot),
+            target => Err(failure::format_err!("Unknown target {:?}.", target)),
+        }
+    }
+}
+
 pub struct NotationCompiler {
     path: PathBuf,
     dependencies: Option<PathBuf>,
+    target: Target,
+    serde_names: bool,
+    oid_limits: OidLimits,
 }
 
 impl NotationCompiler {
@@ -22,6 +50,9 @@ impl NotationCompiler {
         Self {
             path: path.into(),
             dependencies: None,
+            target: Target::default(),
+            serde_names: false,
+            oid_limits: OidLimits::default(),
         }
     }
 
@@ -30,18 +61,119 @@ impl NotationCompiler {
         self
     }
 
+    /// Sets the language to generate. Default: `Target::Rust`.
+    pub fn target(mut self, target: Target) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets whether to name the generated fields and variants after their
+    /// ASN.1 identifiers for serde, and derive serde for the `ENUMERATED`
+    /// types too, so the types can be used with other serde formats such as
+    /// JSON. Default: `false`.
+    pub fn serde_names(mut self, enabled: bool) -> Self {
+        self.serde_names = enabled;
+        self
+    }
+
+    /// Sets the limits on resolving OBJECT IDENTIFIERs defined relative to
+    /// other OBJECT IDENTIFIERs, past which the module is rejected. Default:
+    /// `OidLimits::default()`.
+    pub fn oid_limits(mut self, limits: OidLimits) -> Self {
+        self.oid_limits = limits;
+        self
+    }
+
+    /// Generates the code for every module in the file. Modules in the same
+    /// file may import from each other, and their code is generated in the
+    /// order they're defined, after a prelude shared by all of them.
     pub fn build(self) -> Result<String> {
         let source = fs::read_to_string(&self.path)?;
-        let ast = Parser::parse(&source)?;
+        let modules = Parser::parse_all(&source)?;
+        check_modules(&modules)?;
 
-        let mut fixed_tree = SemanticChecker::new(ast);
-        fixed_tree.build()?;
+        let mut fixed_trees = Vec::new();
+
+        for module in modules {
+            let mut fixed_tree = SemanticChecker::new(module);
+            fixed_tree.oid_limits = self.oid_limits;
+            fixed_tree.build()?;
+            fixed_trees.push(fixed_tree);
+        }
 
         let mut output = Vec::new();
 
-        CodeGenerator::<Vec<u8>, Rust>::new(fixed_tree, &mut output)
-            .generate()?;
+        match self.target {
+            Target::Rust => CodeGenerator::<Vec<u8>, Rust>::new(fixed_trees, &mut output)
+                .serde_names(self.serde_names)
+                .generate()?,
+            Target::Dot => CodeGenerator::<Vec<u8>, Dot>::new(fixed_trees, &mut output).generate()?,
+        }
 
         Ok(String::from_utf8(output).unwrap())
     }
+
+    /// Decodes `der` as a value of the type assigned to `name` in whichever
+    /// of the file's modules defines it, and prints it in ASN.1 value
+    /// notation, as the assignment `value <name> ::= <value>`.
+    pub fn decode(self, name: &str, der: &[u8]) -> Result<String> {
+        let source = fs::read_to_string(&self.path)?;
+
+        for module in Parser::parse_all(&source)? {
+            let mut checker = SemanticChecker::new(module);
+            checker.oid_limits = self.oid_limits;
+            checker.build()?;
+
+            if checker.table.types.contains_key(name) {
+                let environment = TagEnvironment::from(&checker.module.tag);
+                return Decoder::new(&checker.table, environment).decode(name, der);
+            }
+        }
+
+        Err(failure::format_err!("{} isn't defined in {}.", name, self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_target() {
+        let graph = NotationCompiler::new("tests/basic.asn1")
+            .target(Target::Dot)
+            .build()
+            .unwrap();
+
+        assert!(graph.starts_with("digraph asn1 {"));
+        assert!(graph.contains("\"PFX\" [shape=record, label=\"{PFX|version: INTEGER\\l|"));
+        assert!(graph.contains("\"PFX\" -> \"ContentInfo\";"));
+        assert!(graph.contains("macData: MacData OPTIONAL}\"];"));
+        assert!(graph.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn oid_limits() {
+        let compiler = || NotationCompiler::new("tests/oids.asn1");
+        assert!(compiler().build().is_ok());
+
+        let limits = OidLimits { max_arcs: 4, ..OidLimits::default() };
+        let error = compiler().oid_limits(limits).build().unwrap_err().to_string();
+        assert!(error.ends_with("has more than the maximum of 4 arcs."), "{}", error);
+    }
+
+    #[test]
+    fn multiple_modules() {
+        let output = NotationCompiler::new("tests/compile/multiple.asn1").build().unwrap();
+
+        assert!(output.contains("enum Version {"));
+        assert!(output.contains("struct Name {"));
+        assert!(output.contains("struct Person {"));
+        assert!(output.contains("version: Enumerated<Version>,"));
+
+        // Both modules use OCTET STRING, which is only imported once.
+        let imports = output.lines().filter(|line| line.starts_with("use ")).collect::<Vec<_>>();
+        assert!(imports.contains(&"use asn1::types::OctetString;"));
+        assert_eq!(imports.len(), imports.iter().collect::<std::collections::HashSet<_>>().len());
+    }
 }
@@ -1,7 +1,9 @@
+use std::{fmt::Display, fs, process};
+
 use clap::{clap_app, crate_description, crate_version};
 use log::{debug, LevelFilter};
 
-use dasn1_notation::NotationCompiler;
+use dasn1_notation::{NotationCompiler, Target};
 
 fn main() {
     let matches = clap_app!(casn1 =>
@@ -12,9 +14,23 @@ fn main() {
             +takes_value
             "Specify the dependency directory. Will automatically parse the headers of \
             the files, and import them if necessary. Default: \"./definitions\"")
+        (@arg target: -t --target
+            +takes_value
+            possible_values(&["rust", "dot"])
+            "The language to generate. Default: \"rust\"")
+        (@arg serde: --serde
+            "Name the generated fields and variants after their ASN.1 identifiers \
+            for serde, and derive serde for ENUMERATED types, for use with other \
+            serde formats such as JSON.")
         (@arg input: ... "ASN.1 files to parse.")
         (@arg verbose: -v --verbose ...
             "Set log output level")
+        (@subcommand decode =>
+            (about: "Decodes a DER encoded value against a type from an ASN.1 file, and \
+            prints it in ASN.1 value notation.")
+            (@arg schema: +required "The ASN.1 file defining the type.")
+            (@arg name: +required "The name of the type to decode the value as.")
+            (@arg der: +required "The file holding the DER encoded value."))
     )
     .get_matches();
 
@@ -32,12 +48,36 @@ fn main() {
     debug!("LOG Level: {:?}", filter_level);
     debug!("CLI Config: {:#?}", matches);
 
+    if let Some(matches) = matches.subcommand_matches("decode") {
+        let der = fs::read(matches.value_of("der").unwrap()).unwrap_or_else(exit);
+
+        let value = NotationCompiler::new(matches.value_of("schema").unwrap())
+            .decode(matches.value_of("name").unwrap(), &der)
+            .unwrap_or_else(exit);
+
+        println!("{}", value);
+        return;
+    }
+
     let directory = matches.value_of("dependencies").unwrap_or("./asn1");
 
+    let target = matches
+        .value_of("target")
+        .map(|t| t.parse::<Target>().unwrap_or_else(exit))
+        .unwrap_or_default();
+
     let module = NotationCompiler::new(matches.value_of("input").unwrap())
         .dependencies(directory)
+        .target(target)
+        .serde_names(matches.is_present("serde"))
         .build()
-        .unwrap_or_else(|e| panic!("{}", e));
+        .unwrap_or_else(exit);
 
     println!("{}", module);
 }
+
+/// Prints `error` and exits with a failure status.
+fn exit<T>(error: impl Display) -> T {
+    eprintln!("error: {}", error);
+    process::exit(1)
+}
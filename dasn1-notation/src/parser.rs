@@ -28,20 +28,21 @@ type ElementSet = Vec<Vec<Element>>;
 pub(crate) struct Parser<'a>(Peekable<FlatPairs<'a, Rule>>, &'a str);
 
 impl<'a> Parser<'a> {
-    /// Parse asn1 module into an Abstract Syntax Tree (AST) represented by the
-    /// `Module` struct.
-    pub fn parse(source: &'a str) -> Result<Module> {
-        Self::new(Rule::ModuleDefinition, source)?.parse_module()
-    }
+    /// Parses every module in `source` into an Abstract Syntax Tree (AST) of
+    /// `Module`s. Most files hold a single module, but some concatenate
+    /// several.
+    pub fn parse_all(source: &'a str) -> Result<Vec<Module>> {
+        let mut ast = Self::new(Rule::ModuleDefinitions, source)?;
+        ast.take(Rule::ModuleDefinitions);
+
+        let mut modules = Vec::new();
+        while ast.peek(Rule::Module) {
+            modules.push(ast.parse_module()?);
+        }
 
-    /// Copies the lexer output and parses the module's identifying information
-    /// into an Abstract Syntax Tree (AST) represented by the
-    /// `ModuleIdentifier` struct.
-    pub fn parse_header(source: &'a str) -> Result<ModuleIdentifier> {
-        let mut ast = Self::new(Rule::ModuleHeaderOnly, source)?;
-        ast.take(Rule::ModuleHeaderOnly);
+        ast.take(Rule::EOI);
 
-        ast.parse_module_identifier()
+        Ok(modules)
     }
 
     fn new(rule: Rule, source: &'a str) -> Result<Self> {
@@ -108,7 +109,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_module(&mut self) -> Result<Module> {
-        self.take(Rule::ModuleDefinition);
+        self.take(Rule::Module);
 
         let identifier = self.parse_module_identifier()?;
         let tag = self.parse_tag_default();
@@ -124,8 +125,6 @@ impl<'a> Parser<'a> {
             (Exports::All, Vec::new(), Vec::new())
         };
 
-        self.take(Rule::EOI);
-
         Ok(Module {
             identifier,
             tag,
@@ -398,6 +397,7 @@ impl<'a> Parser<'a> {
                         raw_type,
                         name: None,
                         constraints: Some(vec![constraint]),
+                        effective_constraint: None,
                     }
                 } else {
                     let raw_type = self.parse_unconstrained_type();
@@ -411,6 +411,7 @@ impl<'a> Parser<'a> {
                         raw_type,
                         name: None,
                         constraints: Some(constraints),
+                        effective_constraint: None,
                     }
                 }
             }
@@ -517,6 +518,15 @@ impl<'a> Parser<'a> {
         if self.look(Rule::BuiltinType).is_some() {
             let pair = self.next();
             match pair.as_rule() {
+                Rule::AnyType => {
+                    // The field an `ANY` is defined by isn't needed to
+                    // represent its values, which can be of any type.
+                    if pair.into_inner().next().is_some() {
+                        self.parse_identifier();
+                    }
+
+                    RawType::Builtin(BuiltinType::OpenType)
+                }
                 Rule::BooleanType => RawType::Builtin(BuiltinType::Boolean),
                 Rule::BitStringType => {
                     let mut named_bits = BTreeMap::new();
@@ -576,8 +586,14 @@ impl<'a> Parser<'a> {
                     }))
                 }
 
+                Rule::DateType => RawType::Builtin(BuiltinType::Date),
+                Rule::DateTimeType => RawType::Builtin(BuiltinType::DateTime),
+                Rule::DurationType => RawType::Builtin(BuiltinType::Duration),
+
                 Rule::EnumeratedType => {
-                    self.take(Rule::Enumerations);
+                    // The extension marker isn't a rule of its own, so it can
+                    // only be found in the text.
+                    let extensible = self.take(Rule::Enumerations).as_str().contains("...");
 
                     let enumerations = self.parse_enumeration();
 
@@ -589,6 +605,8 @@ impl<'a> Parser<'a> {
 
                     let extended_enumerations = if self.peek(Rule::Enumeration) {
                         Some(self.parse_enumeration())
+                    } else if extensible {
+                        Some(Vec::new())
                     } else {
                         None
                     };
@@ -634,6 +652,8 @@ impl<'a> Parser<'a> {
 
                 Rule::ObjectIdentifierType => RawType::Builtin(BuiltinType::ObjectIdentifier),
                 Rule::OctetStringType => RawType::Builtin(BuiltinType::OctetString),
+                Rule::TimeType => RawType::Builtin(BuiltinType::Time),
+                Rule::TimeOfDayType => RawType::Builtin(BuiltinType::TimeOfDay),
 
                 Rule::PrefixedType => {
                     if self.look(Rule::TaggedType).is_some() {
@@ -1055,21 +1075,21 @@ impl<'a> Parser<'a> {
                     Rule::SizeConstraint => SubTypeElement::Size(self.parse_size_constraint()),
                     Rule::ValueRange => {
                         self.take(Rule::ValueRange);
-                        let is_low_inclusive =
+                        let is_low_exclusive =
                             self.take(Rule::LowerEndpoint).as_str().contains('<');
                         let low_value = if self.take(Rule::LowerEndValue).as_str().contains("MIN") {
-                            RangeValue::Min(is_low_inclusive)
+                            RangeValue::Min(is_low_exclusive)
                         } else {
-                            RangeValue::Value(self.parse_value(), is_low_inclusive)
+                            RangeValue::Value(self.parse_value(), is_low_exclusive)
                         };
 
-                        let is_high_inclusive =
+                        let is_high_exclusive =
                             self.take(Rule::UpperEndpoint).as_str().contains('<');
                         let high_value = if self.take(Rule::UpperEndValue).as_str().contains("MAX")
                         {
-                            RangeValue::Max(is_high_inclusive)
+                            RangeValue::Max(is_high_exclusive)
                         } else {
-                            RangeValue::Value(self.parse_value(), is_high_inclusive)
+                            RangeValue::Value(self.parse_value(), is_high_exclusive)
                         };
 
                         SubTypeElement::Range(low_value, high_value)
@@ -1692,6 +1712,8 @@ pub enum ExceptionIdentification {
     Arbitrary(Box<Type>, Value),
 }
 
+/// An endpoint of a value range, where the `bool` is whether the endpoint
+/// itself is excluded from the range (e.g. `1<..10`).
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Variation)]
 pub enum RangeValue {
     Min(bool),
@@ -1734,8 +1756,8 @@ pub enum Presence {
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct ComponentConstraint {
-    constraint: Option<Constraint>,
-    presence: Option<Presence>,
+    pub constraint: Option<Constraint>,
+    pub presence: Option<Presence>,
 }
 
 impl ComponentConstraint {
@@ -1746,3 +1768,82 @@ impl ComponentConstraint {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_defined_by() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                AlgorithmIdentifier ::= SEQUENCE {
+                    algorithm OBJECT IDENTIFIER,
+                    parameters ANY DEFINED BY algorithm OPTIONAL
+                }
+                Anything ::= ANY
+                ANYTHING ::= BOOLEAN
+            END";
+
+        let module = Parser::parse_all(source).unwrap().remove(0);
+        let raw_type = |index: usize| match module.assignments[index].kind {
+            AssignmentType::Type(ref ty) => &ty.raw_type,
+            _ => unreachable!(),
+        };
+
+        let components = match raw_type(0) {
+            RawType::Builtin(BuiltinType::Sequence(components)) => components,
+            _ => unreachable!(),
+        };
+        let components = components.components.as_ref().unwrap();
+        let parameters = components[1].as_type().unwrap();
+        assert_eq!(RawType::Builtin(BuiltinType::OpenType), parameters.0.raw_type);
+
+        assert_eq!(&RawType::Builtin(BuiltinType::OpenType), raw_type(1));
+        assert_eq!(&RawType::Builtin(BuiltinType::Boolean), raw_type(2));
+    }
+
+    #[test]
+    fn time_types() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                T ::= DATE
+                D ::= DURATION
+                Stamp ::= SEQUENCE {
+                    at DATE-TIME,
+                    during TIME-OF-DAY OPTIONAL,
+                    when TIME
+                }
+                DATEX ::= BOOLEAN
+            END";
+
+        let module = Parser::parse_all(source).unwrap().remove(0);
+        let raw_type = |index: usize| match module.assignments[index].kind {
+            AssignmentType::Type(ref ty) => &ty.raw_type,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(&RawType::Builtin(BuiltinType::Date), raw_type(0));
+        assert_eq!(&RawType::Builtin(BuiltinType::Duration), raw_type(1));
+
+        let components = match raw_type(2) {
+            RawType::Builtin(BuiltinType::Sequence(components)) => components,
+            _ => unreachable!(),
+        };
+        let components: Vec<_> = components
+            .components
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|component| component.as_type().unwrap().0.raw_type.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                RawType::Builtin(BuiltinType::DateTime),
+                RawType::Builtin(BuiltinType::TimeOfDay),
+                RawType::Builtin(BuiltinType::Time),
+            ],
+            components
+        );
+
+        assert_eq!(&RawType::Builtin(BuiltinType::Boolean), raw_type(3));
+    }
+}
@@ -63,6 +63,10 @@ impl ModuleReference {
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn as_identifier(&self) -> Option<ModuleIdentifier> {
         Some(ModuleIdentifier {
             name: self.name.clone(),
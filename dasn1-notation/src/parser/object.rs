@@ -21,6 +21,42 @@ impl ClassDefinition {
     pub fn new(fields: Vec<FieldSpec>, syntax: Option<Vec<Token>>) -> Self {
         Self { fields, syntax }
     }
+
+    pub fn fields(&self) -> &[FieldSpec] {
+        &self.fields
+    }
+
+    /// The definition X.681 Annex A gives `TYPE-IDENTIFIER`.
+    pub fn type_identifier() -> Self {
+        Self::new(
+            vec![
+                FieldSpec::FixedTypeValue(
+                    String::from("id"),
+                    RawType::from(BuiltinType::ObjectIdentifier).into(),
+                    true,
+                    Optionality::None,
+                ),
+                FieldSpec::Type(String::from("Type"), Optionality::None),
+            ],
+            None,
+        )
+    }
+
+    /// The definition X.681 Annex B gives `ABSTRACT-SYNTAX`.
+    pub fn abstract_syntax() -> Self {
+        let mut property = BTreeMap::new();
+        property.insert(String::from("handles-invalid-encodings"), Number::from(0));
+
+        let mut fields = Self::type_identifier().fields;
+        fields.push(FieldSpec::FixedTypeValue(
+            String::from("property"),
+            RawType::from(BuiltinType::BitString(property)).into(),
+            false,
+            Optionality::Default(Value::BitString(BitString::List(Vec::new()))),
+        ));
+
+        Self::new(fields, None)
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Variation)]
@@ -33,6 +69,19 @@ pub enum FieldSpec {
     ObjectSet(String, DefinedObjectClass, Optionality<(ElementSet, bool)>),
 }
 
+impl FieldSpec {
+    pub fn name(&self) -> &str {
+        match self {
+            FieldSpec::FixedTypeValue(name, ..)
+            | FieldSpec::VariableTypeValue(name, ..)
+            | FieldSpec::FixedValueSet(name, ..)
+            | FieldSpec::ObjectField(name, ..)
+            | FieldSpec::Type(name, ..)
+            | FieldSpec::ObjectSet(name, ..) => name,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum Optionality<T> {
     Optional,
@@ -50,6 +99,10 @@ impl Field {
     pub fn new(name: String, kind: FieldType) -> Self {
         Self { name, kind }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Variation)]
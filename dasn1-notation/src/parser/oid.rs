@@ -5,9 +5,32 @@ use std::{
 };
 
 use derefable::Derefable;
+use failure::ensure;
 use variation::Variation;
 
 use super::values::Number;
+use crate::Result;
+
+/// Limits on resolving OBJECT IDENTIFIERs defined relative to other OBJECT
+/// IDENTIFIERs, so that a pathological module fails to compile rather than
+/// exhausting memory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OidLimits {
+    /// The most arcs a resolved OBJECT IDENTIFIER may have.
+    pub max_arcs: usize,
+    /// The longest chain of OBJECT IDENTIFIERs each defined relative to the
+    /// next.
+    pub max_depth: usize,
+}
+
+impl Default for OidLimits {
+    fn default() -> Self {
+        Self {
+            max_arcs: 128,
+            max_depth: 32,
+        }
+    }
+}
 
 #[derive(Clone, Debug, Derefable, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct ObjectIdentifier(#[deref(mutable)] Vec<ObjIdComponent>);
@@ -37,12 +60,25 @@ impl ObjectIdentifier {
         false
     }
 
-    pub fn replace(&mut self, map: &BTreeMap<String, ObjectIdentifier>) {
+    /// Replaces each reference to an OBJECT IDENTIFIER in `map` with its
+    /// components, failing if that would give more than `max_arcs` arcs.
+    pub fn replace(&mut self, map: &BTreeMap<String, ObjectIdentifier>, max_arcs: usize) -> Result<()> {
         for (name, id) in map {
-            while let Ok(index) = self.0.binary_search(&ObjIdComponent::Name(name.clone())) {
-                self.0.splice(index..(index + 1), id.0.iter().cloned());
+            let name = ObjIdComponent::Name(name.clone());
+
+            while let Some(index) = self.0.iter().position(|component| *component == name) {
+                self.0.splice(index..=index, id.0.iter().cloned());
+
+                ensure!(
+                    self.0.len() <= max_arcs,
+                    "{} has more than the maximum of {} arcs.",
+                    self,
+                    max_arcs
+                );
             }
         }
+
+        Ok(())
     }
 }
 
@@ -1,6 +1,6 @@
 mod prefix;
 
-use crate::parser::*;
+use crate::{parser::*, semantics::EffectiveConstraint};
 pub use prefix::*;
 
 #[derive(Clone, Debug, Derefable, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -9,6 +9,8 @@ pub struct Type {
     pub raw_type: RawType,
     pub name: Option<String>,
     pub constraints: Option<Vec<Constraint>>,
+    /// The normalised form of `constraints`, set by the semantic checker.
+    pub effective_constraint: Option<EffectiveConstraint>,
 }
 
 impl From<RawType> for Type {
@@ -17,6 +19,7 @@ impl From<RawType> for Type {
             raw_type,
             name: None,
             constraints: None,
+            effective_constraint: None,
         }
     }
 }
@@ -47,6 +50,9 @@ pub enum BuiltinType {
     BitString(BTreeMap<String, Number>),
     CharacterString(CharacterStringType),
     Choice(ChoiceType),
+    Date,
+    DateTime,
+    Duration,
     Enumeration(
         Vec<Enumeration>,
         Option<ExceptionIdentification>,
@@ -57,11 +63,15 @@ pub enum BuiltinType {
     ObjectClassField(DefinedObjectClass, Vec<Field>),
     ObjectIdentifier,
     OctetString,
+    /// The type of a `&Type` field, whose values can be of any type.
+    OpenType,
     Prefixed(Prefix, Box<Type>),
     Sequence(ComponentTypeList),
     SequenceOf(Box<Type>),
     Set(Set),
     SetOf(Box<Type>),
+    Time,
+    TimeOfDay,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -124,6 +134,14 @@ impl Enumeration {
     pub fn new(name: String, number: Option<Number>) -> Self {
         Self { name, number }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn number(&self) -> Option<&Number> {
+        self.number.as_ref()
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Variation)]
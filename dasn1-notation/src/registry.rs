@@ -1,19 +1,21 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fs,
     ops::{Deref, DerefMut},
     path::PathBuf,
 };
 
+use failure::ensure;
 use unwrap_to::unwrap_to;
 
 use crate::{parser::*, Result};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct GlobalSymbolTable {
     pub types: SymbolTable<Type>,
     pub values: SymbolTable<(Type, Value)>,
     pub value_sets: SymbolTable<(Type, ElementSetSpec)>,
+    pub classes: SymbolTable<ObjectClass>,
 }
 
 impl GlobalSymbolTable {
@@ -21,6 +23,53 @@ impl GlobalSymbolTable {
         self.types.contains_key(key)
             || self.values.contains_key(key)
             || self.value_sets.contains_key(key)
+            || self.classes.contains_key(key)
+    }
+
+    /// Looks up the type assigned to `name`, following aliases of other
+    /// types in the module (`Alias ::= Name`) to the type they stand for.
+    /// Returns the type along with the name it's assigned to, or `None` if
+    /// `name` isn't assigned in the module, such as when it's imported, or
+    /// the aliases form a cycle.
+    pub fn resolve<'a>(&'a self, mut name: &'a str) -> Option<(&'a str, &'a Type)> {
+        let mut seen = BTreeSet::new();
+
+        loop {
+            let ty = self.types.get(name)?;
+
+            match ty.raw_type {
+                RawType::Referenced(ref reference) if reference.is_internal() => {
+                    if !seen.insert(name) {
+                        return None;
+                    }
+
+                    name = &reference.item;
+                }
+                _ => return Some((name, ty)),
+            }
+        }
+    }
+
+    /// Looks up the INTEGER assigned to the value `name` in the module,
+    /// following values defined as other values (`b INTEGER ::= a`). Returns
+    /// `None` if it isn't assigned an INTEGER in the module, or the values
+    /// form a cycle.
+    pub fn resolve_integer<'a>(&'a self, mut name: &'a str) -> Option<i64> {
+        let mut seen = BTreeSet::new();
+
+        loop {
+            match self.values.get(name)? {
+                (_, Value::Integer(IntegerValue::Literal(value))) => return Some(*value),
+                (_, Value::Defined(DefinedValue::Simple(reference))) if reference.is_internal() => {
+                    if !seen.insert(name) {
+                        return None;
+                    }
+
+                    name = &reference.item;
+                }
+                _ => return None,
+            }
+        }
     }
 
     pub fn insert_type(&mut self, key: String, value: Type) -> Option<Type> {
@@ -39,9 +88,13 @@ impl GlobalSymbolTable {
     ) -> Option<(Type, ElementSetSpec)> {
         self.value_sets.insert(key, (ty, set))
     }
+
+    pub fn insert_class(&mut self, key: String, class: ObjectClass) -> Option<ObjectClass> {
+        self.classes.insert(key, class)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SymbolTable<V, K: Ord = String> {
     map: BTreeMap<K, V>,
 }
@@ -68,8 +121,83 @@ impl<K: Ord, V> DerefMut for SymbolTable<V, K> {
     }
 }
 
+impl SymbolTable<Type> {
+    /// The names of the types in the order they depend on each other: each
+    /// type comes after the types in the module it references, and types that
+    /// don't depend on each other stay in alphabetical order. A cycle of
+    /// references is entered at whichever of its types comes first.
+    pub fn dependency_order(&self) -> Vec<&str> {
+        let mut visited = BTreeSet::new();
+        let mut order = Vec::with_capacity(self.map.len());
+
+        for name in self.map.keys() {
+            self.visit(name, &mut visited, &mut order);
+        }
+
+        order
+    }
+
+    fn visit<'a>(&'a self, name: &str, visited: &mut BTreeSet<&'a str>, order: &mut Vec<&'a str>) {
+        let (name, ty) = match self.map.get_key_value(name) {
+            Some((name, ty)) => (name.as_str(), ty),
+            None => return,
+        };
+
+        if !visited.insert(name) {
+            return;
+        }
+
+        let mut dependencies = Vec::new();
+        collect_references(ty, &mut dependencies);
+
+        for dependency in dependencies {
+            self.visit(dependency, visited, order);
+        }
+
+        order.push(name);
+    }
+}
+
+/// Pushes the name of every type in the module that `ty` references,
+/// including from within its components.
+fn collect_references<'a>(ty: &'a Type, references: &mut Vec<&'a str>) {
+    fn components<'a>(list: &'a ComponentTypeList, references: &mut Vec<&'a str>) {
+        let additions = list.extension.iter().flat_map(|extension| &extension.additions);
+        let added = additions.flat_map(|addition| match addition {
+            ExtensionAddition::Component(component) => std::slice::from_ref(component),
+            ExtensionAddition::Group(_, components) => &components[..],
+        });
+
+        for component in list.components.iter().flatten().chain(added) {
+            match component {
+                ComponentType::Type { ty, .. } | ComponentType::ComponentsOf(ty) => {
+                    collect_references(ty, references)
+                }
+            }
+        }
+    }
+
+    match &ty.raw_type {
+        RawType::Referenced(reference) if reference.is_internal() => references.push(&reference.item),
+        RawType::Builtin(BuiltinType::Choice(choice)) => {
+            for alternative in &choice.alternatives {
+                collect_references(alternative, references);
+            }
+        }
+        RawType::Builtin(BuiltinType::Sequence(list))
+        | RawType::Builtin(BuiltinType::Set(Set::Concrete(list))) => components(list, references),
+        RawType::Builtin(BuiltinType::Prefixed(_, ty))
+        | RawType::Builtin(BuiltinType::SequenceOf(ty))
+        | RawType::Builtin(BuiltinType::SetOf(ty)) => collect_references(ty, references),
+        _ => {}
+    }
+}
+
 impl SymbolTable<(Type, Value)> {
-    pub fn resolve_object_identifiers(&mut self) {
+    /// Resolves each relative OBJECT IDENTIFIER to its absolute form, failing
+    /// if one can't be resolved within `limits`. Those referencing values
+    /// from other modules are left relative.
+    pub fn resolve_object_identifiers_with(&mut self, limits: OidLimits) -> Result<()> {
         debug!("Resolving OIDs to full path.");
         let total_length = self
             .map
@@ -99,13 +227,26 @@ impl SymbolTable<(Type, Value)> {
 
         trace!("Number of initial Absolute OIDs: {}", absolute_oids.len());
 
+        // Each pass resolves at least one more link of every chain of
+        // relative OIDs, so the number of passes is the depth of the chain.
+        let mut depth = 0;
+
         while total_length > absolute_oids.len() {
+            depth += 1;
+            let resolved = absolute_oids.len();
+
+            ensure!(
+                depth <= limits.max_depth,
+                "OBJECT IDENTIFIERs are defined relative to each other more than {} deep.",
+                limits.max_depth
+            );
+
             for (name, object_identifier) in self
                 .get_object_identifiers_mut()
                 .filter(|(_, o)| o.is_relative())
             {
                 trace!("Attempting to canonicalise {}", object_identifier);
-                object_identifier.replace(&absolute_oids);
+                object_identifier.replace(&absolute_oids, limits.max_arcs)?;
                 if object_identifier.is_absolute() {
                     trace!("{} is now absolute.", object_identifier);
                     absolute_oids.insert(name.clone(), object_identifier.clone());
@@ -116,7 +257,16 @@ impl SymbolTable<(Type, Value)> {
                     );
                 }
             }
+
+            // The rest reference values this module imports, which aren't
+            // known here.
+            if absolute_oids.len() == resolved {
+                trace!("{} OIDs are left relative.", total_length - resolved);
+                break;
+            }
         }
+
+        Ok(())
     }
 
     fn get_object_identifiers_mut(
@@ -146,12 +296,70 @@ impl SymbolTable<PathBuf, ModuleIdentifier> {
                     continue;
                 }
 
-                let header = Parser::parse_header(&fs::read_to_string(&path)?)?;
-
-                map.insert(header, path.to_owned());
+                for module in Parser::parse_all(&fs::read_to_string(&path)?)? {
+                    map.insert(module.identifier, path.to_owned());
+                }
             }
         }
 
         Ok(Self { map })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantics::SemanticChecker;
+
+    /// Builds a module where each OID is defined relative to the next,
+    /// `depth` deep, resolving them within `limits`.
+    fn oid_chain(depth: usize, limits: OidLimits) -> Result<SymbolTable<(Type, Value)>> {
+        let mut source = String::from("Test DEFINITIONS ::= BEGIN\n");
+        source.push_str(&format!("oid{:03} OBJECT IDENTIFIER ::= {{ iso 3 }}\n", depth));
+
+        for i in (0..depth).rev() {
+            source.push_str(&format!("oid{:03} OBJECT IDENTIFIER ::= {{ oid{:03} 1 }}\n", i, i + 1));
+        }
+
+        source.push_str("END");
+
+        let mut checker = SemanticChecker::new(Parser::parse_all(&source).unwrap().remove(0));
+        checker.oid_limits = limits;
+        checker.build()?;
+        Ok(checker.table.values)
+    }
+
+    #[test]
+    fn dependency_order() {
+        let source = "Test DEFINITIONS ::= BEGIN
+            A ::= SEQUENCE { b B, c SEQUENCE OF C }
+            B ::= CHOICE { c C, d [0] D }
+            C ::= SEQUENCE { a A OPTIONAL }
+            D ::= INTEGER
+            E ::= BOOLEAN
+        END";
+
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        checker.build().unwrap();
+
+        assert_eq!(vec!["C", "D", "B", "A", "E"], checker.table.types.dependency_order());
+    }
+
+    #[test]
+    fn relative_oid_chain() {
+        let values = oid_chain(8, OidLimits::default()).unwrap();
+
+        let resolved = unwrap_to!(&values["oid000"].1 => Value::ObjectIdentifier);
+        assert!(resolved.is_absolute());
+        assert_eq!(10, resolved.len());
+    }
+
+    #[test]
+    fn relative_oid_chain_limits() {
+        assert!(oid_chain(OidLimits::default().max_depth + 8, OidLimits::default()).is_err());
+
+        let limits = OidLimits { max_arcs: 5, ..OidLimits::default() };
+        let error = oid_chain(8, limits).unwrap_err().to_string();
+        assert!(error.ends_with("has more than the maximum of 5 arcs."), "{}", error);
+    }
+}
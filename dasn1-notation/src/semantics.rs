@@ -1,18 +1,27 @@
-use std::{collections::BTreeMap, mem};
+mod constraint;
 
-use failure::ensure;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt, mem,
+};
+
+use failure::{bail, ensure};
 use unwrap_to::unwrap_to;
 
 use crate::{parser::*, registry::*, Result};
 
+pub use self::constraint::*;
+
 #[derive(Debug)]
 pub struct SemanticChecker {
     pub imports: BTreeMap<ModuleReference, Vec<String>>,
     pub module: Module,
     pub table: GlobalSymbolTable,
+    /// The limits on resolving relative OBJECT IDENTIFIERs.
+    pub oid_limits: OidLimits,
     // object_sets: ValueRegistry,
     // objects: ValueRegistry,
-    // classes: ValueRegistry,
 }
 
 impl SemanticChecker {
@@ -24,20 +33,82 @@ impl SemanticChecker {
             imports,
             module,
             table,
+            oid_limits: OidLimits::default(),
         }
     }
 
     pub fn build(&mut self) -> Result<()> {
         debug!("Building {}", self.module.identifier);
         self.resolve_imports()?;
+        self.check_definitions()?;
         self.resolve_assignments()?;
+        self.resolve_object_class_fields()?;
         self.resolve_type_aliases();
-        debug!("Skipping resolving object identifiers");
-        //self.values.resolve_object_identifiers();
+        self.resolve_component_constraints();
+        self.table.values.resolve_object_identifiers_with(self.oid_limits)?;
         self.resolve_defined_values();
+        self.resolve_constraints()?;
         Ok(())
     }
 
+    /// Checks that no name is assigned twice, and that every type and value
+    /// referenced within the module is either assigned or imported, reporting
+    /// every problem found rather than only the first.
+    pub fn check_definitions(&self) -> Result<()> {
+        debug!("Checking definitions");
+        let mut defined = BTreeSet::new();
+        let mut errors = Vec::new();
+
+        for assignment in &self.module.assignments {
+            if !defined.insert(&*assignment.name) {
+                errors.push(DefinitionError::Duplicate(assignment.name.clone()));
+            }
+        }
+
+        defined.extend(self.imports.values().flatten().map(|name| &**name));
+
+        for assignment in &self.module.assignments {
+            let mut references = Vec::new();
+
+            match assignment.kind {
+                AssignmentType::Type(ref ty) | AssignmentType::ValueSet(ref ty, _) => {
+                    type_references(ty, &mut references);
+                }
+                AssignmentType::Value(ref ty, ref value) => {
+                    type_references(ty, &mut references);
+
+                    if let Value::Defined(DefinedValue::Simple(ref reference)) = value {
+                        references.push(reference);
+                    }
+                }
+                _ => {}
+            }
+
+            // The dummy references of a parameterized assignment are only
+            // defined within it.
+            let parameters = assignment.parameters.iter().flatten().map(|(_, name)| name);
+            let parameters: BTreeSet<_> = parameters.collect();
+
+            for reference in references {
+                if reference.is_internal()
+                    && !defined.contains(&*reference.item)
+                    && !parameters.contains(&reference.item)
+                {
+                    errors.push(DefinitionError::Undefined {
+                        name: reference.item.clone(),
+                        referenced_by: assignment.name.clone(),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DefinitionErrors(errors).into())
+        }
+    }
+
     pub fn resolve_assignments(&mut self) -> Result<()> {
         debug!("Resolving assignments");
         for assignment in mem::replace(&mut self.module.assignments, Vec::new()) {
@@ -69,9 +140,8 @@ impl SemanticChecker {
                     // unimplemented!()
                     //self.objects.insert(assignment.name, (class, object));
                 }
-                AssignmentType::ObjectClass(..) => {
-                    // unimplemented!()
-                    //self.classes.insert(assignment.name, class);
+                AssignmentType::ObjectClass(class) => {
+                    self.table.insert_class(assignment.name, class);
                 }
                 AssignmentType::ObjectSet(..) => {
                     // unimplemented!()
@@ -87,6 +157,28 @@ impl SemanticChecker {
         self.table.contains_key(name)
     }
 
+    /// Replaces each `CLASS.&field` type with the type that field has in the
+    /// class's definition. Fields whose type is only known once an object is
+    /// chosen (`&Type`, or a value of type `&Type`) become an open type.
+    pub fn resolve_object_class_fields(&mut self) -> Result<()> {
+        debug!("Resolving object class fields.");
+        let classes = &self.table.classes;
+
+        for ty in self.table.types.values_mut() {
+            resolve_class_fields(classes, ty)?;
+        }
+
+        for (ty, _) in self.table.values.values_mut() {
+            resolve_class_fields(classes, ty)?;
+        }
+
+        for (ty, _) in self.table.value_sets.values_mut() {
+            resolve_class_fields(classes, ty)?;
+        }
+
+        Ok(())
+    }
+
     pub fn resolve_type_aliases(&mut self) {
         debug!("Resolving type aliases.");
         for t in self
@@ -110,6 +202,50 @@ impl SemanticChecker {
         }
     }
 
+    /// Replaces a reference to a SEQUENCE that has a `WITH COMPONENTS`
+    /// constraint with a copy of the SEQUENCE, where the constraint's
+    /// presence requirements are applied to the components.
+    pub fn resolve_component_constraints(&mut self) {
+        debug!("Resolving component constraints.");
+        let mut resolved = Vec::new();
+
+        for (name, ty) in self.table.types.iter() {
+            let components = match ty.raw_type {
+                RawType::Referenced(ref reference) if reference.is_internal() => {
+                    match self.table.types.get(&reference.item).map(|t| &t.raw_type) {
+                        Some(RawType::Builtin(BuiltinType::Sequence(components))) => components,
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            let specs = component_specs(ty.constraints.as_ref().map_or(&[][..], |c| &c[..]));
+
+            if specs.is_empty() {
+                continue;
+            }
+
+            let mut components = components.clone();
+
+            for (spec, is_full) in specs {
+                apply_component_spec(&mut components, spec, is_full);
+            }
+
+            resolved.push((
+                name.clone(),
+                Type {
+                    raw_type: RawType::Builtin(BuiltinType::Sequence(components)),
+                    ..ty.clone()
+                },
+            ));
+        }
+
+        for (name, ty) in resolved {
+            self.table.types.insert(name, ty);
+        }
+    }
+
     pub fn resolve_defined_values(&mut self) {
         debug!("Resolving defined values");
         let frozen_map = self.table.values.clone();
@@ -167,6 +303,34 @@ impl SemanticChecker {
         }
     }
 
+    pub fn resolve_constraints(&mut self) -> Result<()> {
+        debug!("Resolving constraints");
+        let evaluator = ConstraintEvaluator::new(&self.table);
+        let resolve = |ty: &Type| -> Result<Type> {
+            let mut ty = ty.clone();
+            evaluator.resolve(&mut ty)?;
+            Ok(ty)
+        };
+
+        let types = self.table.types.values().map(resolve).collect::<Result<Vec<_>>>()?;
+        let values = self.table.values.values().map(|(t, _)| resolve(t)).collect::<Result<Vec<_>>>()?;
+        let value_sets = self.table.value_sets.values().map(|(t, _)| resolve(t)).collect::<Result<Vec<_>>>()?;
+
+        for (t, resolved) in self.table.types.values_mut().zip(types) {
+            *t = resolved;
+        }
+
+        for ((t, _), resolved) in self.table.values.values_mut().zip(values) {
+            *t = resolved;
+        }
+
+        for ((t, _), resolved) in self.table.value_sets.values_mut().zip(value_sets) {
+            *t = resolved;
+        }
+
+        Ok(())
+    }
+
     pub fn resolve_imports(&mut self) -> Result<()> {
         for (reference, items) in mem::replace(&mut self.module.imports, Vec::new()) {
             ensure!(
@@ -181,3 +345,579 @@ impl SemanticChecker {
         Ok(())
     }
 }
+
+/// A problem with how a name is defined or used in a module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DefinitionError {
+    /// The name was assigned more than once.
+    Duplicate(String),
+    /// The name was referenced by an assignment, but was neither assigned
+    /// nor imported.
+    Undefined { name: String, referenced_by: String },
+    /// The name was imported from a module in the same file, which doesn't
+    /// assign or doesn't export it.
+    NotExported { name: String, module: String },
+    /// The name was assigned by two modules in the same file.
+    DefinedInBoth { name: String, modules: (String, String) },
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DefinitionError::Duplicate(name) => write!(f, "{:?} was already defined.", name),
+            DefinitionError::Undefined { name, referenced_by } => {
+                write!(f, "{:?} is used by {:?}, but isn't defined.", name, referenced_by)
+            }
+            DefinitionError::NotExported { name, module } => {
+                write!(f, "{:?} is imported from {:?}, which doesn't export it.", name, module)
+            }
+            DefinitionError::DefinedInBoth { name, modules: (first, second) } => {
+                write!(f, "{:?} is defined in both {:?} and {:?}.", name, first, second)
+            }
+        }
+    }
+}
+
+/// Checks that the symbols each of `modules` imports from another of them are
+/// assigned and exported there, and that no two of them assign the same
+/// name, as their code is generated together. Imports from modules outside
+/// of `modules` are left alone.
+pub fn check_modules(modules: &[Module]) -> Result<()> {
+    let mut errors = Vec::new();
+    let mut assigned_by = BTreeMap::new();
+
+    for module in modules {
+        for assignment in &module.assignments {
+            let name = &module.identifier.name;
+
+            if let Some(first) = assigned_by.insert(&assignment.name, name) {
+                errors.push(DefinitionError::DefinedInBoth {
+                    name: assignment.name.clone(),
+                    modules: (first.clone(), name.clone()),
+                });
+            }
+        }
+
+        for (reference, symbols) in &module.imports {
+            let exporter = modules
+                .iter()
+                .find(|exporter| exporter.identifier.name == reference.name());
+
+            let exporter = match exporter {
+                Some(exporter) => exporter,
+                None => continue,
+            };
+
+            for symbol in symbols {
+                let assigned = exporter.assignments.iter().any(|a| a.name == *symbol);
+                let exported = match exporter.exports {
+                    Exports::All => true,
+                    Exports::Symbols(ref exports) => exports.contains(symbol),
+                };
+
+                if !assigned || !exported {
+                    errors.push(DefinitionError::NotExported {
+                        name: symbol.clone(),
+                        module: exporter.identifier.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(DefinitionErrors(errors).into())
+    }
+}
+
+/// Every problem found by `SemanticChecker::check_definitions`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefinitionErrors(pub Vec<DefinitionError>);
+
+impl fmt::Display for DefinitionErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, error) in self.0.iter().enumerate() {
+            if index != 0 {
+                writeln!(f)?;
+            }
+
+            error.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for DefinitionErrors {}
+
+/// Collects the references to other types in `ty` and any types nested in it.
+fn type_references<'a>(ty: &'a Type, references: &mut Vec<&'a ReferenceType>) {
+    match ty.raw_type {
+        RawType::Referenced(ref reference) | RawType::ParameterizedReference(ref reference, _) => {
+            references.push(reference);
+        }
+        RawType::Builtin(BuiltinType::Sequence(ref components))
+        | RawType::Builtin(BuiltinType::Set(Set::Concrete(ref components))) => {
+            for component in components.components.iter().flatten() {
+                match component {
+                    ComponentType::Type { ty, .. } | ComponentType::ComponentsOf(ty) => {
+                        type_references(ty, references);
+                    }
+                }
+            }
+        }
+        RawType::Builtin(BuiltinType::Choice(ref choice)) => {
+            for alternative in &choice.alternatives {
+                type_references(alternative, references);
+            }
+        }
+        RawType::Builtin(BuiltinType::SequenceOf(ref inner))
+        | RawType::Builtin(BuiltinType::SetOf(ref inner))
+        | RawType::Builtin(BuiltinType::Prefixed(_, ref inner)) => {
+            type_references(inner, references);
+        }
+        _ => {}
+    }
+}
+
+/// Resolves the `ObjectClassField` types in `ty` and any types nested in it.
+/// The constraints of a fixed type field apply before those at its use.
+fn resolve_class_fields(classes: &SymbolTable<ObjectClass>, ty: &mut Type) -> Result<()> {
+    let resolved = match ty.raw_type {
+        RawType::Builtin(BuiltinType::ObjectClassField(ref class, ref fields)) => {
+            match class_field_type(classes, class, fields)? {
+                Some(resolved) => resolved,
+                None => return Ok(()),
+            }
+        }
+        RawType::Builtin(BuiltinType::Sequence(ref mut components))
+        | RawType::Builtin(BuiltinType::Set(Set::Concrete(ref mut components))) => {
+            for component in components.components.iter_mut().flatten() {
+                if let ComponentType::Type { ty, .. } = component {
+                    resolve_class_fields(classes, ty)?;
+                }
+            }
+
+            return Ok(());
+        }
+        RawType::Builtin(BuiltinType::Choice(ref mut choice)) => {
+            for alternative in &mut choice.alternatives {
+                resolve_class_fields(classes, alternative)?;
+            }
+
+            return Ok(());
+        }
+        RawType::Builtin(BuiltinType::SequenceOf(ref mut inner))
+        | RawType::Builtin(BuiltinType::SetOf(ref mut inner))
+        | RawType::Builtin(BuiltinType::Prefixed(_, ref mut inner)) => {
+            return resolve_class_fields(classes, inner);
+        }
+        _ => return Ok(()),
+    };
+
+    ty.raw_type = resolved.raw_type;
+
+    if let Some(mut constraints) = resolved.constraints {
+        constraints.extend(ty.constraints.take().into_iter().flatten());
+        ty.constraints = Some(constraints);
+    }
+
+    Ok(())
+}
+
+/// Finds the type of the field `fields` names, following object fields
+/// through to the class of their object. `None` if the class isn't defined in
+/// this module.
+fn class_field_type(
+    classes: &SymbolTable<ObjectClass>,
+    class: &DefinedObjectClass,
+    fields: &[Field],
+) -> Result<Option<Type>> {
+    let (last, path) = match fields.split_last() {
+        Some(split) => split,
+        None => bail!("{:?} doesn't name a field.", class),
+    };
+
+    let mut definition = match class_definition(classes, class)? {
+        Some(definition) => definition,
+        None => return Ok(None),
+    };
+
+    for field in path {
+        let class = match find_field(&definition, field)? {
+            FieldSpec::ObjectField(_, class, _) | FieldSpec::ObjectSet(_, class, _) => class.clone(),
+            _ => bail!("&{} isn't an object field.", field.name()),
+        };
+
+        definition = match class_definition(classes, &class)? {
+            Some(definition) => definition,
+            None => return Ok(None),
+        };
+    }
+
+    match find_field(&definition, last)? {
+        FieldSpec::FixedTypeValue(_, ty, ..) | FieldSpec::FixedValueSet(_, ty, _) => {
+            Ok(Some(ty.clone()))
+        }
+        FieldSpec::Type(..) | FieldSpec::VariableTypeValue(..) => {
+            Ok(Some(RawType::from(BuiltinType::OpenType).into()))
+        }
+        _ => bail!("&{} is an object field, so doesn't name a type.", last.name()),
+    }
+}
+
+/// Follows `class` through any class aliases to its definition. `TYPE-IDENTIFIER`
+/// and `ABSTRACT-SYNTAX` are predefined by X.681.
+fn class_definition<'a>(
+    classes: &'a SymbolTable<ObjectClass>,
+    class: &DefinedObjectClass,
+) -> Result<Option<Cow<'a, ClassDefinition>>> {
+    let mut class = class;
+
+    // Each alias can only be followed once without forming a cycle.
+    for _ in 0..=classes.len() {
+        let reference = match class {
+            DefinedObjectClass::TypeIdentifier => {
+                return Ok(Some(Cow::Owned(ClassDefinition::type_identifier())));
+            }
+            DefinedObjectClass::AbstractSyntax => {
+                return Ok(Some(Cow::Owned(ClassDefinition::abstract_syntax())));
+            }
+            DefinedObjectClass::Reference(reference) => reference,
+        };
+
+        if !reference.is_internal() {
+            debug!("Skipping external class {}", reference);
+            return Ok(None);
+        }
+
+        class = match classes.get(&reference.item) {
+            Some(ObjectClass::Def(definition)) => return Ok(Some(Cow::Borrowed(definition))),
+            Some(ObjectClass::Defined(defined)) => defined,
+            Some(ObjectClass::Parameterized(..)) => {
+                bail!("Parameterized classes are not currently supported.")
+            }
+            None => {
+                debug!("Skipping imported class {}", reference);
+                return Ok(None);
+            }
+        };
+    }
+
+    bail!("{:?} is defined in terms of itself.", class)
+}
+
+fn find_field<'a>(definition: &'a ClassDefinition, field: &Field) -> Result<&'a FieldSpec> {
+    match definition.fields().iter().find(|spec| spec.name() == field.name()) {
+        Some(spec) => Ok(spec),
+        None => bail!("The class has no field &{}.", field.name()),
+    }
+}
+
+/// Finds the `WITH COMPONENTS` constraints that apply to every value, along
+/// with whether each is a full specification. Those inside a union only
+/// apply to some values, so can't change the generated type.
+fn component_specs(
+    constraints: &[Constraint],
+) -> Vec<(&BTreeMap<String, ComponentConstraint>, bool)> {
+    constraints
+        .iter()
+        .filter_map(|constraint| match constraint {
+            Constraint::ElementSet(set, _) if set.len() == 1 => Some(&set[0]),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|element| match element {
+            Element::SubType(SubTypeElement::FullSpec(spec)) => Some((spec, true)),
+            Element::SubType(SubTypeElement::PartialSpec(spec)) => Some((spec, false)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// PRESENT components are made mandatory, and ABSENT components are removed.
+/// A full specification also makes any optional component it doesn't list
+/// absent.
+fn apply_component_spec(
+    components: &mut ComponentTypeList,
+    spec: &BTreeMap<String, ComponentConstraint>,
+    is_full: bool,
+) {
+    let list = match components.components {
+        Some(ref mut list) => list,
+        None => return,
+    };
+
+    list.retain(|component| {
+        let (ty, optional, default) = match component.as_type() {
+            Some(component) => component,
+            None => return true,
+        };
+
+        let presence = spec.get(ty.name.as_ref().unwrap()).and_then(|c| c.presence.as_ref());
+        let is_optional = *optional || default.is_some();
+
+        match presence {
+            Some(Presence::Absent) => false,
+            None => !(is_full && is_optional),
+            _ => true,
+        }
+    });
+
+    for component in list.iter_mut() {
+        if let ComponentType::Type { ty, optional, default } = component {
+            let presence = spec.get(ty.name.as_ref().unwrap()).and_then(|c| c.presence.as_ref());
+
+            if presence == Some(&Presence::Present) {
+                *optional = false;
+                *default = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effective_constraint(source: &str, name: &str) -> EffectiveConstraint {
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        checker.build().unwrap();
+
+        checker.table.types[name].effective_constraint.clone().unwrap()
+    }
+
+    #[test]
+    fn intersection_of_ranges() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                Small ::= INTEGER ((1..10) INTERSECTION (5..15))
+                Serial ::= INTEGER (1..10)(5..MAX, ...)
+            END";
+
+        let constraint = effective_constraint(source, "Small");
+        assert_eq!(Some(ValueSet::new(Some(5), Some(10))), constraint.value);
+        assert!(!constraint.extensible);
+
+        let constraint = effective_constraint(source, "Serial");
+        assert_eq!(Some(ValueSet::new(Some(5), Some(10))), constraint.value);
+        assert!(constraint.extensible);
+    }
+
+    #[test]
+    fn union_of_ranges() {
+        let constraint = effective_constraint(
+            "Test DEFINITIONS ::= BEGIN
+                Sparse ::= INTEGER (MIN..0 | 2..4 | 3<..<7 | 10<..MAX)
+            END",
+            "Sparse",
+        );
+
+        let expected = ValueSet::new(None, Some(0))
+            .union(&ValueSet::new(Some(2), Some(6)))
+            .union(&ValueSet::new(Some(11), None));
+
+        assert_eq!(Some(expected), constraint.value);
+    }
+
+    #[test]
+    fn serial_size_constraints() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                Name ::= OCTET STRING (SIZE (1..64))(SIZE (8..MAX), ...)
+            END";
+        let constraint = effective_constraint(source, "Name");
+
+        assert_eq!(Some(ValueSet::new(Some(8), Some(64))), constraint.size);
+        assert_eq!(None, constraint.value);
+        assert!(constraint.extensible);
+    }
+
+    #[test]
+    fn defined_size_bounds() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                maxLen INTEGER ::= 255
+                minLen INTEGER ::= one
+                one INTEGER ::= 1
+                Name ::= OCTET STRING (SIZE(1..maxLen))
+                Names ::= SEQUENCE (SIZE(minLen..maxLen)) OF Name
+                Record ::= SEQUENCE { name UTF8String (SIZE(minLen..maxLen)) }
+            END";
+
+        let bounds = Some(ValueSet::new(Some(1), Some(255)));
+        assert_eq!(bounds, effective_constraint(source, "Name").size);
+        assert_eq!(bounds, effective_constraint(source, "Names").size);
+
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        checker.build().unwrap();
+
+        let components = match checker.table.types["Record"].raw_type {
+            RawType::Builtin(BuiltinType::Sequence(ref components)) => components,
+            _ => unreachable!(),
+        };
+        let (name, _, _) = components.components.as_ref().unwrap()[0].as_type().unwrap();
+        assert_eq!(bounds, name.effective_constraint.clone().unwrap().size);
+    }
+
+    #[test]
+    fn self_referencing_bounds() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                a INTEGER ::= b
+                b INTEGER ::= a
+                T ::= INTEGER (0..a)
+            END";
+
+        assert_eq!(None, effective_constraint(source, "T").value);
+    }
+
+    #[test]
+    fn self_referencing_constraints() {
+        let sources = [
+            "S INTEGER ::= { 1 | S }\n A ::= INTEGER (S)",
+            "A ::= INTEGER (A | 1)",
+            "A ::= INTEGER (B)\n B ::= INTEGER (A | 1)",
+        ];
+
+        for assignments in sources.iter() {
+            let source = format!("Test DEFINITIONS ::= BEGIN\n{}\nEND", assignments);
+            let mut checker = SemanticChecker::new(Parser::parse_all(&source).unwrap().remove(0));
+            let error = checker.build().unwrap_err().to_string();
+
+            assert!(error.ends_with("is constrained in terms of itself."), "{}", error);
+        }
+    }
+
+    fn definition_errors(source: &str) -> Vec<DefinitionError> {
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        let error = checker.build().unwrap_err();
+
+        error.downcast::<DefinitionErrors>().unwrap().0
+    }
+
+    #[test]
+    fn duplicate_definition() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                Foo ::= INTEGER
+                Foo ::= BOOLEAN
+            END";
+
+        assert_eq!(vec![DefinitionError::Duplicate(String::from("Foo"))], definition_errors(source));
+    }
+
+    #[test]
+    fn undefined_reference() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                IMPORTS Imported FROM Other;
+                Foo ::= SEQUENCE {
+                    bar Bar,
+                    baz SET OF [0] Baz,
+                    imported Imported
+                }
+                Param{Type} ::= SEQUENCE { value Type }
+                Baz ::= Param{BOOLEAN}
+                defaultValue INTEGER ::= undefinedValue
+            END";
+
+        let undefined = |name: &str, referenced_by: &str| DefinitionError::Undefined {
+            name: String::from(name),
+            referenced_by: String::from(referenced_by),
+        };
+
+        assert_eq!(
+            vec![undefined("Bar", "Foo"), undefined("undefinedValue", "defaultValue")],
+            definition_errors(source)
+        );
+    }
+
+    #[test]
+    fn imports_between_modules() {
+        let source = "Common DEFINITIONS ::= BEGIN
+                EXPORTS Version;
+                Version ::= INTEGER
+                Internal ::= BOOLEAN
+            END
+            Test DEFINITIONS ::= BEGIN
+                IMPORTS Version, Internal, Missing FROM Common
+                    Other FROM Elsewhere;
+                Foo ::= SEQUENCE { version Version, internal Internal }
+            END";
+
+        let modules = Parser::parse_all(source).unwrap();
+        assert_eq!(2, modules.len());
+
+        let error = check_modules(&modules).unwrap_err();
+        let not_exported = |name: &str| DefinitionError::NotExported {
+            name: String::from(name),
+            module: String::from("Common"),
+        };
+
+        assert_eq!(
+            vec![not_exported("Internal"), not_exported("Missing")],
+            error.downcast::<DefinitionErrors>().unwrap().0
+        );
+    }
+
+    #[test]
+    fn duplicate_names_between_modules() {
+        let source = "Common DEFINITIONS ::= BEGIN
+                Version ::= INTEGER
+            END
+            Test DEFINITIONS ::= BEGIN
+                Version ::= BOOLEAN
+            END";
+
+        let error = check_modules(&Parser::parse_all(source).unwrap()).unwrap_err();
+        let defined_in_both = DefinitionError::DefinedInBoth {
+            name: String::from("Version"),
+            modules: (String::from("Common"), String::from("Test")),
+        };
+
+        assert_eq!(vec![defined_in_both], error.downcast::<DefinitionErrors>().unwrap().0);
+    }
+
+    #[test]
+    fn object_class_fields() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                ALGORITHM ::= CLASS { &id OBJECT IDENTIFIER UNIQUE, &Type OPTIONAL }
+                AlgorithmIdentifier ::= SEQUENCE {
+                    algorithm ALGORITHM.&id,
+                    parameters ALGORITHM.&Type OPTIONAL
+                }
+                HashId ::= ALGORITHM.&id (SIZE (1..4))
+                Syntax ::= TYPE-IDENTIFIER.&Type
+            END";
+
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        checker.build().unwrap();
+
+        let components = match checker.table.types["AlgorithmIdentifier"].raw_type {
+            RawType::Builtin(BuiltinType::Sequence(ref components)) => components,
+            _ => unreachable!(),
+        };
+        let components = components.components.as_ref().unwrap();
+
+        let field_type = |index: usize| &components[index].as_type().unwrap().0.raw_type;
+        assert_eq!(&RawType::Builtin(BuiltinType::ObjectIdentifier), field_type(0));
+        assert_eq!(&RawType::Builtin(BuiltinType::OpenType), field_type(1));
+
+        let hash_id = &checker.table.types["HashId"];
+        assert_eq!(RawType::Builtin(BuiltinType::ObjectIdentifier), hash_id.raw_type);
+        assert!(hash_id.constraints.is_some());
+
+        assert_eq!(
+            RawType::Builtin(BuiltinType::OpenType),
+            checker.table.types["Syntax"].raw_type
+        );
+    }
+
+    #[test]
+    fn unknown_object_class_field() {
+        let source = "Test DEFINITIONS ::= BEGIN
+                ALGORITHM ::= CLASS { &id OBJECT IDENTIFIER UNIQUE }
+                Parameters ::= ALGORITHM.&Type
+            END";
+
+        let mut checker = SemanticChecker::new(Parser::parse_all(source).unwrap().remove(0));
+        assert!(checker.build().is_err());
+    }
+}
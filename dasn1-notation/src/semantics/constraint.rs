@@ -0,0 +1,342 @@
+use std::{cell::RefCell, cmp};
+
+use failure::ensure;
+
+use crate::{parser::*, registry::GlobalSymbolTable, Result};
+
+/// A range of integers, where both bounds are inclusive. A bound of `None`
+/// means the range is unbounded in that direction (`MIN` or `MAX`).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct ValueRange {
+    pub lower: Option<i64>,
+    pub upper: Option<i64>,
+}
+
+impl ValueRange {
+    fn is_empty(&self) -> bool {
+        match (self.lower, self.upper) {
+            (Some(lower), Some(upper)) => lower > upper,
+            _ => false,
+        }
+    }
+
+    /// Whether `self` overlaps or directly adjoins `next`, where `next`
+    /// doesn't start before `self`.
+    fn touches(&self, next: &ValueRange) -> bool {
+        match (self.upper, next.lower) {
+            (None, _) | (_, None) => true,
+            (Some(upper), Some(lower)) => upper.checked_add(1).is_none_or(|end| lower <= end),
+        }
+    }
+}
+
+/// A normalised set of permitted integers, stored as sorted, disjoint, and
+/// non-adjacent ranges.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct ValueSet(Vec<ValueRange>);
+
+impl ValueSet {
+    pub fn new(lower: Option<i64>, upper: Option<i64>) -> Self {
+        Self::from_ranges(vec![ValueRange { lower, upper }])
+    }
+
+    pub fn single(value: i64) -> Self {
+        Self::new(Some(value), Some(value))
+    }
+
+    fn from_ranges(mut ranges: Vec<ValueRange>) -> Self {
+        ranges.retain(|range| !range.is_empty());
+        // `None` sorts first, which is what we want for a `MIN` lower bound.
+        ranges.sort();
+
+        let mut merged: Vec<ValueRange> = Vec::with_capacity(ranges.len());
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if last.touches(&range) => {
+                    last.upper = match (last.upper, range.upper) {
+                        (Some(a), Some(b)) => Some(cmp::max(a, b)),
+                        _ => None,
+                    };
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        ValueSet(merged)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.0.iter().chain(&other.0).cloned().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+
+        for a in &self.0 {
+            for b in &other.0 {
+                let lower = match (a.lower, b.lower) {
+                    (Some(a), Some(b)) => Some(cmp::max(a, b)),
+                    (bound, None) | (None, bound) => bound,
+                };
+
+                let upper = match (a.upper, b.upper) {
+                    (Some(a), Some(b)) => Some(cmp::min(a, b)),
+                    (bound, None) | (None, bound) => bound,
+                };
+
+                ranges.push(ValueRange { lower, upper });
+            }
+        }
+
+        Self::from_ranges(ranges)
+    }
+}
+
+/// The result of evaluating all of a type's constraints. A `None` set means
+/// that aspect of the type isn't constrained.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct EffectiveConstraint {
+    pub value: Option<ValueSet>,
+    pub size: Option<ValueSet>,
+    pub extensible: bool,
+}
+
+impl EffectiveConstraint {
+    fn intersection(self, other: Self) -> Self {
+        let intersect = |a: Option<ValueSet>, b: Option<ValueSet>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.intersection(&b)),
+            (set, None) | (None, set) => set,
+        };
+
+        Self {
+            value: intersect(self.value, other.value),
+            size: intersect(self.size, other.size),
+            extensible: self.extensible || other.extensible,
+        }
+    }
+
+    /// As the value and size are tracked separately, a union where only one
+    /// side constrains an aspect leaves that aspect unconstrained.
+    fn union(self, other: Self) -> Self {
+        let unite = |a: Option<ValueSet>, b: Option<ValueSet>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.union(&b)),
+            _ => None,
+        };
+
+        Self {
+            value: unite(self.value, other.value),
+            size: unite(self.size, other.size),
+            extensible: self.extensible || other.extensible,
+        }
+    }
+}
+
+/// Folds constraints into their `EffectiveConstraint`, looking up any
+/// referenced values, value sets, and types in `table`. Elements which can't
+/// be evaluated (such as inner type or table constraints) are treated as
+/// unconstrained.
+pub struct ConstraintEvaluator<'a> {
+    table: &'a GlobalSymbolTable,
+    /// The value sets and types whose constraints are being evaluated, and
+    /// the values being looked up, so that one defined in terms of itself
+    /// isn't followed forever.
+    evaluating: RefCell<Vec<String>>,
+}
+
+impl<'a> ConstraintEvaluator<'a> {
+    pub fn new(table: &'a GlobalSymbolTable) -> Self {
+        Self { table, evaluating: RefCell::default() }
+    }
+
+    /// Sets the effective constraint of `ty` and of any types nested inside it.
+    pub fn resolve(&self, ty: &mut Type) -> Result<()> {
+        ty.effective_constraint = match ty.constraints {
+            Some(ref constraints) if !constraints.is_empty() => Some(self.evaluate(constraints)?),
+            _ => None,
+        };
+
+        match ty.raw_type {
+            RawType::Builtin(BuiltinType::Prefixed(_, ref mut ty))
+            | RawType::Builtin(BuiltinType::SequenceOf(ref mut ty))
+            | RawType::Builtin(BuiltinType::SetOf(ref mut ty)) => self.resolve(ty)?,
+            RawType::Builtin(BuiltinType::Sequence(ref mut components))
+            | RawType::Builtin(BuiltinType::Set(Set::Concrete(ref mut components))) => {
+                for component in components.components.iter_mut().flatten() {
+                    if let ComponentType::Type { ref mut ty, .. } = component {
+                        self.resolve(ty)?;
+                    }
+                }
+            }
+            RawType::Builtin(BuiltinType::Choice(ref mut choice)) => {
+                for alternative in &mut choice.alternatives {
+                    self.resolve(alternative)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Constraints that are applied one after another are equivalent to
+    /// their intersection, though only the last one decides whether the
+    /// result is extensible.
+    pub fn evaluate(&self, constraints: &[Constraint]) -> Result<EffectiveConstraint> {
+        let mut effective = EffectiveConstraint::default();
+
+        for constraint in constraints {
+            effective = match constraint {
+                Constraint::ElementSet(set, extensible) => EffectiveConstraint {
+                    extensible: *extensible,
+                    ..effective.intersection(self.evaluate_set(set)?)
+                },
+                Constraint::General(_) => effective,
+            };
+        }
+
+        Ok(effective)
+    }
+
+    fn evaluate_set(&self, set: &[Vec<Element>]) -> Result<EffectiveConstraint> {
+        let mut union: Option<EffectiveConstraint> = None;
+
+        for intersections in set {
+            let mut constraint = EffectiveConstraint::default();
+
+            for element in intersections {
+                constraint = constraint.intersection(self.evaluate_element(element)?);
+            }
+
+            union = Some(match union {
+                Some(union) => union.union(constraint),
+                None => constraint,
+            });
+        }
+
+        Ok(union.unwrap_or_default())
+    }
+
+    fn evaluate_element(&self, element: &Element) -> Result<EffectiveConstraint> {
+        let subtype = match element {
+            Element::ElementSet(set) => return self.evaluate_set(set),
+            Element::SubType(subtype) => subtype,
+            Element::Object(_) | Element::ObjectSet(_) => return Ok(EffectiveConstraint::default()),
+        };
+
+        Ok(match subtype {
+            SubTypeElement::Value(value) => EffectiveConstraint {
+                value: self.integer(value).map(ValueSet::single),
+                ..EffectiveConstraint::default()
+            },
+            SubTypeElement::Range(lower, upper) => EffectiveConstraint {
+                value: self.range(lower, upper),
+                ..EffectiveConstraint::default()
+            },
+            SubTypeElement::Size(constraint) => EffectiveConstraint {
+                size: self.evaluate(std::slice::from_ref(constraint))?.value,
+                ..EffectiveConstraint::default()
+            },
+            SubTypeElement::Type(ty) => self.evaluate_type(ty)?,
+            SubTypeElement::Constraint(_)
+            | SubTypeElement::FullSpec(_)
+            | SubTypeElement::PartialSpec(_) => EffectiveConstraint::default(),
+        })
+    }
+
+    /// A contained subtype, which is either a reference to a value set or
+    /// a type whose own constraints apply.
+    fn evaluate_type(&self, ty: &Type) -> Result<EffectiveConstraint> {
+        if let RawType::Referenced(ref reference) = ty.raw_type {
+            if reference.is_internal() {
+                if let Some((_, spec)) = self.table.value_sets.get(&reference.item) {
+                    let set = self.following(&reference.item, || self.evaluate_set(&spec.set))?;
+
+                    return Ok(EffectiveConstraint {
+                        extensible: spec.extensible != Extensible::No,
+                        ..set
+                    });
+                }
+
+                if let Some(ty) = self.table.types.get(&reference.item) {
+                    return self.following(&reference.item, || self.evaluate_type(ty));
+                }
+            }
+        }
+
+        match ty.constraints {
+            Some(ref constraints) => self.evaluate(constraints),
+            None => Ok(EffectiveConstraint::default()),
+        }
+    }
+
+    /// Evaluates the constraints of the value set or type assigned to
+    /// `name`, failing if they're already being evaluated.
+    fn following(
+        &self,
+        name: &str,
+        evaluate: impl FnOnce() -> Result<EffectiveConstraint>,
+    ) -> Result<EffectiveConstraint> {
+        ensure!(
+            !self.evaluating.borrow().iter().any(|evaluating| evaluating == name),
+            "{} is constrained in terms of itself.",
+            name
+        );
+
+        self.evaluating.borrow_mut().push(name.to_owned());
+        let constraint = evaluate();
+        self.evaluating.borrow_mut().pop();
+
+        constraint
+    }
+
+    /// Converts the endpoints into an inclusive range. `MIN` as an upper
+    /// endpoint or `MAX` as a lower one can't be evaluated.
+    fn range(&self, lower: &RangeValue, upper: &RangeValue) -> Option<ValueSet> {
+        let lower = match lower {
+            RangeValue::Min(_) => None,
+            RangeValue::Max(_) => return None,
+            RangeValue::Value(value, exclusive) => {
+                match self.integer(value)?.checked_add(*exclusive as i64) {
+                    Some(value) => Some(value),
+                    None => return Some(ValueSet::default()),
+                }
+            }
+        };
+
+        let upper = match upper {
+            RangeValue::Min(_) => return None,
+            RangeValue::Max(_) => None,
+            RangeValue::Value(value, exclusive) => {
+                match self.integer(value)?.checked_sub(*exclusive as i64) {
+                    Some(value) => Some(value),
+                    None => return Some(ValueSet::default()),
+                }
+            }
+        };
+
+        Some(ValueSet::new(lower, upper))
+    }
+
+    fn integer(&self, value: &Value) -> Option<i64> {
+        match value {
+            Value::Integer(IntegerValue::Literal(integer)) => Some(*integer),
+            Value::Defined(DefinedValue::Simple(reference)) if reference.is_internal() => {
+                let name = &reference.item;
+
+                // A value defined in terms of itself has no value to use.
+                if self.evaluating.borrow().iter().any(|evaluating| evaluating == name) {
+                    return None;
+                }
+
+                let (_, value) = self.table.values.get(name)?;
+                self.evaluating.borrow_mut().push(name.to_owned());
+                let integer = self.integer(value);
+                self.evaluating.borrow_mut().pop();
+
+                integer
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Generates the Rust code for each module in `tests/compile`, and checks
+//! that it compiles against the `dasn1` crates.
+
+use std::{env, fs, path::Path};
+
+use dasn1_notation::NotationCompiler;
+
+/// The imports the generated code expects its surrounding module to provide.
+const PRELUDE: &str = "#![allow(dead_code, unused_imports)]
+extern crate dasn1 as asn1;
+
+use dasn1::{AsnType, Enumerable};
+use serde::{Deserialize, Serialize};
+";
+
+#[test]
+fn generated_code_compiles() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("compile");
+    fs::create_dir_all(&output_dir).unwrap();
+
+    let cases = trybuild::TestCases::new();
+    let mut schemas: Vec<_> = fs::read_dir(manifest_dir.join("tests/compile"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    schemas.sort();
+
+    for schema in schemas {
+        for serde_names in [false, true] {
+            let code = NotationCompiler::new(&schema).serde_names(serde_names).build().unwrap();
+            let name = schema.file_stem().unwrap().to_string_lossy();
+            let path = output_dir.join(format!("{}{}.rs", name, if serde_names { "_serde" } else { "" }));
+
+            fs::write(&path, format!("{}\n{}\nfn main() {{}}\n", PRELUDE, code)).unwrap();
+            cases.pass(path);
+        }
+    }
+}
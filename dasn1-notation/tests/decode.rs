@@ -0,0 +1,32 @@
+//! Runs the `decode` subcommand against `decode.asn1`, checking the value
+//! printed for `decode.der`.
+
+use std::process::Command;
+
+const EXPECTED: &str = r#"value Message ::= {
+    version v2,
+    id unknown,
+    name "hello",
+    algorithm { 1 2 3 4 },
+    flags '101'B,
+    body data : '0102'H,
+    extra 'FF'H,
+    parameters NULL,
+    tags {
+        1,
+        2
+    }
+}
+"#;
+
+#[test]
+fn decode_subcommand() {
+    let output = Command::new(env!("CARGO_BIN_EXE_dasn1-notation"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["decode", "tests/decode.asn1", "Message", "tests/decode.der"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(EXPECTED, String::from_utf8(output.stdout).unwrap());
+}
@@ -0,0 +1,3 @@
+pub mod reader;
+
+pub use self::reader::BitReader;
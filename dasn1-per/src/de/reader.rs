@@ -0,0 +1,68 @@
+/// A cursor over the bits of a PER encoding, the reading counterpart of
+/// `Buffer`. Bits are read most significant bit first, both within each byte
+/// and within each value read. Every read returns `None` without moving the
+/// cursor if there aren't enough bits left.
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    input: &'a [u8],
+    /// The index of the next bit to read, counted from the start of `input`.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// The number of bits read so far.
+    pub fn bit_position(&self) -> usize {
+        self.position
+    }
+
+    /// The number of bits left to read.
+    pub fn remaining_bits(&self) -> usize {
+        self.input.len() * 8 - self.position
+    }
+
+    pub fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.input.get(self.position / 8)?;
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+
+        Some(bit)
+    }
+
+    /// Reads `count` bits as an unsigned number, the inverse of
+    /// `Buffer::push_bits`.
+    pub fn read_bits(&mut self, count: u8) -> Option<u64> {
+        assert!(count <= 64, "Can't read more than 64 bits into a u64.");
+
+        if self.remaining_bits() < usize::from(count) {
+            return None;
+        }
+
+        let mut value = 0;
+
+        for _ in 0..count {
+            value = (value << 1) | u64::from(self.read_bit()?);
+        }
+
+        Some(value)
+    }
+
+    /// Skips the padding bits up to the next octet boundary, if the cursor
+    /// isn't already on one.
+    pub fn align(&mut self) {
+        self.position = self.position.div_ceil(8) * 8;
+    }
+
+    /// Reads `count` whole bytes after aligning the cursor, borrowed from the
+    /// input.
+    pub fn read_aligned_bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let start = self.position.div_ceil(8);
+        let bytes = self.input.get(start..start.checked_add(count)?)?;
+        self.position = (start + count) * 8;
+
+        Some(bytes)
+    }
+}
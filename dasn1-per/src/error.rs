@@ -0,0 +1,37 @@
+//! When encoding PER goes wrong.
+use std::{error, fmt};
+
+use num_traits::PrimInt;
+
+/// Alias for a `Result` with the error type `dasn1_per::Error`.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// This type represents all possible errors that can occur when encoding PER.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// An integer was outside the range of its constraint.
+    ValueNotInRange(u128),
+    /// A string or list had a number of items outside the range of its size
+    /// constraint.
+    SizeNotInRange(usize),
+    /// A character string had a character outside its permitted alphabet.
+    CharacterNotPermitted(char),
+}
+
+impl Error {
+    pub(crate) fn value_not_in_range<N: PrimInt>(value: N) -> Self {
+        Error::ValueNotInRange(value.to_u128().unwrap_or(u128::MAX))
+    }
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ValueNotInRange(value) => write!(f, "{} is outside the range of its constraint", value),
+            Error::SizeNotInRange(len) => write!(f, "A size of {} is outside the range of its constraint", len),
+            Error::CharacterNotPermitted(character) => write!(f, "{:?} is not in the permitted alphabet", character),
+        }
+    }
+}
@@ -1,44 +1,90 @@
-use std::ops::{Bound, RangeBounds};
+use std::{
+    ops::RangeBounds,
+    rc::Rc,
+    sync::Arc,
+};
 
+use core::types::{AnyValue, BitString, Ia5String, NumericString, OctetString, PrintableString};
+
+pub mod de;
+pub mod error;
 pub mod ser;
 
-pub use ser::Buffer;
+pub use de::BitReader;
+pub use error::{Error, Result};
+pub use ser::{Buffer, PermittedAlphabet};
 
-pub fn encode<T: PerEncodable>(value: &T) -> Vec<u8> {
-    value.encode().to_bytes()
+pub fn encode<T: PerEncodable>(value: &T) -> Result<Vec<u8>> {
+    Ok(value.encode()?.to_bytes())
 }
 
 pub trait PerEncodable {
-    fn encode(&self) -> Buffer;
+    fn encode(&self) -> Result<Buffer>;
 }
 
 impl PerEncodable for bool {
-    fn encode(&self) -> Buffer {
-        Buffer::from_elem(1, *self)
+    fn encode(&self) -> Result<Buffer> {
+        Ok(Buffer::from_elem(1, *self))
     }
 }
 
-impl<T: PerEncodable> PerEncodable for Option<T> {
-    fn encode(&self) -> Buffer {
-        // Encode if available else provide an empty buffer.
-        self.as_ref().map(T::encode).unwrap_or_default()
+/// Encodes an OPTIONAL component of a SEQUENCE with `encode`, or nothing if
+/// the component is absent.
+///
+/// PER signals whether an OPTIONAL component is present with a bit in the
+/// preamble of the enclosing SEQUENCE, so a lone `None` has no encoding of its
+/// own. For this reason `Option` doesn't implement `PerEncodable`; `Option`
+/// must only appear as a SEQUENCE field, where the enclosing type pushes
+/// `is_some()` into its preamble before encoding the field with this function.
+pub fn encode_optional<T, F: FnOnce(&T) -> Result<Buffer>>(value: &Option<T>, encode: F) -> Result<Buffer> {
+    value.as_ref().map_or_else(|| Ok(Buffer::new()), encode)
+}
+
+/// Pointers are encoded as the value they point to.
+macro_rules! pointers {
+    ($($pointer:ident)+) => {
+        $(
+            impl<T: PerEncodable + ?Sized> PerEncodable for $pointer<T> {
+                fn encode(&self) -> Result<Buffer> {
+                    (**self).encode()
+                }
+            }
+        )+
     }
 }
 
-impl<T: PerEncodable> PerEncodable for (T, T) {
-    fn encode(&self) -> Buffer {
-        let mut buffer = Buffer::new();
-        buffer.push_field_list(self.0.encode());
-        buffer.push_field_list(self.1.encode());
-        buffer
+pointers!(Box Rc Arc);
+
+/// Tuples are encoded like a SEQUENCE with a component for each element, in
+/// order, and without a preamble as none of the components are OPTIONAL.
+macro_rules! tuples {
+    ($(($($ty:ident . $index:tt),+))+) => {
+        $(
+            impl<$($ty: PerEncodable),+> PerEncodable for ($($ty,)+) {
+                fn encode(&self) -> Result<Buffer> {
+                    let mut buffer = Buffer::new();
+                    $(buffer.push_field_list(self.$index.encode()?);)+
+                    Ok(buffer)
+                }
+            }
+        )+
     }
 }
 
+tuples! {
+    (A.0)
+    (A.0, B.1)
+    (A.0, B.1, C.2)
+    (A.0, B.1, C.2, D.3)
+    (A.0, B.1, C.2, D.3, E.4)
+    (A.0, B.1, C.2, D.3, E.4, F.5)
+}
+
 macro_rules! integers {
     ($($int:ty)+) => {
         $(
             impl PerEncodable for $int {
-                fn encode(&self) -> Buffer {
+                fn encode(&self) -> Result<Buffer> {
                     let range = <$int>::min_value()..=<$int>::max_value();
                     self.encode_with_constraint(range)
                 }
@@ -46,7 +92,7 @@ macro_rules! integers {
 
             impl ConstrainedValue for $int {
                 type RangeBound = $int;
-                fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Buffer {
+                fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Result<Buffer> {
                     ser::number::encode_integer(*self, range)
                 }
             }
@@ -59,79 +105,138 @@ integers!(u8 u16 u32 u64 u128 usize);
 pub trait ConstrainedValue: PerEncodable {
     type RangeBound;
 
-    fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Buffer;
+    fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Result<Buffer>;
 }
 
-impl<T: ConstrainedValue> ConstrainedValue for Option<T> {
-    type RangeBound = T::RangeBound;
+impl<T: PerEncodable> PerEncodable for Vec<T> {
+    fn encode(&self) -> Result<Buffer> {
+        self.encode_with_constraint(0..)
+    }
+}
 
-    fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Buffer {
-        match self {
-            Some(val) => val.encode_with_constraint(range),
-            None => Buffer::new(),
-        }
+impl<T: PerEncodable> ConstrainedValue for Vec<T> {
+    type RangeBound = usize;
+
+    fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Result<Buffer> {
+        ser::number::encode_length(self.len(), range, |items| {
+            let mut buffer = Buffer::new();
+
+            for item in &self[items] {
+                buffer.push_field_list(item.encode()?);
+            }
+
+            Ok(buffer)
+        })
     }
 }
 
-impl<T: PerEncodable> PerEncodable for Vec<T> {
-    fn encode(&self) -> Buffer {
+/// Open types are encoded as their complete encoding, prefixed with an
+/// unconstrained length determinant. (X.691 11.2)
+impl PerEncodable for AnyValue {
+    fn encode(&self) -> Result<Buffer> {
+        let bytes = self.as_ref();
+
+        ser::number::encode_fragmented(bytes.len(), |items| Ok(bytes_buffer(&bytes[items])))
+    }
+}
+
+impl PerEncodable for OctetString {
+    fn encode(&self) -> Result<Buffer> {
         self.encode_with_constraint(0..)
     }
 }
 
-impl<T: PerEncodable> ConstrainedValue for Vec<T> {
+/// The octets follow the length determinant directly, as only the unaligned
+/// variant of PER is supported, which never pads to an octet boundary.
+/// (X.691 17)
+impl ConstrainedValue for OctetString {
     type RangeBound = usize;
 
-    fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Buffer {
-        let mut buffer = Buffer::new();
+    fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Result<Buffer> {
+        ser::number::encode_length(self.len(), range, |items| Ok(bytes_buffer(&self[items])))
+    }
+}
 
-        // Assert that we have the minimnum number of elements required to
-        // encode.
-        match range.start_bound() {
-            Bound::Included(&start) => assert!(self.len() >= start),
-            Bound::Excluded(&start) => assert!(self.len() > start),
-            // Unbounded is equivalvent to `0` in this case.
-            Bound::Unbounded => {}
-        }
+impl PerEncodable for BitString {
+    fn encode(&self) -> Result<Buffer> {
+        self.encode_with_constraint(0..)
+    }
+}
 
-        buffer.push_field_list(ser::number::encode_length(self.len(), range));
+/// The length determinant counts bits rather than octets, and is followed by
+/// the bits themselves. (X.691 16)
+impl ConstrainedValue for BitString {
+    type RangeBound = usize;
 
-        for item in self {
-            buffer.push_field_list(item.encode());
-        }
+    fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Result<Buffer> {
+        ser::number::encode_length(self.len(), range, |items| {
+            let mut buffer = Buffer::new();
 
-        buffer
+            for bit in self.iter().skip(items.start).take(items.len()) {
+                buffer.push(bit);
+            }
+
+            Ok(buffer)
+        })
     }
 }
 
-impl<T: PerEncodable> PerEncodable for [T; 0] {
-    fn encode(&self) -> Buffer {
-        Buffer::new()
-    }
+fn bytes_buffer(bytes: &[u8]) -> Buffer {
+    let mut buffer = Buffer::new();
+    buffer.push_bytes(bytes);
+    buffer
 }
 
-macro_rules! arrays {
-    ($($num:tt)+) => {
+/// A character string type whose characters are drawn from a fixed alphabet.
+pub trait RestrictedString: AsRef<str> {
+    /// The effective permitted alphabet when no constraint is applied.
+    fn permitted_alphabet() -> PermittedAlphabet;
 
+    /// Encodes the string with the length constrained by `size`, and the
+    /// characters constrained to `alphabet`.
+    fn encode_with_alphabet<R: RangeBounds<usize>>(&self, size: R, alphabet: &PermittedAlphabet) -> Result<Buffer> {
+        ser::string::encode_string(self.as_ref(), alphabet, size)
+    }
+}
+
+macro_rules! restricted_strings {
+    ($($string:ty)+) => {
         $(
-            impl<T: PerEncodable> PerEncodable for [T; $num] {
-                fn encode(&self) -> Buffer {
-                    let mut buffer = Buffer::new();
+            impl RestrictedString for $string {
+                fn permitted_alphabet() -> PermittedAlphabet {
+                    PermittedAlphabet::new((0u8..=0x7f).map(char::from).filter(|&c| <$string>::is_permitted(c)))
+                }
+            }
 
-                    for item in self {
-                        buffer.push_field_list(item.encode());
-                    }
+            impl PerEncodable for $string {
+                fn encode(&self) -> Result<Buffer> {
+                    self.encode_with_constraint(0..)
+                }
+            }
 
-                    buffer
+            impl ConstrainedValue for $string {
+                type RangeBound = usize;
+
+                fn encode_with_constraint<R: RangeBounds<Self::RangeBound>>(&self, range: R) -> Result<Buffer> {
+                    self.encode_with_alphabet(range, &Self::permitted_alphabet())
                 }
             }
         )+
     }
 }
 
-arrays! {
-    1 2 3 4 5 6 7 8 9 10
-    11 12 13 14 15 16 17 18 19 20
+restricted_strings!(Ia5String NumericString PrintableString);
+
+impl<T: PerEncodable, const N: usize> PerEncodable for [T; N] {
+    fn encode(&self) -> Result<Buffer> {
+        let mut buffer = Buffer::new();
+
+        for item in self {
+            buffer.push_field_list(item.encode()?);
+        }
+
+        Ok(buffer)
+    }
 }
 
 #[cfg(test)]
@@ -140,25 +245,25 @@ mod tests {
 
     #[test]
     fn integers_encode_to_correct_width() {
-        assert_eq!(8, 0u8.encode().len());
-        assert_eq!(16, 0u16.encode().len());
-        assert_eq!(32, 0u32.encode().len());
-        assert_eq!(64, 0u64.encode().len());
-        assert_eq!(128, 0u128.encode().len());
+        assert_eq!(8, 0u8.encode().unwrap().len());
+        assert_eq!(16, 0u16.encode().unwrap().len());
+        assert_eq!(32, 0u32.encode().unwrap().len());
+        assert_eq!(64, 0u64.encode().unwrap().len());
+        assert_eq!(128, 0u128.encode().unwrap().len());
     }
 
     #[test]
     fn fixed_width_arrays() {
         let array = [1u8, 2, 3, 4, 5, 6, 7];
 
-        assert_eq!(array.len(), array.encode().len() / 8)
+        assert_eq!(array.len(), array.encode().unwrap().len() / 8)
     }
 
     #[test]
     fn push_to_field_list() {
-        let mut a = 1u8.encode();
-        let mut b = 2u16.encode();
-        let mut c = dbg!(3u8.encode());
+        let mut a = 1u8.encode().unwrap();
+        let mut b = 2u16.encode().unwrap();
+        let mut c = dbg!(3u8.encode().unwrap());
 
         a.push_field_list(b);
         a.push_field_list(c);
@@ -168,4 +273,176 @@ mod tests {
 
         assert_eq!(&[01, 00, 02, 03][..], &*a.to_bytes());
     }
+
+    #[test]
+    fn heterogeneous_tuple() {
+        let buffer = (7u8, true, 0x0102u16).encode().unwrap();
+
+        assert_eq!(8 + 1 + 16, buffer.len());
+        assert_eq!(&[7, 0b1000_0000, 0b1000_0001, 0][..], &*buffer.to_bytes());
+    }
+
+    #[test]
+    fn push_bits_then_byte() {
+        let mut buffer = Buffer::new();
+        buffer.push_bits(0b101, 3);
+        buffer.push_bytes(&[0xff]);
+
+        assert_eq!(11, buffer.bit_len());
+        assert_eq!(&[0b1011_1111, 0b1110_0000][..], &*buffer.to_bytes());
+    }
+
+    #[test]
+    fn size_constrained_numeric_string() {
+        let string = NumericString::new("123").unwrap();
+        let buffer = string.encode_with_constraint(1..=4).unwrap();
+
+        // The length `3` is encoded as `3 - 1` in two bits (`10`), followed
+        // by the four bit index of each character in " 0123456789".
+        assert_eq!(14, buffer.len());
+        assert_eq!(&[0b1000_1000, 0b1101_0000][..], &*buffer.to_bytes());
+
+        let digits = PermittedAlphabet::new("0123456789".chars());
+        let buffer = string.encode_with_alphabet(3..=3, &digits).unwrap();
+
+        assert_eq!(12, buffer.len());
+        assert_eq!(&[0b0001_0010, 0b0011_0000][..], &*buffer.to_bytes());
+
+        let odd = PermittedAlphabet::new("13579".chars());
+        assert_eq!(Some(Error::CharacterNotPermitted('2')), string.encode_with_alphabet(3..=3, &odd).err());
+    }
+
+    #[test]
+    fn size_constrained_octet_string() {
+        let string = OctetString::from(vec![0xde, 0xad, 0xbe]);
+        let buffer = string.encode_with_constraint(1..=4).unwrap();
+
+        // The length `3` is encoded as `3 - 1` in two bits, followed
+        // directly by the octets.
+        assert_eq!(2 + 8 * 3, buffer.len());
+        assert_eq!(&[0b1011_0111, 0b1010_1011, 0b0110_1111, 0b1000_0000][..], &*buffer.to_bytes());
+
+        // A fixed size has no length determinant at all.
+        assert_eq!(&[0xde, 0xad, 0xbe][..], &*string.encode_with_constraint(3..=3).unwrap().to_bytes());
+    }
+
+    #[test]
+    fn unconstrained_octet_string() {
+        let string = OctetString::from(vec![0x01, 0x02]);
+
+        assert_eq!(&[2, 0x01, 0x02][..], &*string.encode().unwrap().to_bytes());
+        assert_eq!(&[0][..], &*OctetString::new().encode().unwrap().to_bytes());
+    }
+
+    #[test]
+    fn fragmented_octet_string() {
+        // One fragment of a single 16K block, followed by the remaining 3616
+        // octets with a two octet length.
+        let bytes = OctetString::from(vec![0u8; 20000]).encode().unwrap().to_bytes();
+        assert_eq!(1 + 16384 + 2 + 3616, bytes.len());
+        assert_eq!(0b1100_0001, bytes[0]);
+        assert_eq!(&[0x8e, 0x20][..], &bytes[1 + 16384..][..2]);
+
+        // A multiple of 16K is followed by an empty final fragment.
+        let bytes = OctetString::from(vec![0u8; 16384]).encode().unwrap().to_bytes();
+        assert_eq!(0b1100_0001, bytes[0]);
+        assert_eq!(&[0][..], &bytes[1 + 16384..]);
+
+        // Fragments hold at most four blocks.
+        let bytes = OctetString::from(vec![0u8; 70000]).encode().unwrap().to_bytes();
+        assert_eq!(0b1100_0100, bytes[0]);
+        assert_eq!(&[0x91, 0x70][..], &bytes[1 + 65536..][..2]);
+    }
+
+    #[test]
+    fn bit_string_packs_bits() {
+        let mut string = BitString::new();
+        string.push(true);
+        string.push(false);
+        string.push(true);
+
+        assert_eq!(8 + 3, string.encode().unwrap().len());
+        assert_eq!(&[3, 0b1010_0000][..], &*string.encode().unwrap().to_bytes());
+        assert_eq!(&[0b0111_0100][..], &*string.encode_with_constraint(0..=7).unwrap().to_bytes());
+    }
+
+    #[test]
+    fn ia5_string_encodes_character_values() {
+        let string = Ia5String::new("Hi").unwrap();
+        let buffer = string.encode().unwrap();
+
+        assert_eq!(8 + 7 * 2, buffer.len());
+        assert_eq!(&[2, b'H' << 1 | b'i' >> 6, b'i' << 2][..], &*buffer.to_bytes());
+    }
+
+    #[test]
+    fn semi_constrained_integer() {
+        // The offset from the lower bound in as few octets as possible,
+        // prefixed with the number of octets.
+        assert_eq!(&[1, 0][..], &*0u32.encode_with_constraint(0..).unwrap().to_bytes());
+        assert_eq!(&[1, 0xff][..], &*260u32.encode_with_constraint(5..).unwrap().to_bytes());
+        assert_eq!(&[2, 0x01, 0x00][..], &*256u32.encode_with_constraint(0..).unwrap().to_bytes());
+    }
+
+    #[test]
+    fn values_outside_their_constraint() {
+        assert_eq!(Some(Error::ValueNotInRange(4)), 4u32.encode_with_constraint(5..).err());
+        assert_eq!(Some(Error::ValueNotInRange(11)), 11u8.encode_with_constraint(0..=10).err());
+        assert_eq!(Some(Error::ValueNotInRange(11)), 11u8.encode_with_constraint(..=10).err());
+        assert_eq!(Some(Error::ValueNotInRange(10)), 10u8.encode_with_constraint(..10).err());
+        assert_eq!(Some(Error::SizeNotInRange(3)), vec![1u8, 2, 3].encode_with_constraint(4..).err());
+        assert_eq!(Some(Error::SizeNotInRange(3)), OctetString::from(vec![0; 3]).encode_with_constraint(1..=2).err());
+    }
+
+    #[test]
+    fn unconstrained_integer() {
+        // Two's-complement, so values with the top bit set need another octet.
+        assert_eq!(&[1, 0][..], &*0u32.encode_with_constraint(..).unwrap().to_bytes());
+        assert_eq!(&[1, 0x7f][..], &*127u32.encode_with_constraint(..).unwrap().to_bytes());
+        assert_eq!(&[2, 0x00, 0x80][..], &*128u32.encode_with_constraint(..).unwrap().to_bytes());
+
+        // An upper bound alone leaves the value unconstrained.
+        assert_eq!(&[1, 0x05][..], &*5u8.encode_with_constraint(..=10).unwrap().to_bytes());
+
+        let max = u128::MAX.encode_with_constraint(..).unwrap().to_bytes();
+        assert_eq!(&[17, 0][..], &max[..2]);
+        assert!(max[2..].iter().all(|&octet| octet == 0xff));
+    }
+
+    #[test]
+    fn bit_reader_reads_pushed_bits() {
+        let mut buffer = Buffer::new();
+        buffer.push(true);
+        buffer.push_bits(0b101, 3);
+        buffer.push_bits(0x1234, 13);
+        buffer.push_bits(0, 7);
+        buffer.push_bytes(&[0xAB, 0xCD]);
+        buffer.push_bits(1, 1);
+
+        let bytes = buffer.to_bytes();
+        let mut reader = BitReader::new(&bytes);
+
+        assert_eq!(Some(true), reader.read_bit());
+        assert_eq!(Some(0b101), reader.read_bits(3));
+        assert_eq!(Some(0x1234), reader.read_bits(13));
+        reader.align();
+        assert_eq!(24, reader.bit_position());
+        assert_eq!(Some(&[0xAB, 0xCD][..]), reader.read_aligned_bytes(2));
+        assert_eq!(Some(true), reader.read_bit());
+        assert_eq!(7, reader.remaining_bits());
+        assert_eq!(None, reader.read_bits(8));
+        assert_eq!(Some(0), reader.read_bits(7));
+        assert_eq!(None, reader.read_bit());
+    }
+
+    #[test]
+    fn bit_reader_aligns_before_bytes() {
+        let mut reader = BitReader::new(&[0b1000_0000, 0x7F, 0x80]);
+
+        assert_eq!(Some(true), reader.read_bit());
+        assert_eq!(Some(&[0x7F][..]), reader.read_aligned_bytes(1));
+        assert_eq!(None, reader.read_aligned_bytes(2));
+        assert_eq!(16, reader.bit_position());
+        assert_eq!(Some(1), reader.read_bits(1));
+    }
 }
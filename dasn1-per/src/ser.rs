@@ -1,7 +1,9 @@
 pub mod number;
 pub mod buffer;
+pub mod string;
 
 pub use self::buffer::Buffer;
+pub use self::string::PermittedAlphabet;
 
 pub trait PerEncodable {
     fn encode(&self) -> bit_vec::BitVec;
@@ -1,5 +1,8 @@
 use bit_vec::BitVec;
 
+/// A growable sequence of bits making up a PER encoding. Bits are always
+/// stored most significant bit first, both within each pushed value and when
+/// packed into bytes by `to_bytes`.
 #[derive(Debug, Default, Clone)]
 pub struct Buffer(BitVec);
 
@@ -12,9 +15,32 @@ impl Buffer {
         Self(BitVec::from_elem(n, default))
     }
 
+    /// Pushes the lowest `count` bits of `value`, starting with the most
+    /// significant of those bits.
+    pub fn push_bits(&mut self, value: u64, count: u8) {
+        assert!(count <= 64, "Can't push more than 64 bits from a u64.");
+
+        for shift in (0..count).rev() {
+            self.0.push((value >> shift) & 1 == 1);
+        }
+    }
+
+    /// Pushes each byte in `bytes` as eight bits. No padding is inserted, so
+    /// the bytes are only octet aligned if the buffer already was.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.push_bits(u64::from(byte), 8);
+        }
+    }
+
+    /// The number of bits currently in the buffer.
+    pub fn bit_len(&self) -> usize {
+        self.0.len()
+    }
+
     pub fn push_field_list(&mut self, target: Self) {
         for bit in target.into_inner() {
-            self.0.push(bit);
+            self.push_bits(u64::from(bit), 1);
         }
     }
 
@@ -3,104 +3,195 @@ use std::ops::{self, Bound};
 use num_traits::{PrimInt, Unsigned};
 
 use super::Buffer;
+use crate::{Error, Result};
 
-pub fn encode_integer<N, R>(n: N, range: R) -> Buffer
+pub fn encode_integer<N, R>(n: N, range: R) -> Result<Buffer>
     where N: PrimInt + ops::BitAnd<Output=N> + Copy + ops::ShrAssign<u32> + Unsigned,
           R: ops::RangeBounds<N>
 {
-    match (range.start_bound(), range.end_bound()) {
-        (Bound::Included(&start), Bound::Included(&end)) => {
-            encode_constrained_whole_number(n, start..=end)
-        }
-        (Bound::Excluded(&start), Bound::Included(&end)) => {
-            encode_constrained_whole_number(n, start+N::one()..=end)
-        }
-        (Bound::Included(&start), Bound::Excluded(&end)) => {
-            encode_constrained_whole_number(n, start..=end-N::one())
-        }
-        (Bound::Included(&start), Bound::Unbounded) => {
-            encode_semi_constrained_whole_number(n, start)
-        }
-        (Bound::Excluded(&start), Bound::Unbounded) => {
-            encode_semi_constrained_whole_number(n, start + N::one())
-        }
-        _ => unimplemented!()
+    let lower = match range.start_bound() {
+        Bound::Included(&start) => Some(start),
+        Bound::Excluded(&start) => Some(start + N::one()),
+        Bound::Unbounded => None,
+    };
+
+    let upper = match range.end_bound() {
+        Bound::Included(&end) => Some(end),
+        Bound::Excluded(&end) => Some(end - N::one()),
+        Bound::Unbounded => None,
+    };
+
+    // Without a lower bound, an upper bound doesn't change the encoding, but
+    // the value still has to be within it.
+    match (lower, upper) {
+        (Some(start), Some(end)) => encode_constrained_whole_number(n, start..=end),
+        (Some(start), None) => encode_semi_constrained_whole_number(n, start),
+        (None, Some(end)) if n > end => Err(Error::value_not_in_range(n)),
+        (None, _) => Ok(encode_unconstrained_whole_number(n)),
     }
 }
 
-pub fn encode_length<R: ops::RangeBounds<usize>>(len: usize, range: R) -> Buffer {
+/// Encodes a length determinant of `len` constrained by `range`, calling
+/// `items` with the range of items that follows each length.
+pub fn encode_length<R, F>(len: usize, range: R, items: F) -> Result<Buffer>
+    where R: ops::RangeBounds<usize>,
+          F: FnMut(ops::Range<usize>) -> Result<Buffer>,
+{
+    if !range.contains(&len) {
+        return Err(Error::SizeNotInRange(len));
+    }
+
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+
     match range.end_bound() {
-        Bound::Unbounded => {
-            encode_unconstrained_length(len)
-        }
-        _ => unimplemented!(),
+        Bound::Included(&end) => encode_constrained_length(len, start..=end, items),
+        Bound::Excluded(&end) => encode_constrained_length(len, start..=end - 1, items),
+        Bound::Unbounded => encode_fragmented(len, items),
+    }
+}
+
+pub fn encode_constrained_length<F>(len: usize, range: ops::RangeInclusive<usize>, mut items: F) -> Result<Buffer>
+    where F: FnMut(ops::Range<usize>) -> Result<Buffer>,
+{
+    if !range.contains(&len) {
+        return Err(Error::SizeNotInRange(len));
     }
 
+    // A fixed size has no length determinant, and upper bounds below 64K are
+    // encoded as a constrained whole number. (X.691 11.9.3.3 & 11.9.4.1)
+    if range.start() == range.end() {
+        items(0..len)
+    } else if *range.end() < 65536 {
+        let mut buffer = encode_constrained_whole_number(len, range)?;
+        buffer.push_field_list(items(0..len)?);
+        Ok(buffer)
+    } else {
+        encode_fragmented(len, items)
+    }
 }
 
-pub fn encode_unconstrained_length(len: usize) -> Buffer
+/// Encodes `len` items with an unconstrained length determinant. Lengths of
+/// 16K and above are split into fragments of up to 64K items, each prefixed
+/// with the number of 16K blocks it holds, and followed by the length of the
+/// remaining items, which may be zero. (X.691 11.9.3.8)
+pub fn encode_fragmented<F>(len: usize, mut items: F) -> Result<Buffer>
+    where F: FnMut(ops::Range<usize>) -> Result<Buffer>,
 {
+    let mut buffer = Buffer::new();
+    let mut start = 0;
+
+    while len - start >= FRAGMENT_BLOCK {
+        let blocks = ((len - start) / FRAGMENT_BLOCK).min(4);
+        let end = start + blocks * FRAGMENT_BLOCK;
+
+        buffer.push_bits(0b1100_0000 | blocks as u64, 8);
+        buffer.push_field_list(items(start..end)?);
+        start = end;
+    }
+
+    buffer.push_field_list(encode_unconstrained_length(len - start));
+    buffer.push_field_list(items(start..len)?);
+    Ok(buffer)
+}
+
+/// The number of items in each block of a fragment.
+const FRAGMENT_BLOCK: usize = 16384;
+
+/// Encodes a length below 16K in one octet, or in two octets with the top
+/// bit set. Longer lengths must be fragmented with `encode_fragmented`.
+/// (X.691 11.9.3.6 & 11.9.3.7)
+fn encode_unconstrained_length(len: usize) -> Buffer {
     match len {
         0..=127 => {
             encode_non_negative_binary_integer(len, 8)
         }
-        128..=15999 => {
+        128..=16383 => {
             let mut buffer = encode_non_negative_binary_integer(len, 16);
             buffer.set(0, true);
 
             buffer
         }
-        _ => unimplemented!(),
+        _ => unreachable!("lengths of 16K and above are fragmented"),
     }
 }
 
 pub fn encode_constrained_whole_number<N>(n: N, range: ops::RangeInclusive<N>)
-    -> Buffer
+    -> Result<Buffer>
     where N: PrimInt + ops::BitAnd<Output=N> + Copy + ops::ShrAssign<u32> + Unsigned,
 {
-    assert!(range.contains(&n));
+    if !range.contains(&n) {
+        return Err(Error::value_not_in_range(n));
+    }
+
     // We only encode the difference between the lower bound and the value
     // we're enocding.
     let bits = n - *range.start();
 
     let max_difference = bit_width(*range.end() - *range.start());
-    encode_non_negative_binary_integer(bits, max_difference)
+    Ok(encode_non_negative_binary_integer(bits, max_difference))
 }
 
 pub(crate) fn _encode_normally_small_whole_number<N>(n: N)
-    -> Buffer
+    -> Result<Buffer>
     where N: PrimInt + ops::BitAnd<Output=N> + Copy + ops::ShrAssign<u32> + Unsigned
 {
     let mut buffer = Buffer::from_elem(1, false);
     let boundary = N::from(63).unwrap();
     if n <= boundary {
-        buffer.push_field_list(encode_constrained_whole_number(n, N::zero()..=boundary));
-        buffer
+        buffer.push_field_list(encode_constrained_whole_number(n, N::zero()..=boundary)?);
+        Ok(buffer)
     } else {
         unimplemented!()
     }
 }
 
-pub(crate) fn encode_semi_constrained_whole_number<N>(n: N, lb: N) -> Buffer
+/// The offset from the lower bound in the fewest octets that hold it,
+/// prefixed with the number of octets. (X.691 11.7 & 11.9)
+pub(crate) fn encode_semi_constrained_whole_number<N>(n: N, lb: N) -> Result<Buffer>
     where N: PrimInt + ops::BitAnd<Output=N> + Copy + ops::ShrAssign<u32> + Unsigned
 {
-    encode_non_negative_binary_integer(n - lb, bit_width(n - lb))
+    if n < lb {
+        return Err(Error::value_not_in_range(n));
+    }
+
+    let octets = bit_width(n - lb).div_ceil(8).max(1);
+
+    let mut buffer = encode_unconstrained_length(octets);
+    buffer.push_field_list(encode_non_negative_binary_integer(n - lb, octets * 8));
+    Ok(buffer)
 }
 
-fn encode_non_negative_binary_integer<N>(mut n: N, width: usize)
-    -> Buffer
+/// The value as a two's-complement binary integer in the fewest octets that
+/// hold it, prefixed with the number of octets. As the value is unsigned, the
+/// octets always leave room for a clear sign bit. (X.691 11.8 & 11.9)
+pub(crate) fn encode_unconstrained_whole_number<N>(n: N) -> Buffer
     where N: PrimInt + ops::BitAnd<Output=N> + Copy + ops::ShrAssign<u32> + Unsigned
 {
-    // calculate the mininum number of bits required to encode the number.
+    let octets = bit_width(n) / 8 + 1;
 
-    let mut buffer = Buffer::from_elem(width, false);
+    let mut buffer = encode_unconstrained_length(octets);
+    buffer.push_field_list(encode_non_negative_binary_integer(n, octets * 8));
+    buffer
+}
 
-    // We always encode the number in big endian format.
-    let mut index = buffer.len();
-    while n != N::zero() {
-        index -= 1;
-        buffer.set(index, (n & N::one()) == N::one());
-        n >>= 1;
+fn encode_non_negative_binary_integer<N>(n: N, width: usize)
+    -> Buffer
+    where N: PrimInt + ops::BitAnd<Output=N> + Copy + ops::ShrAssign<u32> + Unsigned
+{
+    let mut buffer = Buffer::new();
+
+    // We always encode the number in big endian format, in chunks of at most
+    // 64 bits so that `u128`s fit through `push_bits`.
+    let mut remaining = width;
+    while remaining > 0 {
+        let count = remaining.min(64);
+        remaining -= count;
+        let chunk = (n >> remaining).to_u128().unwrap() as u64;
+        buffer.push_bits(chunk, count as u8);
     }
 
     buffer
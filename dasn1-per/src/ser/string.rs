@@ -0,0 +1,67 @@
+//! Encoding restricted character strings, each character packed into the
+//! fewest bits its permitted alphabet allows. (X.691 27)
+
+use std::ops::RangeBounds;
+
+use super::{number, Buffer};
+use crate::{Error, Result};
+
+/// The set of characters permitted in a restricted character string, kept in
+/// the canonical order used to index characters when encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PermittedAlphabet(Vec<char>);
+
+impl PermittedAlphabet {
+    /// Instantiates a new alphabet of `characters`, in any order and with
+    /// any duplicates removed.
+    pub fn new<I: IntoIterator<Item = char>>(characters: I) -> Self {
+        let mut characters: Vec<char> = characters.into_iter().collect();
+        characters.sort();
+        characters.dedup();
+
+        Self(characters)
+    }
+
+    /// The number of bits used to encode each character, the smallest width
+    /// that can index every character in the alphabet. (X.691 27.5.2)
+    pub fn bit_width(&self) -> usize {
+        let max_index = self.0.len().saturating_sub(1);
+        (0usize.count_zeros() - max_index.leading_zeros()) as usize
+    }
+
+    fn encode_char(&self, character: char) -> Result<Buffer> {
+        let width = self.bit_width();
+        let max = (1u32 << width) - 1;
+
+        let index = self
+            .0
+            .binary_search(&character)
+            .map_err(|_| Error::CharacterNotPermitted(character))?;
+
+        // Characters are encoded by their value when the alphabet's largest
+        // value fits in `width` bits, and by their index otherwise.
+        // (X.691 27.5.4)
+        let value = match self.0.last() {
+            Some(&last) if last as u32 <= max => character as u32,
+            _ => index as u32,
+        };
+
+        number::encode_constrained_whole_number(value, 0..=max)
+    }
+}
+
+/// Encodes `string` with a length determinant constrained by `size`, followed
+/// by each character packed according to `alphabet`.
+pub fn encode_string<R: RangeBounds<usize>>(string: &str, alphabet: &PermittedAlphabet, size: R) -> Result<Buffer> {
+    let len = string.chars().count();
+
+    number::encode_length(len, size, |items| {
+        let mut buffer = Buffer::new();
+
+        for character in string.chars().skip(items.start).take(items.len()) {
+            buffer.push_field_list(alphabet.encode_char(character)?);
+        }
+
+        Ok(buffer)
+    })
+}
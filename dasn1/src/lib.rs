@@ -33,11 +33,16 @@
 //! **Note:** The notation compiler is still a work in progress, and its use
 //! is **not currently recommended.**
 //!
+//! The [`prelude`] gathers the types, tag markers, and functions most
+//! definitions need behind a single import.
+//!
 //! [`asn1_core`]: ./core/
 //! [`asn1_der`]: ./der/
 //! [`asn1_notation`]: ./notation/
 //! [`serde::{Deserialize, Serialize}`]: docs.serde.rs/serde
 
+pub mod prelude;
+
 pub use core::{self, *};
 
 #[cfg(feature = "der")] pub use der;
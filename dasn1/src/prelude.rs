@@ -0,0 +1,23 @@
+//! Re-exports of the items used by most ASN.1 definitions, so that they
+//! can be brought into scope with `use dasn1::prelude::*;`.
+//!
+//! ```
+//! use dasn1::prelude::*;
+//!
+//! type Version = Implicit<Context, U0, Integer>;
+//! ```
+
+pub use core::{
+    identifier::constant::{Application, Context, Private, Universal},
+    types::{
+        AsnString, BitString, Enumerable, Enumerated, Explicit, Implicit, Integer, Null, ObjectIdentifier,
+        OctetString, SetOf,
+    },
+};
+pub use typenum::consts::{
+    U0, U1, U2, U3, U4, U5, U6, U7, U8, U9, U10, U11, U12, U13, U14, U15, U16, U17, U18, U19,
+    U20, U21, U22, U23, U24, U25, U26, U27, U28, U29, U30, U31, U32,
+};
+
+#[cfg(feature = "der")]
+pub use der::{from_slice, to_vec};
@@ -0,0 +1,24 @@
+use dasn1::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Certificate {
+    version: Explicit<Context, U0, u8>,
+    serial_number: Integer,
+    algorithm: ObjectIdentifier,
+    subject_key: BitString,
+    key_id: Implicit<Context, U1, OctetString>,
+}
+
+#[test]
+fn prelude_is_enough() {
+    let certificate = Certificate {
+        version: Explicit::new(2),
+        serial_number: Integer::from(1234),
+        algorithm: ObjectIdentifier::new(vec![1, 2, 840, 113549, 1, 1, 11]).unwrap(),
+        subject_key: BitString::from_bytes(&[0xA5]),
+        key_id: Implicit::new(OctetString::from(vec![1, 2, 3])),
+    };
+
+    assert_eq!(certificate, from_slice(&to_vec(&certificate).unwrap()).unwrap());
+}